@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: 0BSD
+//! Typed access to the visual parts of atoms: appearance snapshots, overlays,
+//! `vis_contents`, and filters.
+//!
+//! Every change to an atom's appearance creates a new appearance in BYOND, so
+//! changing several things at once is cheaper than changing them one by one.
+//! [`Overlays`] and [`VisContents`] add and remove many values with a single
+//! proc call, [`Filters`] adds many filters at once, and
+//! [`Atom::set_overlays`] and friends replace a whole list with a single
+//! write.
+//!
+//! Creating filters requires a helper proc to be defined in your DM code, see
+//! [`Filter::create`].
+//!
+//! ```no_run
+//! use meowtonin::{
+//!     Atom, ByondResult, ByondValue,
+//!     appearance::{DropShadow, Filter, Outline},
+//! };
+//!
+//! #[meowtonin::byond_fn]
+//! pub fn highlight(mut atom: Atom, overlays: Vec<ByondValue>) -> ByondResult<()> {
+//!     atom.overlays()?.extend(overlays)?;
+//!     atom.set_filters([
+//!         Filter::from(Outline {
+//!             size: 2.0,
+//!             color: Some("#ffff00".to_owned()),
+//!             ..Default::default()
+//!         }),
+//!         Filter::from(DropShadow::default()),
+//!     ])
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! [`Overlays`]: crate::handle::Overlays
+//! [`VisContents`]: crate::handle::VisContents
+//! [`Filters`]: crate::handle::Filters
+//! [`Atom::set_overlays`]: crate::handle::Atom::set_overlays
+mod filter;
+mod snapshot;
+
+pub use self::{
+	filter::{
+		Alpha, AngularBlur, Bloom, Blur, Color, Displace, DropShadow, FILTER_COLOR_HCY,
+		FILTER_COLOR_HSL, FILTER_COLOR_HSV, FILTER_COLOR_RGB, FILTER_OVERLAY, FILTER_PROC,
+		FILTER_UNDERLAY, Filter, Layer, MASK_INVERSE, MASK_SWAP, MotionBlur, OUTLINE_SHARP,
+		OUTLINE_SQUARE, Outline, RadialBlur, Rays, Ripple, WAVE_BOUNDED, WAVE_SIDEWAYS, Wave,
+	},
+	snapshot::Appearance,
+};
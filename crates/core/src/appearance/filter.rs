@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondResult, ByondValue, ToByond, call_global};
+
+/// The name of the global proc used to create filters, see
+/// [`Filter::create`].
+pub const FILTER_PROC: &str = "meowtonin_filter";
+
+/// `FILTER_OVERLAY`, for [`Layer::flags`] and [`Rays::flags`].
+pub const FILTER_OVERLAY: u32 = 1;
+/// `FILTER_UNDERLAY`, for [`Layer::flags`] and [`Rays::flags`].
+pub const FILTER_UNDERLAY: u32 = 2;
+/// `MASK_INVERSE`, for [`Alpha::flags`].
+pub const MASK_INVERSE: u32 = 1;
+/// `MASK_SWAP`, for [`Alpha::flags`].
+pub const MASK_SWAP: u32 = 2;
+/// `OUTLINE_SHARP`, for [`Outline::flags`].
+pub const OUTLINE_SHARP: u32 = 1;
+/// `OUTLINE_SQUARE`, for [`Outline::flags`].
+pub const OUTLINE_SQUARE: u32 = 2;
+/// `WAVE_SIDEWAYS`, for [`Wave::flags`] and [`Ripple::flags`].
+pub const WAVE_SIDEWAYS: u32 = 1;
+/// `WAVE_BOUNDED`, for [`Wave::flags`] and [`Ripple::flags`].
+pub const WAVE_BOUNDED: u32 = 2;
+/// `FILTER_COLOR_RGB`, for [`Color::space`].
+pub const FILTER_COLOR_RGB: u32 = 0;
+/// `FILTER_COLOR_HSV`, for [`Color::space`].
+pub const FILTER_COLOR_HSV: u32 = 1;
+/// `FILTER_COLOR_HSL`, for [`Color::space`].
+pub const FILTER_COLOR_HSL: u32 = 2;
+/// `FILTER_COLOR_HCY`, for [`Color::space`].
+pub const FILTER_COLOR_HCY: u32 = 3;
+
+/// Defines a struct for each filter type, with its parameters as fields,
+/// along with the [`Filter`] enum containing all of them.
+///
+/// Parameters that convert to `null`, such as `None`, are left out, so BYOND
+/// uses its own default.
+macro_rules! define_filters {
+	($(
+		$(#[$meta:meta])*
+		$name:ident($kind:literal) {
+			$($(#[$field_meta:meta])* $field:ident: $ty:ty = $default:expr),* $(,)?
+		}
+	)*) => {
+		$(
+			$(#[$meta])*
+			#[derive(Debug, Clone, PartialEq)]
+			pub struct $name {
+				$($(#[$field_meta])* pub $field: $ty,)*
+			}
+
+			impl Default for $name {
+				fn default() -> Self {
+					Self {
+						$($field: $default,)*
+					}
+				}
+			}
+
+			impl $name {
+				fn write_params(&self, params: &mut ByondValue) -> ByondResult<()> {
+					$(write_param(params, stringify!($field), &self.$field)?;)*
+					Ok(())
+				}
+			}
+
+			impl From<$name> for Filter {
+				#[inline]
+				fn from(filter: $name) -> Self {
+					Self::$name(filter)
+				}
+			}
+		)*
+
+		/// A filter, which can be added to an atom's `filters`.
+		///
+		/// Converts to a list of named parameters, the same as what would be
+		/// passed to `filter()` in DM, i.e `list(type = "blur", size = 2)`.
+		/// Use [`create`](Filter::create) to get an actual filter.
+		#[derive(Debug, Clone, PartialEq)]
+		pub enum Filter {
+			$($(#[$meta])* $name($name),)*
+		}
+
+		impl Filter {
+			/// Returns the filter's `type` parameter, such as `"drop_shadow"`.
+			pub fn kind(&self) -> &'static str {
+				match self {
+					$(Self::$name(_) => $kind,)*
+				}
+			}
+
+			/// Creates the filter, equivalent to `filter(type = ..., ...)` in DM.
+			///
+			/// `filter()` is a built-in instruction rather than a proc, so it
+			/// can't be called through byondapi directly. This requires the
+			/// [`FILTER_PROC`] proc to be defined somewhere in your DM code:
+			///
+			/// ```dm
+			/// /proc/meowtonin_filter(list/params)
+			///     return filter(arglist(params))
+			/// ```
+			pub fn create(&self) -> ByondResult<ByondValue> {
+				call_global(FILTER_PROC, [self.to_byond()?])
+			}
+
+			fn write_params(&self, params: &mut ByondValue) -> ByondResult<()> {
+				match self {
+					$(Self::$name(filter) => filter.write_params(params),)*
+				}
+			}
+		}
+	};
+}
+
+define_filters! {
+	/// Masks the atom by the alpha of an icon or render source.
+	Alpha("alpha") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		icon: Option<ByondValue> = None,
+		render_source: Option<String> = None,
+		flags: u32 = 0,
+	}
+
+	/// Blurs the atom in circles around a point.
+	AngularBlur("angular_blur") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: f32 = 1.0,
+	}
+
+	/// Makes bright parts of the atom glow.
+	Bloom("bloom") {
+		/// The color that's bright enough to glow.
+		threshold: Option<String> = None,
+		size: Option<f32> = None,
+		offset: Option<f32> = None,
+		alpha: Option<f32> = None,
+	}
+
+	/// A gaussian blur.
+	Blur("blur") {
+		size: f32 = 1.0,
+	}
+
+	/// Applies a color matrix.
+	Color("color") {
+		/// The color matrix, in any form DM accepts.
+		color: Option<Vec<f32>> = None,
+		/// The color space to apply the matrix in, such as
+		/// [`FILTER_COLOR_HSV`].
+		space: u32 = FILTER_COLOR_RGB,
+	}
+
+	/// Distorts the atom using the red and green of an icon or render source.
+	Displace("displace") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: Option<f32> = None,
+		icon: Option<ByondValue> = None,
+		render_source: Option<String> = None,
+	}
+
+	/// A blurred shadow underneath the atom.
+	DropShadow("drop_shadow") {
+		x: f32 = 1.0,
+		y: f32 = -1.0,
+		size: f32 = 1.0,
+		offset: f32 = 0.0,
+		color: Option<String> = None,
+	}
+
+	/// Draws an icon or render source over or under the atom.
+	Layer("layer") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		icon: Option<ByondValue> = None,
+		render_source: Option<String> = None,
+		flags: u32 = FILTER_OVERLAY,
+		color: Option<String> = None,
+		blend_mode: u32 = 0,
+	}
+
+	/// Blurs the atom in a direction.
+	MotionBlur("motion_blur") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+	}
+
+	/// An outline around the atom.
+	Outline("outline") {
+		size: f32 = 1.0,
+		color: Option<String> = None,
+		flags: u32 = 0,
+	}
+
+	/// Blurs the atom outwards from a point.
+	RadialBlur("radial_blur") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: f32 = 0.01,
+	}
+
+	/// Rays of light, shining out from a point.
+	Rays("rays") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: Option<f32> = None,
+		color: Option<String> = None,
+		offset: f32 = 0.0,
+		density: Option<f32> = None,
+		threshold: Option<f32> = None,
+		factor: f32 = 0.0,
+		flags: Option<u32> = None,
+	}
+
+	/// Ripples spreading out from a point.
+	Ripple("ripple") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: Option<f32> = None,
+		repeat: Option<f32> = None,
+		radius: f32 = 0.0,
+		falloff: Option<f32> = None,
+		flags: u32 = 0,
+	}
+
+	/// A wave distortion.
+	Wave("wave") {
+		x: f32 = 0.0,
+		y: f32 = 0.0,
+		size: Option<f32> = None,
+		offset: f32 = 0.0,
+		flags: u32 = 0,
+	}
+}
+
+impl ToByond for Filter {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		let mut params = ByondValue::new_list()?;
+		params.write_list_index("type", self.kind())?;
+		self.write_params(&mut params)?;
+		Ok(params)
+	}
+}
+
+impl ToByond for &Filter {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		(*self).to_byond()
+	}
+}
+
+fn write_param<Value>(params: &mut ByondValue, name: &str, value: &Value) -> ByondResult<()>
+where
+	Value: ToByond,
+{
+	let value = value.to_byond()?;
+	if value.is_null() {
+		return Ok(());
+	}
+	params.write_list_index(name, value)
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondDir, ByondMatrix, ByondResult, ByondValue, FromByond};
+
+/// A snapshot of the visual vars of an atom, image, or appearance.
+///
+/// Each var is read separately, so this is a copy of the values at the time
+/// it was read, rather than a single read from BYOND.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance {
+	pub name: String,
+	/// The icon, usually a resource such as `'icons/obj/food.dmi'`.
+	pub icon: ByondValue,
+	pub icon_state: String,
+	pub dir: ByondDir,
+	/// The color, either as a string such as `"#ff0000"`, or as a color
+	/// matrix list.
+	pub color: ByondValue,
+	pub alpha: u8,
+	pub transform: ByondMatrix,
+	pub layer: f32,
+	pub plane: f32,
+	pub pixel_x: f32,
+	pub pixel_y: f32,
+	pub pixel_w: f32,
+	pub pixel_z: f32,
+	pub appearance_flags: u32,
+	pub blend_mode: u32,
+	pub invisibility: u32,
+	pub mouse_opacity: u32,
+	pub maptext: Option<String>,
+	pub render_source: Option<String>,
+	pub render_target: Option<String>,
+	/// The appearances of each overlay.
+	pub overlays: Vec<ByondValue>,
+	/// The appearances of each underlay.
+	pub underlays: Vec<ByondValue>,
+}
+
+impl FromByond for Appearance {
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let number = |name: &str| -> ByondResult<f32> {
+			Ok(value.read_var::<_, Option<f32>>(name)?.unwrap_or_default())
+		};
+		Ok(Self {
+			name: value
+				.read_var::<_, Option<String>>("name")?
+				.unwrap_or_default(),
+			icon: value.read_var("icon")?,
+			icon_state: value
+				.read_var::<_, Option<String>>("icon_state")?
+				.unwrap_or_default(),
+			dir: value.read_var("dir")?,
+			color: value.read_var("color")?,
+			alpha: number("alpha")?.clamp(0.0, 255.0) as u8,
+			transform: value
+				.read_var::<_, Option<ByondMatrix>>("transform")?
+				.unwrap_or_default(),
+			layer: number("layer")?,
+			plane: number("plane")?,
+			pixel_x: number("pixel_x")?,
+			pixel_y: number("pixel_y")?,
+			pixel_w: number("pixel_w")?,
+			pixel_z: number("pixel_z")?,
+			appearance_flags: number("appearance_flags")? as u32,
+			blend_mode: number("blend_mode")? as u32,
+			invisibility: number("invisibility")? as u32,
+			mouse_opacity: number("mouse_opacity")? as u32,
+			maptext: value.read_var("maptext")?,
+			render_source: value.read_var("render_source")?,
+			render_target: value.read_var("render_target")?,
+			overlays: value.read_var("overlays")?,
+			underlays: value.read_var("underlays")?,
+		})
+	}
+}
@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondResult, ByondValue, ByondXYZ, misc};
+use std::iter::FusedIterator;
+
+/// A rectangular region of tiles, between two corners (inclusive).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ByondBlock {
+	min: ByondXYZ,
+	max: ByondXYZ,
+}
+
+impl ByondBlock {
+	/// Creates a block between any two opposite corners.
+	pub fn new(a: ByondXYZ, b: ByondXYZ) -> Self {
+		Self {
+			min: ByondXYZ::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z())),
+			max: ByondXYZ::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z())),
+		}
+	}
+
+	/// Creates a single-z block of the tiles within `radius` tiles of
+	/// `center`, like `range(radius, center)` in DM.
+	pub fn around(center: ByondXYZ, radius: u16) -> Self {
+		let radius = radius.min(i16::MAX as u16) as i16;
+		let offset = ByondXYZ::new(radius, radius, 0);
+		Self::new(center - offset, center + offset)
+	}
+
+	/// Returns a block covering the entire map, from `1,1,1` to
+	/// `world.maxx,world.maxy,world.maxz`.
+	///
+	/// Returns `None` if the map has no z-levels.
+	pub fn world() -> ByondResult<Option<Self>> {
+		let world = ByondValue::world();
+		let max = ByondXYZ::new(
+			world.read_var("maxx")?,
+			world.read_var("maxy")?,
+			world.read_var("maxz")?,
+		);
+		if max.x() < 1 || max.y() < 1 || max.z() < 1 {
+			return Ok(None);
+		}
+		Ok(Some(Self::new(ByondXYZ::new(1, 1, 1), max)))
+	}
+
+	/// Returns the bottom-left corner of the block, with the lowest Z.
+	#[inline]
+	pub const fn min(&self) -> ByondXYZ {
+		self.min
+	}
+
+	/// Returns the top-right corner of the block, with the highest Z.
+	#[inline]
+	pub const fn max(&self) -> ByondXYZ {
+		self.max
+	}
+
+	/// Returns the width (X size) of the block.
+	#[inline]
+	pub const fn width(&self) -> usize {
+		(self.max.x() as i32 - self.min.x() as i32 + 1) as usize
+	}
+
+	/// Returns the height (Y size) of the block.
+	#[inline]
+	pub const fn height(&self) -> usize {
+		(self.max.y() as i32 - self.min.y() as i32 + 1) as usize
+	}
+
+	/// Returns the depth (Z size) of the block.
+	#[inline]
+	pub const fn depth(&self) -> usize {
+		(self.max.z() as i32 - self.min.z() as i32 + 1) as usize
+	}
+
+	/// Returns the number of tiles in the block.
+	#[inline]
+	pub const fn len(&self) -> usize {
+		self.width() * self.height() * self.depth()
+	}
+
+	/// Blocks always contain at least one tile, so this is always false.
+	#[inline]
+	pub const fn is_empty(&self) -> bool {
+		false
+	}
+
+	/// Returns if the given coordinates are within the block.
+	pub fn contains(&self, xyz: &ByondXYZ) -> bool {
+		(self.min.x()..=self.max.x()).contains(&xyz.x())
+			&& (self.min.y()..=self.max.y()).contains(&xyz.y())
+			&& (self.min.z()..=self.max.z()).contains(&xyz.z())
+	}
+
+	/// Returns if the other block is entirely within this one.
+	pub fn contains_block(&self, other: &Self) -> bool {
+		self.contains(&other.min) && self.contains(&other.max)
+	}
+
+	/// Returns the region where two blocks overlap, if they do.
+	pub fn intersect(&self, other: &Self) -> Option<Self> {
+		let min = ByondXYZ::new(
+			self.min.x().max(other.min.x()),
+			self.min.y().max(other.min.y()),
+			self.min.z().max(other.min.z()),
+		);
+		let max = ByondXYZ::new(
+			self.max.x().min(other.max.x()),
+			self.max.y().min(other.max.y()),
+			self.max.z().min(other.max.z()),
+		);
+		(min.x() <= max.x() && min.y() <= max.y() && min.z() <= max.z())
+			.then_some(Self { min, max })
+	}
+
+	/// Returns the smallest block containing both blocks.
+	pub fn union(&self, other: &Self) -> Self {
+		Self::new(
+			ByondXYZ::new(
+				self.min.x().min(other.min.x()),
+				self.min.y().min(other.min.y()),
+				self.min.z().min(other.min.z()),
+			),
+			ByondXYZ::new(
+				self.max.x().max(other.max.x()),
+				self.max.y().max(other.max.y()),
+				self.max.z().max(other.max.z()),
+			),
+		)
+	}
+
+	/// Grows the block horizontally by the given number of tiles in every
+	/// direction. Negative amounts shrink it, but never below a single tile.
+	pub fn expand(&self, amount: i16) -> Self {
+		let axis = |min: i16, max: i16| {
+			let (lo, hi) = (min.saturating_sub(amount), max.saturating_add(amount));
+			if lo > hi {
+				let center = ((min as i32 + max as i32) / 2) as i16;
+				(center, center)
+			} else {
+				(lo, hi)
+			}
+		};
+		let (min_x, max_x) = axis(self.min.x(), self.max.x());
+		let (min_y, max_y) = axis(self.min.y(), self.max.y());
+		Self {
+			min: ByondXYZ::new(min_x, min_y, self.min.z()),
+			max: ByondXYZ::new(max_x, max_y, self.max.z()),
+		}
+	}
+
+	/// Returns the index of the given coordinates in row-major order (the
+	/// same order as [`iter()`](Self::iter) and `block()`), or `None` if
+	/// they're outside of the block.
+	pub fn index_of(&self, xyz: &ByondXYZ) -> Option<usize> {
+		if !self.contains(xyz) {
+			return None;
+		}
+		let x = (xyz.x() - self.min.x()) as usize;
+		let y = (xyz.y() - self.min.y()) as usize;
+		let z = (xyz.z() - self.min.z()) as usize;
+		Some((z * self.height() + y) * self.width() + x)
+	}
+
+	/// Returns the coordinates at the given row-major index, or `None` if
+	/// it's out of bounds.
+	pub fn xyz_at(&self, idx: usize) -> Option<ByondXYZ> {
+		if idx >= self.len() {
+			return None;
+		}
+		let (width, height) = (self.width(), self.height());
+		let x = idx % width;
+		let y = (idx / width) % height;
+		let z = idx / (width * height);
+		Some(ByondXYZ::new(
+			self.min.x() + x as i16,
+			self.min.y() + y as i16,
+			self.min.z() + z as i16,
+		))
+	}
+
+	/// Iterates over every tile in the block, in row-major order: X first,
+	/// then Y, then Z.
+	///
+	/// This is the same order `block()` returns turfs in.
+	pub fn iter(&self) -> BlockIter {
+		BlockIter {
+			block: *self,
+			front: 0,
+			back: self.len(),
+		}
+	}
+
+	/// Returns all turfs in the block, in row-major order.
+	///
+	/// Equivalent to `block(min, max)` in DM.
+	pub fn turfs(&self) -> ByondResult<Vec<ByondValue>> {
+		misc::block(self.min, self.max)
+	}
+}
+
+impl IntoIterator for ByondBlock {
+	type Item = ByondXYZ;
+	type IntoIter = BlockIter;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl IntoIterator for &ByondBlock {
+	type Item = ByondXYZ;
+	type IntoIter = BlockIter;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl From<(ByondXYZ, ByondXYZ)> for ByondBlock {
+	fn from((a, b): (ByondXYZ, ByondXYZ)) -> Self {
+		Self::new(a, b)
+	}
+}
+
+/// A row-major iterator over the coordinates of a [`ByondBlock`].
+#[derive(Debug, Clone)]
+pub struct BlockIter {
+	block: ByondBlock,
+	front: usize,
+	back: usize,
+}
+
+impl Iterator for BlockIter {
+	type Item = ByondXYZ;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.front >= self.back {
+			return None;
+		}
+		let xyz = self.block.xyz_at(self.front);
+		self.front += 1;
+		xyz
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.back - self.front;
+		(len, Some(len))
+	}
+
+	fn nth(&mut self, n: usize) -> Option<Self::Item> {
+		self.front = self.front.saturating_add(n).min(self.back);
+		self.next()
+	}
+}
+
+impl DoubleEndedIterator for BlockIter {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.front >= self.back {
+			return None;
+		}
+		self.back -= 1;
+		self.block.xyz_at(self.back)
+	}
+}
+
+impl ExactSizeIterator for BlockIter {}
+
+impl FusedIterator for BlockIter {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dimensions() {
+		let block = ByondBlock::new(ByondXYZ::new(5, 1, 2), ByondXYZ::new(1, 3, 1));
+		assert_eq!(block.min(), ByondXYZ::new(1, 1, 1));
+		assert_eq!(block.max(), ByondXYZ::new(5, 3, 2));
+		assert_eq!((block.width(), block.height(), block.depth()), (5, 3, 2));
+		assert_eq!(block.len(), 30);
+		assert!(block.contains(&ByondXYZ::new(5, 3, 2)));
+		assert!(!block.contains(&ByondXYZ::new(5, 3, 3)));
+	}
+
+	#[test]
+	fn iteration_order() {
+		let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(2, 2, 2));
+		let coords = block
+			.iter()
+			.map(<(i16, i16, i16)>::from)
+			.collect::<Vec<_>>();
+		assert_eq!(coords, [
+			(1, 1, 1),
+			(2, 1, 1),
+			(1, 2, 1),
+			(2, 2, 1),
+			(1, 1, 2),
+			(2, 1, 2),
+			(1, 2, 2),
+			(2, 2, 2),
+		]);
+		for (idx, xyz) in block.iter().enumerate() {
+			assert_eq!(block.index_of(&xyz), Some(idx));
+		}
+		assert_eq!(block.iter().next_back(), Some(ByondXYZ::new(2, 2, 2)));
+		assert_eq!(block.iter().len(), 8);
+	}
+
+	#[test]
+	fn intersect_and_expand() {
+		let a = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(5, 5, 1));
+		let b = ByondBlock::new(ByondXYZ::new(4, 4, 1), ByondXYZ::new(8, 8, 1));
+		let c = ByondBlock::new(ByondXYZ::new(6, 6, 1), ByondXYZ::new(8, 8, 1));
+		assert_eq!(
+			a.intersect(&b),
+			Some(ByondBlock::new(
+				ByondXYZ::new(4, 4, 1),
+				ByondXYZ::new(5, 5, 1)
+			))
+		);
+		assert_eq!(a.intersect(&c), None);
+		assert_eq!(
+			a.expand(1),
+			ByondBlock::new(ByondXYZ::new(0, 0, 1), ByondXYZ::new(6, 6, 1))
+		);
+		assert_eq!(
+			a.expand(-1),
+			ByondBlock::new(ByondXYZ::new(2, 2, 1), ByondXYZ::new(4, 4, 1))
+		);
+		assert_eq!(a.expand(-10).len(), 1);
+		assert_eq!(
+			ByondBlock::around(ByondXYZ::new(5, 5, 1), 2),
+			ByondBlock::new(ByondXYZ::new(3, 3, 1), ByondXYZ::new(7, 7, 1))
+		);
+	}
+}
@@ -8,13 +8,16 @@
 fn init_lib() -> ByondApi {
 	// Clear string ID cache, just in case anything's changed.
 	crate::strid::STRID_CACHE.pin().clear();
+	crate::typepath::TYPESOF_CACHE.pin().clear();
+	crate::typepath::TYPESOF_MISSING.store(false, std::sync::atomic::Ordering::Relaxed);
 
 	// Run any custom initialization functions.
 	for func in inventory::iter::<InitFunc> {
 		func.0();
 	}
-	// Dynamically load the byondcore dylib.
-	let library = {
+	// Dynamically load the byondcore dylib. It's opened once for the required
+	// functions and once for the optional ones, as each keeps its own handle.
+	let open = || {
 		cfg_if::cfg_if! {
 			if #[cfg(windows)] {
 				libloading::os::windows::Library::open_already_loaded("byondcore.dll")
@@ -25,7 +28,7 @@ fn init_lib() -> ByondApi {
 		}
 	};
 	// Initialize ByondApi with the loaded dylib.
-	unsafe { meowtonin_byondapi_sys::ByondApi::init_from_library(library) }
+	unsafe { meowtonin_byondapi_sys::ByondApi::init_from_library(open(), open()) }
 		.expect("failed to initialize byondapi")
 }
 
@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: 0BSD
+//! Colors, color matrices and gradients, matching how DM represents them.
+//!
+//! ```no_run
+//! use meowtonin::{ByondResult, color::{ByondColor, ColorMatrix, Gradient}};
+//!
+//! #[meowtonin::byond_fn]
+//! pub fn damage_color(gradient: Gradient, health: f32) -> Option<ByondColor> {
+//!     gradient.at(health / 100.0)
+//! }
+//!
+//! #[meowtonin::byond_fn]
+//! pub fn faded(color: ColorMatrix, amount: f32) -> ColorMatrix {
+//!     color.then(&ColorMatrix::saturation(1.0 - amount))
+//! }
+//! # fn main() {}
+//! ```
+mod gradient;
+mod matrix;
+
+pub use self::{
+	gradient::{ColorSpace, Gradient},
+	matrix::ColorMatrix,
+};
+
+use crate::{ByondError, ByondResult, ByondValue, FromByond, ToByond};
+use std::{borrow::Cow, fmt};
+
+/// An 8-bit RGBA color.
+///
+/// Converts to and from DM color strings, such as `"#ff8000"`, with the
+/// canonical form being lowercase `"#rrggbb"`, or `"#rrggbbaa"` if it isn't
+/// fully opaque - the same as `rgb()` returns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ByondColor {
+	pub r: u8,
+	pub g: u8,
+	pub b: u8,
+	pub a: u8,
+}
+
+impl ByondColor {
+	pub const BLACK: Self = Self::rgb(0, 0, 0);
+	pub const WHITE: Self = Self::rgb(255, 255, 255);
+	pub const TRANSPARENT: Self = Self::new(0, 0, 0, 0);
+
+	#[inline]
+	pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+		Self { r, g, b, a }
+	}
+
+	/// Creates a fully opaque color.
+	#[inline]
+	pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+		Self::new(r, g, b, 255)
+	}
+
+	/// Parses a color string in the `#RGB`, `#RGBA`, `#RRGGBB` or
+	/// `#RRGGBBAA` forms, ignoring case.
+	pub fn parse(color: &str) -> Option<Self> {
+		parse_hex(color).map(|(color, _)| color)
+	}
+
+	/// Returns the same color with a different alpha.
+	#[inline]
+	#[must_use]
+	pub const fn with_alpha(self, a: u8) -> Self {
+		Self::new(self.r, self.g, self.b, a)
+	}
+
+	/// Linearly interpolates each channel, where a `t` of 0 returns `self`
+	/// and a `t` of 1 returns `other`.
+	pub fn lerp(&self, other: &Self, t: f32) -> Self {
+		let lerp = |from: u8, to: u8| {
+			(f32::from(from) + (f32::from(to) - f32::from(from)) * t)
+				.round()
+				.clamp(0.0, 255.0) as u8
+		};
+		Self::new(
+			lerp(self.r, other.r),
+			lerp(self.g, other.g),
+			lerp(self.b, other.b),
+			lerp(self.a, other.a),
+		)
+	}
+
+	/// Converts to hue (0 to 360), saturation (0 to 100) and value (0 to
+	/// 100), the same as `rgb2num(color, COLORSPACE_HSV)`.
+	pub fn to_hsv(&self) -> (f32, f32, f32) {
+		let (hue, max, min) = self.hue();
+		let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+		(hue, saturation * 100.0, max * 100.0)
+	}
+
+	/// Creates an opaque color from hue (0 to 360), saturation (0 to 100)
+	/// and value (0 to 100), the same as `rgb(h, s, v, space =
+	/// COLORSPACE_HSV)`.
+	pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+		let value = (value / 100.0).clamp(0.0, 1.0);
+		let chroma = value * (saturation / 100.0).clamp(0.0, 1.0);
+		Self::from_chroma(hue, chroma, value - chroma)
+	}
+
+	/// Converts to hue (0 to 360), saturation (0 to 100) and lightness (0 to
+	/// 100), the same as `rgb2num(color, COLORSPACE_HSL)`.
+	pub fn to_hsl(&self) -> (f32, f32, f32) {
+		let (hue, max, min) = self.hue();
+		let lightness = (max + min) / 2.0;
+		let saturation = if max == min {
+			0.0
+		} else {
+			(max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+		};
+		(hue, saturation * 100.0, lightness * 100.0)
+	}
+
+	/// Creates an opaque color from hue (0 to 360), saturation (0 to 100)
+	/// and lightness (0 to 100), the same as `rgb(h, s, l, space =
+	/// COLORSPACE_HSL)`.
+	pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+		let lightness = (lightness / 100.0).clamp(0.0, 1.0);
+		let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * (saturation / 100.0).clamp(0.0, 1.0);
+		Self::from_chroma(hue, chroma, lightness - chroma / 2.0)
+	}
+
+	/// Returns the hue in degrees, along with the largest and smallest
+	/// channels from 0 to 1.
+	fn hue(&self) -> (f32, f32, f32) {
+		let [r, g, b] = [self.r, self.g, self.b].map(|channel| f32::from(channel) / 255.0);
+		let max = r.max(g).max(b);
+		let min = r.min(g).min(b);
+		let delta = max - min;
+		let hue = if delta == 0.0 {
+			0.0
+		} else if max == r {
+			60.0 * ((g - b) / delta).rem_euclid(6.0)
+		} else if max == g {
+			60.0 * ((b - r) / delta + 2.0)
+		} else {
+			60.0 * ((r - g) / delta + 4.0)
+		};
+		(hue, max, min)
+	}
+
+	fn from_chroma(hue: f32, chroma: f32, offset: f32) -> Self {
+		let hue = hue.rem_euclid(360.0) / 60.0;
+		let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+		let (r, g, b) = match hue as u32 {
+			0 => (chroma, x, 0.0),
+			1 => (x, chroma, 0.0),
+			2 => (0.0, chroma, x),
+			3 => (0.0, x, chroma),
+			4 => (x, 0.0, chroma),
+			_ => (chroma, 0.0, x),
+		};
+		let channel = |value: f32| ((value + offset) * 255.0).round().clamp(0.0, 255.0) as u8;
+		Self::rgb(channel(r), channel(g), channel(b))
+	}
+}
+
+impl Default for ByondColor {
+	#[inline]
+	fn default() -> Self {
+		Self::WHITE
+	}
+}
+
+impl fmt::Display for ByondColor {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+		if self.a != 255 {
+			write!(f, "{:02x}", self.a)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromByond for ByondColor {
+	/// Converts a color string, such as `"#ff8000"`.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		if !value.is_string() {
+			return Err(ByondError::InvalidConversion {
+				expected: Cow::Borrowed("color"),
+				got: value.get_type().name(),
+			});
+		}
+		let string = value.get_string()?;
+		Self::parse(&string).ok_or(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("color"),
+			got: Cow::Owned(string),
+		})
+	}
+}
+
+impl ToByond for ByondColor {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		self.to_string().to_byond()
+	}
+}
+
+impl From<[u8; 4]> for ByondColor {
+	#[inline]
+	fn from([r, g, b, a]: [u8; 4]) -> Self {
+		Self::new(r, g, b, a)
+	}
+}
+
+impl From<ByondColor> for [u8; 4] {
+	#[inline]
+	fn from(color: ByondColor) -> Self {
+		[color.r, color.g, color.b, color.a]
+	}
+}
+
+/// Parses a hex color, returning it along with if it had an alpha channel.
+fn parse_hex(color: &str) -> Option<(ByondColor, bool)> {
+	let digits = color
+		.strip_prefix('#')?
+		.chars()
+		.map(|c| c.to_digit(16).map(|digit| digit as u8))
+		.collect::<Option<Vec<_>>>()?;
+	let mut rgba = [255; 4];
+	match digits.len() {
+		3 | 4 => {
+			for (channel, digit) in rgba.iter_mut().zip(&digits) {
+				*channel = digit * 17;
+			}
+		}
+		6 | 8 => {
+			for (channel, pair) in rgba.iter_mut().zip(digits.chunks_exact(2)) {
+				*channel = pair[0] * 16 + pair[1];
+			}
+		}
+		_ => return None,
+	}
+	Some((rgba.into(), matches!(digits.len(), 4 | 8)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn strings() {
+		let orange = ByondColor::rgb(255, 136, 0);
+		assert_eq!(ByondColor::parse("#f80"), Some(orange));
+		assert_eq!(ByondColor::parse("#FF8800"), Some(orange));
+		assert_eq!(ByondColor::parse("#ff880080"), Some(orange.with_alpha(128)));
+		assert_eq!(ByondColor::parse("#f808"), Some(orange.with_alpha(136)));
+		assert_eq!(ByondColor::parse("ff8800"), None);
+		assert_eq!(ByondColor::parse("#ff880"), None);
+		assert_eq!(ByondColor::parse("#gg8800"), None);
+		assert_eq!(orange.to_string(), "#ff8800");
+		assert_eq!(orange.with_alpha(128).to_string(), "#ff880080");
+	}
+
+	#[test]
+	fn hsv_and_hsl() {
+		let orange = ByondColor::rgb(255, 128, 0);
+		let (h, s, v) = orange.to_hsv();
+		assert!((h - 30.1).abs() < 0.1 && s == 100.0 && v == 100.0);
+		assert_eq!(ByondColor::from_hsv(h, s, v), orange);
+
+		let (h, s, l) = orange.to_hsl();
+		assert!((h - 30.1).abs() < 0.1 && s == 100.0 && l == 50.0);
+		assert_eq!(ByondColor::from_hsl(h, s, l), orange);
+
+		assert_eq!(
+			ByondColor::from_hsv(240.0, 50.0, 50.0),
+			ByondColor::rgb(64, 64, 128)
+		);
+		assert_eq!(
+			ByondColor::from_hsl(120.0, 100.0, 25.0),
+			ByondColor::rgb(0, 128, 0)
+		);
+		assert_eq!(
+			ByondColor::from_hsv(-60.0, 100.0, 100.0),
+			ByondColor::rgb(255, 0, 255)
+		);
+		assert_eq!(ByondColor::WHITE.to_hsl(), (0.0, 0.0, 100.0));
+	}
+}
@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: 0BSD
+use super::ByondColor;
+use crate::{ByondError, ByondResult, ByondValue, FromByond, ToByond};
+use std::borrow::Cow;
+
+/// The color space a [`Gradient`] blends colors in, the same as DM's
+/// `COLORSPACE_*` defines.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+	/// Blends red, green and blue separately.
+	#[default]
+	Rgb = 0,
+	/// Blends hue, saturation and value, taking the shortest way around the
+	/// hue wheel.
+	Hsv = 1,
+	/// Blends hue, saturation and lightness, taking the shortest way around
+	/// the hue wheel.
+	Hsl = 2,
+}
+
+impl ColorSpace {
+	/// Blends two colors, where a `t` of 0 returns `from` and a `t` of 1
+	/// returns `to`.
+	pub fn blend(self, from: &ByondColor, to: &ByondColor, t: f32) -> ByondColor {
+		match self {
+			Self::Rgb => from.lerp(to, t),
+			Self::Hsv => blend_hue(from.to_hsv(), to.to_hsv(), t, ByondColor::from_hsv),
+			Self::Hsl => blend_hue(from.to_hsl(), to.to_hsl(), t, ByondColor::from_hsl),
+		}
+		.with_alpha(from.lerp(to, t).a)
+	}
+}
+
+/// Blends a hue and two other components, taking the shortest way around the
+/// hue wheel.
+fn blend_hue(
+	(from_h, from_s, from_v): (f32, f32, f32),
+	(to_h, to_s, to_v): (f32, f32, f32),
+	t: f32,
+	back: fn(f32, f32, f32) -> ByondColor,
+) -> ByondColor {
+	let mut turn = to_h - from_h;
+	if turn > 180.0 {
+		turn -= 360.0;
+	} else if turn < -180.0 {
+		turn += 360.0;
+	}
+	let lerp = |from: f32, to: f32| from + (to - from) * t;
+	back(from_h + turn * t, lerp(from_s, to_s), lerp(from_v, to_v))
+}
+
+impl FromByond for ColorSpace {
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		match value.get_number().map(|space| space as u32) {
+			Ok(0) => Ok(Self::Rgb),
+			Ok(1) => Ok(Self::Hsv),
+			Ok(2) => Ok(Self::Hsl),
+			_ => Err(ByondError::InvalidConversion {
+				expected: Cow::Borrowed("color space"),
+				got: value.get_type().name(),
+			}),
+		}
+	}
+}
+
+impl ToByond for ColorSpace {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		Ok(ByondValue::new_num(*self as u8 as f32))
+	}
+}
+
+/// A color gradient, equivalent to the lists DM's `gradient()` takes, such as
+/// `list(0, "#ff0000", 0.5, "#ffff00", 1, "#00ff00", "loop", space =
+/// COLORSPACE_HSV)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gradient {
+	stops: Vec<(f32, ByondColor)>,
+	looped: bool,
+	space: ColorSpace,
+}
+
+impl Gradient {
+	/// Creates a gradient from colors and their positions, which don't need
+	/// to be sorted.
+	pub fn new<Stops>(stops: Stops) -> Self
+	where
+		Stops: IntoIterator<Item = (f32, ByondColor)>,
+	{
+		let mut stops = stops.into_iter().collect::<Vec<_>>();
+		stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+		Self {
+			stops,
+			looped: false,
+			space: ColorSpace::Rgb,
+		}
+	}
+
+	/// Sets if positions past the end wrap around to the start, rather than
+	/// staying at the first or last color.
+	#[must_use]
+	pub fn looped(mut self, looped: bool) -> Self {
+		self.looped = looped;
+		self
+	}
+
+	/// Sets the color space colors are blended in.
+	#[must_use]
+	pub fn space(mut self, space: ColorSpace) -> Self {
+		self.space = space;
+		self
+	}
+
+	/// Returns each color and its position, sorted by position.
+	pub fn stops(&self) -> &[(f32, ByondColor)] {
+		&self.stops
+	}
+
+	/// Returns if positions past the end wrap around to the start.
+	pub fn is_looped(&self) -> bool {
+		self.looped
+	}
+
+	/// Returns the color space colors are blended in.
+	pub fn color_space(&self) -> ColorSpace {
+		self.space
+	}
+
+	/// Returns the color at a position, or `None` if there are no colors.
+	///
+	/// Equivalent to `gradient(list, position)` in DM.
+	pub fn at(&self, position: f32) -> Option<ByondColor> {
+		let (&(start, first), &(end, last)) = (self.stops.first()?, self.stops.last()?);
+		let position = if self.looped && end > start {
+			start + (position - start).rem_euclid(end - start)
+		} else {
+			position
+		};
+		if position <= start {
+			return Some(first);
+		} else if position >= end {
+			return Some(last);
+		}
+		// The first stop past the position, so that the last of several stops
+		// at the same position wins.
+		let next = self.stops.partition_point(|(stop, _)| *stop <= position);
+		let (from, from_color) = self.stops[next - 1];
+		let (to, to_color) = self.stops[next];
+		Some(
+			self.space
+				.blend(&from_color, &to_color, (position - from) / (to - from)),
+		)
+	}
+}
+
+impl FromByond for Gradient {
+	/// Converts a gradient list. Colors without a position before them are
+	/// placed 1 after the previous color, or at 0 if they're first.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let mut stops = Vec::new();
+		let mut looped = false;
+		let mut space = ColorSpace::Rgb;
+		let mut position = None;
+		for (key, value) in value.iter()? {
+			if key.is_number() {
+				position = Some(key.get_number()?);
+				continue;
+			} else if !key.is_string() {
+				continue;
+			}
+			match key.get_string()?.as_str() {
+				"loop" => looped = true,
+				"space" => space = ColorSpace::from_byond(value)?,
+				color => {
+					let color =
+						ByondColor::parse(color).ok_or_else(|| ByondError::InvalidConversion {
+							expected: Cow::Borrowed("color"),
+							got: Cow::Owned(color.to_owned()),
+						})?;
+					let at = position.take().unwrap_or_else(|| {
+						stops.last().map_or(0.0, |(previous, _)| previous + 1.0)
+					});
+					stops.push((at, color));
+				}
+			}
+		}
+		Ok(Self::new(stops).looped(looped).space(space))
+	}
+}
+
+impl ToByond for Gradient {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		let mut list = ByondValue::new_list()?;
+		for (position, color) in &self.stops {
+			list.push_list(position.to_byond()?)?;
+			list.push_list(color.to_byond()?)?;
+		}
+		if self.looped {
+			list.push_list("loop".to_byond()?)?;
+		}
+		if self.space != ColorSpace::Rgb {
+			list.write_list_index("space", self.space)?;
+		}
+		Ok(list)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn positions() {
+		let (red, green) = (ByondColor::rgb(255, 0, 0), ByondColor::rgb(0, 255, 0));
+		let gradient = Gradient::new([(1.0, green), (0.0, red)]);
+		assert_eq!(gradient.at(-1.0), Some(red));
+		assert_eq!(gradient.at(0.5), Some(ByondColor::rgb(128, 128, 0)));
+		assert_eq!(gradient.at(2.0), Some(green));
+		assert_eq!(Gradient::default().at(0.0), None);
+
+		let looped = gradient.clone().looped(true);
+		assert_eq!(looped.at(1.5), looped.at(0.5));
+		assert_eq!(looped.at(-0.25), gradient.at(0.75));
+
+		// A hard edge, where the later color wins.
+		let blue = ByondColor::rgb(0, 0, 255);
+		let edge = Gradient::new([(0.0, red), (1.0, green), (1.0, blue), (2.0, blue)]);
+		assert_eq!(edge.at(1.0), Some(blue));
+	}
+
+	#[test]
+	fn color_spaces() {
+		let (red, blue) = (ByondColor::rgb(255, 0, 0), ByondColor::rgb(0, 0, 255));
+		let magenta = ByondColor::rgb(255, 0, 255);
+		assert_eq!(ColorSpace::Hsv.blend(&red, &blue, 0.5), magenta);
+		assert_eq!(ColorSpace::Hsl.blend(&red, &blue, 0.5), magenta);
+		assert_eq!(
+			ColorSpace::Rgb.blend(&red, &blue, 0.5),
+			ByondColor::rgb(128, 0, 128)
+		);
+		assert_eq!(
+			ColorSpace::Hsv.blend(&red, &red.with_alpha(0), 0.5),
+			red.with_alpha(128)
+		);
+	}
+}
@@ -0,0 +1,313 @@
+// SPDX-License-Identifier: 0BSD
+use super::{ByondColor, parse_hex};
+use crate::{ByondError, ByondResult, ByondValue, FromByond, ToByond};
+use std::{borrow::Cow, ops::Mul};
+
+/// The weights of the red, green and blue channels in a color's luminance.
+const LUMINANCE: [f32; 3] = [0.213, 0.715, 0.072];
+
+/// A color matrix, normalized to DM's 20 number form: 5 rows of 4 columns,
+/// with how much the red, green, blue and alpha of a color contribute to each
+/// channel of the result, then a constant added to each channel.
+///
+/// Channels are from 0 to 1, so a constant of 1 adds 255.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorMatrix(pub [f32; 20]);
+
+impl ColorMatrix {
+	/// The identity matrix, which doesn't change anything.
+	#[rustfmt::skip]
+	pub const IDENTITY: Self = Self([
+		1.0, 0.0, 0.0, 0.0,
+		0.0, 1.0, 0.0, 0.0,
+		0.0, 0.0, 1.0, 0.0,
+		0.0, 0.0, 0.0, 1.0,
+		0.0, 0.0, 0.0, 0.0,
+	]);
+
+	/// Converts any of the list forms DM accepts: 9 numbers (red, green and
+	/// blue rows), 12 (those, then constants), 16 (red, green, blue and alpha
+	/// rows) or 20 (those, then constants).
+	pub fn from_list(values: &[f32]) -> Option<Self> {
+		let (columns, rows) = match values.len() {
+			9 => (3, 3),
+			12 => (3, 4),
+			16 => (4, 4),
+			20 => (4, 5),
+			_ => return None,
+		};
+		let mut matrix = Self::IDENTITY;
+		for row in 0..rows {
+			// 12-number matrices have no alpha row, so the last row is the
+			// constants.
+			let target = if columns == 3 && row == 3 { 4 } else { row };
+			for column in 0..columns {
+				matrix.0[target * 4 + column] = values[row * columns + column];
+			}
+		}
+		Some(matrix)
+	}
+
+	/// Creates a matrix that multiplies each channel by a color, the same as
+	/// setting `color` to a string.
+	pub fn from_color(color: ByondColor) -> Self {
+		let mut matrix = Self([0.0; 20]);
+		for (idx, channel) in <[u8; 4]>::from(color).into_iter().enumerate() {
+			matrix.0[idx * 5] = f32::from(channel) / 255.0;
+		}
+		matrix
+	}
+
+	/// Creates a matrix that changes saturation, where 0 is grayscale, 1
+	/// doesn't change anything, and more than 1 is more saturated.
+	pub fn saturation(amount: f32) -> Self {
+		let mut matrix = Self::IDENTITY;
+		for (input, weight) in LUMINANCE.into_iter().enumerate() {
+			for output in 0..3 {
+				let own = if input == output { amount } else { 0.0 };
+				matrix.0[input * 4 + output] = weight * (1.0 - amount) + own;
+			}
+		}
+		matrix
+	}
+
+	/// Creates a matrix that rotates hue by `angle` degrees, keeping
+	/// luminance the same.
+	pub fn hue_rotation(angle: f32) -> Self {
+		let (sin, cos) = angle.to_radians().sin_cos();
+		let [r, g, b] = LUMINANCE;
+		// Each row is how much one channel of the input contributes to the
+		// red, green and blue of the output.
+		let rows = [
+			[
+				r + cos * (1.0 - r) - sin * r,
+				r - cos * r + sin * 0.143,
+				r - cos * r - sin * (1.0 - r),
+			],
+			[
+				g - cos * g - sin * g,
+				g + cos * (1.0 - g) + sin * 0.140,
+				g - cos * g + sin * g,
+			],
+			[
+				b - cos * b + sin * (1.0 - b),
+				b - cos * b - sin * 0.283,
+				b + cos * (1.0 - b) + sin * b,
+			],
+		];
+		let mut matrix = Self::IDENTITY;
+		for (input, row) in rows.into_iter().enumerate() {
+			matrix.0[input * 4..input * 4 + 3].copy_from_slice(&row);
+		}
+		matrix
+	}
+
+	/// Returns a matrix that applies this matrix, then `other`.
+	pub fn then(&self, other: &Self) -> Self {
+		let mut matrix = Self([0.0; 20]);
+		for row in 0..5 {
+			for column in 0..4 {
+				let mut value = (0..4)
+					.map(|idx| self.0[row * 4 + idx] * other.0[idx * 4 + column])
+					.sum::<f32>();
+				if row == 4 {
+					value += other.0[16 + column];
+				}
+				matrix.0[row * 4 + column] = value;
+			}
+		}
+		matrix
+	}
+
+	/// Applies the matrix to a color.
+	pub fn apply(&self, color: ByondColor) -> ByondColor {
+		let input = <[u8; 4]>::from(color).map(|channel| f32::from(channel) / 255.0);
+		let output: [u8; 4] = std::array::from_fn(|column| {
+			let value = (0..4)
+				.map(|row| input[row] * self.0[row * 4 + column])
+				.sum::<f32>()
+				+ self.0[16 + column];
+			(value.clamp(0.0, 1.0) * 255.0).round() as u8
+		});
+		output.into()
+	}
+
+	/// Interpolates each number of the matrix, the same as `animate()` does,
+	/// where a `t` of 0 returns `self` and a `t` of 1 returns `other`.
+	pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+		Self(std::array::from_fn(|idx| {
+			self.0[idx] + (other.0[idx] - self.0[idx]) * t
+		}))
+	}
+
+	/// Converts a list of 3 to 5 color strings, one per row, such as
+	/// `list("#ff0000", "#00ff00", "#0000ff")`: the red, green and blue rows,
+	/// then optionally the alpha row and the constants.
+	///
+	/// Rows without an alpha channel keep the alpha column of the identity
+	/// matrix, so only the alpha row contributes to alpha.
+	fn from_rows(rows: &[(ByondColor, bool)]) -> Option<Self> {
+		if !(3..=5).contains(&rows.len()) {
+			return None;
+		}
+		let mut matrix = Self::IDENTITY;
+		for (row, (color, has_alpha)) in rows.iter().enumerate() {
+			let alpha = if *has_alpha {
+				f32::from(color.a) / 255.0
+			} else {
+				Self::IDENTITY.0[row * 4 + 3]
+			};
+			let values = [color.r, color.g, color.b].map(|channel| f32::from(channel) / 255.0);
+			matrix.0[row * 4..row * 4 + 3].copy_from_slice(&values);
+			matrix.0[row * 4 + 3] = alpha;
+		}
+		Some(matrix)
+	}
+}
+
+impl Default for ColorMatrix {
+	#[inline]
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl FromByond for ColorMatrix {
+	/// Converts a color string, a list of numbers, or a list of color strings.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let got = value.get_type().name();
+		let matrix = if value.is_string() {
+			ByondColor::from_byond(value).map(Self::from_color).ok()
+		} else if value.is_list() {
+			let values = value.read_list()?;
+			if values.iter().all(ByondValue::is_string) {
+				values
+					.into_iter()
+					.map(|row| Ok(parse_hex(&row.get_string()?)))
+					.collect::<ByondResult<Option<Vec<_>>>>()?
+					.and_then(|rows| Self::from_rows(&rows))
+			} else {
+				let values = values
+					.into_iter()
+					.map(|value| Ok(Option::<f32>::from_byond(value)?.unwrap_or_default()))
+					.collect::<ByondResult<Vec<_>>>()?;
+				Self::from_list(&values)
+			}
+		} else {
+			None
+		};
+		matrix.ok_or(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("color matrix"),
+			got,
+		})
+	}
+}
+
+impl ToByond for ColorMatrix {
+	/// Creates a list of all 20 numbers.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		self.0.to_byond()
+	}
+}
+
+impl From<[f32; 20]> for ColorMatrix {
+	#[inline]
+	fn from(matrix: [f32; 20]) -> Self {
+		Self(matrix)
+	}
+}
+
+impl From<ByondColor> for ColorMatrix {
+	#[inline]
+	fn from(color: ByondColor) -> Self {
+		Self::from_color(color)
+	}
+}
+
+/// Applies `self`, then `other`.
+impl Mul for ColorMatrix {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		self.then(&other)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lists_and_apply() {
+		let color = ByondColor::rgb(200, 100, 50);
+		assert_eq!(ColorMatrix::IDENTITY.apply(color), color);
+		assert_eq!(
+			ColorMatrix::from_color(ByondColor::new(255, 128, 0, 128)).apply(color),
+			ByondColor::new(200, 50, 0, 128)
+		);
+
+		// Swap red and blue, then add some green.
+		let matrix =
+			ColorMatrix::from_list(&[0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.2, 0.0])
+				.unwrap();
+		assert_eq!(matrix.apply(color), ByondColor::rgb(50, 151, 200));
+		assert_eq!(ColorMatrix::from_list(&[1.0; 10]), None);
+
+		let rows = ["#0000ff", "#00ff00", "#ff0000"].map(|row| parse_hex(row).unwrap());
+		let swap = ColorMatrix::from_rows(&rows).unwrap();
+		assert_eq!(swap.apply(color), ByondColor::rgb(50, 100, 200));
+		assert_eq!(swap.then(&swap), ColorMatrix::IDENTITY);
+
+		// Three rows with alpha, where red also adds to alpha.
+		let rows = ["#0000ff80", "#00ff0000", "#ff000000"].map(|row| parse_hex(row).unwrap());
+		assert_eq!(
+			ColorMatrix::from_rows(&rows)
+				.unwrap()
+				.apply(color.with_alpha(0)),
+			ByondColor::new(50, 100, 200, 100)
+		);
+		// Five rows without alpha, with constants at the end.
+		let rows = ["#0000ff", "#00ff00", "#ff0000", "#000000", "#102030"]
+			.map(|row| parse_hex(row).unwrap());
+		assert_eq!(
+			ColorMatrix::from_rows(&rows).unwrap().apply(color),
+			ByondColor::rgb(66, 132, 248)
+		);
+		assert_eq!(ColorMatrix::from_rows(&rows[..2]), None);
+	}
+
+	#[test]
+	fn adjustments() {
+		let color = ByondColor::rgb(200, 100, 50);
+		let gray = ColorMatrix::saturation(0.0).apply(color);
+		assert!(gray.r == gray.g && gray.g == gray.b);
+		assert_eq!(ColorMatrix::saturation(1.0).apply(color), color);
+
+		let turned = ColorMatrix::hue_rotation(120.0)
+			.then(&ColorMatrix::hue_rotation(240.0))
+			.apply(color);
+		for (a, b) in <[u8; 4]>::from(turned)
+			.into_iter()
+			.zip(<[u8; 4]>::from(color))
+		{
+			assert!(a.abs_diff(b) <= 1, "{turned:?} != {color:?}");
+		}
+		let white = ByondColor::WHITE;
+		assert_eq!(ColorMatrix::hue_rotation(90.0).apply(white), white);
+
+		// Composing is the same as applying one after another.
+		let darken = ColorMatrix::from_color(ByondColor::rgb(128, 128, 128));
+		let invert = ColorMatrix::from_list(&[
+			-1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, -1.0, 1.0, 1.0, 1.0,
+		])
+		.unwrap();
+		assert_eq!(
+			(invert * darken).apply(color),
+			darken.apply(invert.apply(color))
+		);
+		assert_eq!(
+			invert.interpolate(&ColorMatrix::IDENTITY, 0.5).apply(color),
+			ByondColor::rgb(128, 128, 128)
+		);
+	}
+}
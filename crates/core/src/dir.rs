@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondResult, ByondValue, ByondXYZ, FromByond, ToByond};
+
+bitflags::bitflags! {
+	/// A direction, matching the values of DM's direction constants.
+	///
+	/// Angles are in degrees, clockwise from north, i.e `EAST` is 90 degrees.
+	#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+	#[repr(transparent)]
+	pub struct ByondDir: u8 {
+		const NORTH = 1;
+		const SOUTH = 2;
+		const EAST = 4;
+		const WEST = 8;
+		const UP = 16;
+		const DOWN = 32;
+
+		const NORTHEAST = Self::NORTH.bits() | Self::EAST.bits();
+		const NORTHWEST = Self::NORTH.bits() | Self::WEST.bits();
+		const SOUTHEAST = Self::SOUTH.bits() | Self::EAST.bits();
+		const SOUTHWEST = Self::SOUTH.bits() | Self::WEST.bits();
+	}
+}
+
+impl ByondDir {
+	/// The four cardinal directions.
+	pub const CARDINALS: [Self; 4] = [Self::NORTH, Self::SOUTH, Self::EAST, Self::WEST];
+
+	/// The four diagonal directions.
+	pub const DIAGONALS: [Self; 4] = [
+		Self::NORTHEAST,
+		Self::NORTHWEST,
+		Self::SOUTHEAST,
+		Self::SOUTHWEST,
+	];
+
+	/// All eight horizontal directions, in clockwise order starting from
+	/// north.
+	pub const HORIZONTAL: [Self; 8] = [
+		Self::NORTH,
+		Self::NORTHEAST,
+		Self::EAST,
+		Self::SOUTHEAST,
+		Self::SOUTH,
+		Self::SOUTHWEST,
+		Self::WEST,
+		Self::NORTHWEST,
+	];
+
+	/// Returns only the horizontal (north/south/east/west) part of the
+	/// direction.
+	pub const fn horizontal(self) -> Self {
+		Self::from_bits_truncate(self.bits() & 0b1111)
+	}
+
+	/// Returns only the vertical (up/down) part of the direction.
+	pub const fn vertical(self) -> Self {
+		Self::from_bits_truncate(self.bits() & 0b11_0000)
+	}
+
+	/// Returns if this is exactly one of north, south, east or west.
+	pub const fn is_cardinal(self) -> bool {
+		matches!(self, Self::NORTH | Self::SOUTH | Self::EAST | Self::WEST)
+	}
+
+	/// Returns if this is exactly one of the four diagonal directions.
+	pub const fn is_diagonal(self) -> bool {
+		matches!(
+			self,
+			Self::NORTHEAST | Self::NORTHWEST | Self::SOUTHEAST | Self::SOUTHWEST
+		)
+	}
+
+	/// Returns the tile offset of a single step in this direction.
+	///
+	/// Opposing directions, such as `NORTH | SOUTH`, cancel out.
+	pub const fn offset(self) -> (i16, i16, i16) {
+		(
+			self.axis(Self::EAST, Self::WEST),
+			self.axis(Self::NORTH, Self::SOUTH),
+			self.axis(Self::UP, Self::DOWN),
+		)
+	}
+
+	const fn axis(self, positive: Self, negative: Self) -> i16 {
+		self.contains(positive) as i16 - self.contains(negative) as i16
+	}
+
+	/// Returns the opposite direction, i.e `SOUTHWEST` for `NORTHEAST`.
+	pub const fn reverse(self) -> Self {
+		let bits = self.bits();
+		// swap each pair of opposing bits
+		Self::from_bits_truncate(((bits & 0b01_0101) << 1) | ((bits & 0b10_1010) >> 1))
+	}
+
+	/// Rotates the horizontal part of the direction counter-clockwise by the
+	/// given angle in degrees, rounded to the nearest 45 degrees. The vertical
+	/// part is kept as-is.
+	///
+	/// Equivalent to `turn(dir, angle)` in DM.
+	pub fn turn(self, angle: f32) -> Self {
+		match self.horizontal().to_angle() {
+			Some(current) => Self::from_angle(current - angle) | self.vertical(),
+			None => self,
+		}
+	}
+
+	/// Returns the angle of the horizontal part of the direction, in degrees
+	/// clockwise from north, or `None` if it has no horizontal part (or it
+	/// cancels out).
+	pub fn to_angle(self) -> Option<f32> {
+		let (x, y, _) = self.offset();
+		let idx = Self::HORIZONTAL
+			.iter()
+			.position(|dir| dir.offset() == (x, y, 0))?;
+		Some(idx as f32 * 45.0)
+	}
+
+	/// Returns the horizontal direction closest to the given angle, in
+	/// degrees clockwise from north.
+	pub fn from_angle(angle: f32) -> Self {
+		let idx = (angle.rem_euclid(360.0) / 45.0).round() as usize % 8;
+		Self::HORIZONTAL[idx]
+	}
+}
+
+/// Returns the coordinates one step away in the given direction.
+///
+/// Like `get_step(ref, dir)` in DM, but returns coordinates rather than a turf.
+pub fn get_step(xyz: ByondXYZ, dir: ByondDir) -> ByondXYZ {
+	let (x, y, z) = dir.offset();
+	xyz + ByondXYZ::new(x, y, z)
+}
+
+/// Returns the direction from one set of coordinates to another.
+///
+/// Equivalent to `get_dir(from, to)` in DM.
+pub fn get_dir(from: ByondXYZ, to: ByondXYZ) -> ByondDir {
+	let mut dir = ByondDir::empty();
+	let pick = |delta: i16, positive: ByondDir, negative: ByondDir| match delta.signum() {
+		1 => positive,
+		-1 => negative,
+		_ => ByondDir::empty(),
+	};
+	dir |= pick(to.x() - from.x(), ByondDir::EAST, ByondDir::WEST);
+	dir |= pick(to.y() - from.y(), ByondDir::NORTH, ByondDir::SOUTH);
+	dir |= pick(to.z() - from.z(), ByondDir::UP, ByondDir::DOWN);
+	dir
+}
+
+impl ByondXYZ {
+	/// Returns the coordinates one step away in the given direction.
+	#[inline]
+	pub fn step(self, dir: ByondDir) -> Self {
+		get_step(self, dir)
+	}
+
+	/// Returns the direction from these coordinates to another.
+	#[inline]
+	pub fn dir_to(self, other: Self) -> ByondDir {
+		get_dir(self, other)
+	}
+}
+
+impl From<ByondDir> for u8 {
+	#[inline]
+	fn from(dir: ByondDir) -> Self {
+		dir.bits()
+	}
+}
+
+impl FromByond for ByondDir {
+	/// Converts a number, discarding any unknown bits.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		value
+			.get_number()
+			.map(|dir| Self::from_bits_truncate(dir as u8))
+	}
+}
+
+impl ToByond for ByondDir {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		self.bits().to_byond()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn offsets() {
+		assert_eq!(ByondDir::NORTHEAST.offset(), (1, 1, 0));
+		assert_eq!(ByondDir::SOUTHWEST.offset(), (-1, -1, 0));
+		assert_eq!((ByondDir::WEST | ByondDir::UP).offset(), (-1, 0, 1));
+		assert_eq!((ByondDir::NORTH | ByondDir::SOUTH).offset(), (0, 0, 0));
+	}
+
+	#[test]
+	fn reverse() {
+		assert_eq!(ByondDir::NORTH.reverse(), ByondDir::SOUTH);
+		assert_eq!(ByondDir::SOUTHEAST.reverse(), ByondDir::NORTHWEST);
+		assert_eq!(
+			(ByondDir::EAST | ByondDir::DOWN).reverse(),
+			ByondDir::WEST | ByondDir::UP
+		);
+	}
+
+	#[test]
+	fn turning() {
+		assert_eq!(ByondDir::NORTH.turn(90.0), ByondDir::WEST);
+		assert_eq!(ByondDir::NORTH.turn(-90.0), ByondDir::EAST);
+		assert_eq!(ByondDir::NORTH.turn(45.0), ByondDir::NORTHWEST);
+		assert_eq!(ByondDir::SOUTHWEST.turn(180.0), ByondDir::NORTHEAST);
+		assert_eq!(
+			(ByondDir::EAST | ByondDir::UP).turn(90.0),
+			ByondDir::NORTH | ByondDir::UP
+		);
+		assert_eq!(ByondDir::UP.turn(90.0), ByondDir::UP);
+	}
+
+	#[test]
+	fn angles() {
+		assert_eq!(ByondDir::NORTH.to_angle(), Some(0.0));
+		assert_eq!(ByondDir::EAST.to_angle(), Some(90.0));
+		assert_eq!(ByondDir::NORTHWEST.to_angle(), Some(315.0));
+		assert_eq!(ByondDir::UP.to_angle(), None);
+		assert_eq!(ByondDir::from_angle(100.0), ByondDir::EAST);
+		assert_eq!(ByondDir::from_angle(-45.0), ByondDir::NORTHWEST);
+		assert_eq!(ByondDir::from_angle(350.0), ByondDir::NORTH);
+	}
+
+	#[test]
+	fn steps() {
+		let origin = ByondXYZ::new(5, 5, 1);
+		assert_eq!(
+			get_step(origin, ByondDir::NORTHEAST),
+			ByondXYZ::new(6, 6, 1)
+		);
+		assert_eq!(get_dir(origin, ByondXYZ::new(9, 2, 1)), ByondDir::SOUTHEAST);
+		assert_eq!(get_dir(origin, ByondXYZ::new(5, 5, 2)), ByondDir::UP);
+		assert_eq!(get_dir(origin, origin), ByondDir::empty());
+		for dir in ByondDir::HORIZONTAL {
+			assert_eq!(get_dir(origin, origin.step(dir)), dir);
+		}
+	}
+}
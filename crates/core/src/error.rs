@@ -1,5 +1,8 @@
 // SPDX-License-Identifier: 0BSD
-use crate::byond;
+use crate::{
+	byond,
+	sys::{ByondVersion, u4c},
+};
 use std::{
 	borrow::Cow,
 	convert::Infallible,
@@ -45,6 +48,17 @@ pub enum ByondError {
 	/// Thrown by us when we know this type does not have a refnumber
 	#[error("Cannot get a ref from this value")]
 	NotReferenceable,
+	/// Thrown when a string could not be parsed as a typepath.
+	#[error("Invalid typepath: {0}")]
+	InvalidTypePath(String),
+	/// Thrown when something requires a newer version of BYOND than the one
+	/// that is currently running.
+	#[error("{feature} requires BYOND {required} or later, but {running} is running")]
+	Unsupported {
+		feature: Cow<'static, str>,
+		required: ByondVersion,
+		running: ByondVersion,
+	},
 	#[error(transparent)]
 	Boxed(Box<dyn std::error::Error + Send + 'static>),
 }
@@ -57,6 +71,19 @@ pub fn get_last_byond_error() -> Self {
 		}
 	}
 
+	/// Creates an [`Unsupported`](Self::Unsupported) error for the given
+	/// feature, filling in the currently running version of BYOND.
+	pub fn unsupported<Feature>(feature: Feature, version: u4c, build: u4c) -> Self
+	where
+		Feature: Into<Cow<'static, str>>,
+	{
+		Self::Unsupported {
+			feature: feature.into(),
+			required: ByondVersion { version, build },
+			running: byond().get_version(),
+		}
+	}
+
 	pub fn boxed<Err>(err: Err) -> Self
 	where
 		Err: std::error::Error + Send + 'static,
@@ -56,8 +56,9 @@ impl<Key, Value, Hasher> FromByond for HashMap<Key, Value, Hasher>
 	Hasher: BuildHasher + Default,
 {
 	fn from_byond(value: ByondValue) -> ByondResult<Self> {
-		let mut out = HashMap::with_capacity_and_hasher(value.length()?, Hasher::default());
-		for (key, value) in value.iter()? {
+		let iter = value.iter()?;
+		let mut out = HashMap::with_capacity_and_hasher(iter.size_hint().0, Hasher::default());
+		for (key, value) in iter {
 			out.insert(Key::from_byond(key)?, Value::from_byond(value)?);
 		}
 		Ok(out)
@@ -70,8 +71,9 @@ impl<Value, Hasher> FromByond for HashSet<Value, Hasher>
 	Hasher: BuildHasher + Default,
 {
 	fn from_byond(value: ByondValue) -> ByondResult<Self> {
-		let mut out = HashSet::with_capacity_and_hasher(value.length()?, Hasher::default());
-		for key in value.values()? {
+		let iter = value.values()?;
+		let mut out = HashSet::with_capacity_and_hasher(iter.size_hint().0, Hasher::default());
+		for key in iter {
 			out.insert(Value::from_byond(key)?);
 		}
 		Ok(out)
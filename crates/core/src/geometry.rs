@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: 0BSD
+//! Iterators over common shapes of tiles.
+use crate::{ByondXYZ, block::ByondBlock};
+use std::iter::FusedIterator;
+
+/// Iterates over the tiles on a line between two points (inclusive), using
+/// Bresenham's line algorithm.
+///
+/// The line is drawn on the Z level of `from`.
+pub fn line(from: ByondXYZ, to: ByondXYZ) -> LineIter {
+	let (dx, dy) = (
+		(to.x() as i32 - from.x() as i32).abs(),
+		-(to.y() as i32 - from.y() as i32).abs(),
+	);
+	LineIter {
+		x: from.x() as i32,
+		y: from.y() as i32,
+		z: from.z(),
+		end_x: to.x() as i32,
+		end_y: to.y() as i32,
+		dx,
+		dy,
+		step_x: if from.x() < to.x() { 1 } else { -1 },
+		step_y: if from.y() < to.y() { 1 } else { -1 },
+		err: dx + dy,
+		done: false,
+	}
+}
+
+/// Iterates over the tiles within `radius` tiles of `center` on its Z level,
+/// forming a filled circle.
+///
+/// A tile is within the circle if its distance from the center, rounded to
+/// the nearest tile, is at most `radius`.
+pub fn circle(center: ByondXYZ, radius: u16) -> impl Iterator<Item = ByondXYZ> + Clone {
+	let limit = circle_limit(radius);
+	ByondBlock::around(center, radius)
+		.iter()
+		.filter(move |xyz| squared_distance(center, *xyz) <= limit)
+}
+
+/// Iterates over the tiles exactly `radius` tiles away from `center` on its Z
+/// level, forming the outline of a circle.
+///
+/// This yields exactly the tiles in [`circle(center, radius)`](circle) that
+/// aren't in `circle(center, radius - 1)`.
+pub fn ring(center: ByondXYZ, radius: u16) -> impl Iterator<Item = ByondXYZ> + Clone {
+	let outer = circle_limit(radius);
+	let inner = radius.checked_sub(1).map(circle_limit);
+	ByondBlock::around(center, radius)
+		.iter()
+		.filter(move |xyz| {
+			let distance = squared_distance(center, *xyz);
+			distance <= outer && inner.is_none_or(|inner| distance > inner)
+		})
+}
+
+/// The largest squared distance within a circle of the given radius, which is
+/// `(radius + 0.5)^2` rounded down.
+#[inline]
+pub(crate) fn circle_limit(radius: u16) -> i64 {
+	let radius = radius as i64;
+	radius * radius + radius
+}
+
+#[inline]
+pub(crate) fn squared_distance(a: ByondXYZ, b: ByondXYZ) -> i64 {
+	let dx = a.x() as i64 - b.x() as i64;
+	let dy = a.y() as i64 - b.y() as i64;
+	dx * dx + dy * dy
+}
+
+/// An iterator over the tiles on a line, see [`line()`].
+#[derive(Debug, Clone)]
+pub struct LineIter {
+	x: i32,
+	y: i32,
+	z: i16,
+	end_x: i32,
+	end_y: i32,
+	dx: i32,
+	dy: i32,
+	step_x: i32,
+	step_y: i32,
+	err: i32,
+	done: bool,
+}
+
+impl Iterator for LineIter {
+	type Item = ByondXYZ;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+		let current = ByondXYZ::new(self.x as i16, self.y as i16, self.z);
+		if self.x == self.end_x && self.y == self.end_y {
+			self.done = true;
+			return Some(current);
+		}
+		let err2 = self.err * 2;
+		if err2 >= self.dy {
+			self.err += self.dy;
+			self.x += self.step_x;
+		}
+		if err2 <= self.dx {
+			self.err += self.dx;
+			self.y += self.step_y;
+		}
+		Some(current)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		if self.done {
+			return (0, Some(0));
+		}
+		let len = (self.end_x - self.x)
+			.unsigned_abs()
+			.max((self.end_y - self.y).unsigned_abs()) as usize
+			+ 1;
+		(len, Some(len))
+	}
+}
+
+impl ExactSizeIterator for LineIter {}
+
+impl FusedIterator for LineIter {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn xy(iter: impl Iterator<Item = ByondXYZ>) -> Vec<(i16, i16)> {
+		iter.map(|xyz| (xyz.x(), xyz.y())).collect()
+	}
+
+	#[test]
+	fn lines() {
+		let origin = ByondXYZ::new(1, 1, 1);
+		assert_eq!(xy(line(origin, origin)), [(1, 1)]);
+		assert_eq!(xy(line(origin, ByondXYZ::new(4, 1, 1))), [
+			(1, 1),
+			(2, 1),
+			(3, 1),
+			(4, 1)
+		]);
+		assert_eq!(xy(line(origin, ByondXYZ::new(4, 4, 1))), [
+			(1, 1),
+			(2, 2),
+			(3, 3),
+			(4, 4)
+		]);
+		assert_eq!(xy(line(ByondXYZ::new(5, 3, 1), origin)), [
+			(5, 3),
+			(4, 2),
+			(3, 2),
+			(2, 1),
+			(1, 1)
+		]);
+		let iter = line(origin, ByondXYZ::new(10, 4, 1));
+		assert_eq!(iter.len(), 10);
+		assert_eq!(iter.count(), 10);
+	}
+
+	#[test]
+	fn circles() {
+		let center = ByondXYZ::new(10, 10, 1);
+		assert_eq!(xy(circle(center, 0)), [(10, 10)]);
+		assert_eq!(circle(center, 1).count(), 9);
+		assert_eq!(circle(center, 2).count(), 21);
+		assert!(circle(center, 3).all(|xyz| xyz.distance_euclidean(&center) <= 3.5));
+		let ring_tiles = ring(center, 2).collect::<Vec<_>>();
+		assert_eq!(ring_tiles.len(), 12);
+		assert!(
+			ring_tiles
+				.iter()
+				.all(|xyz| !circle(center, 1).any(|inner| inner == *xyz))
+		);
+		assert_eq!(ring(center, 0).count(), 1);
+	}
+}
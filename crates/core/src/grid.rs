@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: 0BSD
+//! Dense, per-tile data covering the map.
+//!
+//! For simulations that read and write several turf vars every tick, see
+//! [`GridSim`].
+mod dense;
+mod sim;
+mod snapshot;
+mod turfs;
+
+pub use self::{
+	dense::Grid,
+	sim::{GridSim, SimCell},
+	snapshot::{VarSnapshot, snapshot_var},
+	turfs::TurfGrid,
+};
+
+#[cfg(test)]
+pub(crate) use self::dense::ascii_map;
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondBlock, ByondXYZ};
+use rayon::prelude::*;
+use std::ops::{Index, IndexMut};
+
+/// A dense grid of values, one for each tile in a [`ByondBlock`], stored in the
+/// same row-major order as `block()`.
+///
+/// Blocks covering a single z-level act as 2D grids, while blocks covering
+/// multiple z-levels act as 3D grids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<Value> {
+	block: ByondBlock,
+	cells: Vec<Value>,
+}
+
+impl<Value> Grid<Value> {
+	/// Creates a grid with every cell set to the same value.
+	pub fn new(block: ByondBlock, value: Value) -> Self
+	where
+		Value: Clone,
+	{
+		Self {
+			block,
+			cells: vec![value; block.len()],
+		}
+	}
+
+	/// Creates a grid by calling `value` for every tile in the block.
+	pub fn from_fn<Func>(block: ByondBlock, value: Func) -> Self
+	where
+		Func: FnMut(ByondXYZ) -> Value,
+	{
+		Self {
+			block,
+			cells: block.iter().map(value).collect(),
+		}
+	}
+
+	/// Creates a grid from values in row-major order, returning `None` if
+	/// there isn't exactly one value for each tile in the block.
+	pub fn from_vec(block: ByondBlock, cells: Vec<Value>) -> Option<Self> {
+		(cells.len() == block.len()).then_some(Self { block, cells })
+	}
+
+	/// Returns the block this grid covers.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.block
+	}
+
+	/// Returns the number of cells in the grid.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+
+	/// Grids have a cell for every tile in their block, which always contains
+	/// at least one tile, so this is always false.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		false
+	}
+
+	/// Returns if the grid contains the given coordinates.
+	#[inline]
+	pub fn contains(&self, xyz: &ByondXYZ) -> bool {
+		self.block.contains(xyz)
+	}
+
+	/// Returns the value at the given coordinates, or `None` if they're
+	/// outside of the grid.
+	pub fn get(&self, xyz: ByondXYZ) -> Option<&Value> {
+		self.block.index_of(&xyz).map(|idx| &self.cells[idx])
+	}
+
+	/// Returns a mutable reference to the value at the given coordinates, or
+	/// `None` if they're outside of the grid.
+	pub fn get_mut(&mut self, xyz: ByondXYZ) -> Option<&mut Value> {
+		self.block.index_of(&xyz).map(|idx| &mut self.cells[idx])
+	}
+
+	/// Sets the value at the given coordinates, returning the old value, or
+	/// `None` if they're outside of the grid.
+	pub fn set(&mut self, xyz: ByondXYZ, value: Value) -> Option<Value> {
+		self.get_mut(xyz).map(|cell| std::mem::replace(cell, value))
+	}
+
+	/// Returns every value, in row-major order.
+	#[inline]
+	pub fn as_slice(&self) -> &[Value] {
+		&self.cells
+	}
+
+	/// Returns every value mutably, in row-major order.
+	#[inline]
+	pub fn as_mut_slice(&mut self) -> &mut [Value] {
+		&mut self.cells
+	}
+
+	/// Returns every value, in row-major order.
+	#[inline]
+	pub fn into_vec(self) -> Vec<Value> {
+		self.cells
+	}
+
+	/// Iterates over every value along with its coordinates.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (ByondXYZ, &Value)> + '_ {
+		self.block.iter().zip(&self.cells)
+	}
+
+	/// Iterates mutably over every value along with its coordinates.
+	pub fn iter_mut(&mut self) -> impl ExactSizeIterator<Item = (ByondXYZ, &mut Value)> + '_ {
+		self.block.iter().zip(&mut self.cells)
+	}
+
+	/// Creates a new grid over the same block by applying `func` to every
+	/// value.
+	pub fn map<Func, Output>(&self, func: Func) -> Grid<Output>
+	where
+		Func: FnMut(&Value) -> Output,
+	{
+		Grid {
+			block: self.block,
+			cells: self.cells.iter().map(func).collect(),
+		}
+	}
+
+	/// Iterates over every value along with its coordinates in parallel.
+	pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (ByondXYZ, &Value)> + '_
+	where
+		Value: Sync,
+	{
+		let block = self.block;
+		self.cells
+			.par_iter()
+			.enumerate()
+			.map(move |(idx, value)| (block.xyz_at(idx).unwrap_or_default(), value))
+	}
+
+	/// Iterates mutably over every value along with its coordinates in
+	/// parallel.
+	pub fn par_iter_mut(
+		&mut self,
+	) -> impl IndexedParallelIterator<Item = (ByondXYZ, &mut Value)> + '_
+	where
+		Value: Send,
+	{
+		let block = self.block;
+		self.cells
+			.par_iter_mut()
+			.enumerate()
+			.map(move |(idx, value)| (block.xyz_at(idx).unwrap_or_default(), value))
+	}
+}
+
+impl<Value> Index<ByondXYZ> for Grid<Value> {
+	type Output = Value;
+
+	/// Panics if the coordinates are outside of the grid.
+	fn index(&self, xyz: ByondXYZ) -> &Self::Output {
+		self.get(xyz)
+			.unwrap_or_else(|| panic!("{xyz:?} is outside of the grid"))
+	}
+}
+
+impl<Value> IndexMut<ByondXYZ> for Grid<Value> {
+	/// Panics if the coordinates are outside of the grid.
+	fn index_mut(&mut self, xyz: ByondXYZ) -> &mut Self::Output {
+		self.get_mut(xyz)
+			.unwrap_or_else(|| panic!("{xyz:?} is outside of the grid"))
+	}
+}
+
+/// Parses a map for tests, where `#` is `false` and anything else is `true`,
+/// with the first line at the top.
+#[cfg(test)]
+pub(crate) fn ascii_map(map: &str) -> Grid<bool> {
+	let rows = map.lines().collect::<Vec<_>>();
+	let (width, height) = (rows[0].len() as i16, rows.len() as i16);
+	let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(width, height, 1));
+	Grid::from_fn(block, |xyz| {
+		rows[(height - xyz.y()) as usize].as_bytes()[(xyz.x() - 1) as usize] != b'#'
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn indexing() {
+		let block = ByondBlock::new(ByondXYZ::new(3, 3, 1), ByondXYZ::new(5, 4, 2));
+		let mut grid = Grid::from_fn(block, |xyz| xyz.x() * 100 + xyz.y() * 10 + xyz.z());
+		assert_eq!(grid.len(), 12);
+		assert_eq!(grid[ByondXYZ::new(4, 4, 2)], 442);
+		assert_eq!(grid.get(ByondXYZ::new(2, 3, 1)), None);
+		assert_eq!(grid.set(ByondXYZ::new(5, 3, 1), 0), Some(531));
+		assert_eq!(grid.as_slice()[2], 0);
+		assert!(grid.iter().all(|(xyz, value)| grid.get(xyz) == Some(value)));
+		assert!(
+			grid.par_iter()
+				.all(|(xyz, value)| grid.get(xyz) == Some(value))
+		);
+		let doubled = grid.map(|value| value * 2);
+		assert_eq!(doubled[ByondXYZ::new(3, 3, 1)], 662);
+		assert!(Grid::from_vec(block, vec![0; 11]).is_none());
+		assert!(!Grid::from_vec(block, vec![0; 12]).unwrap().is_empty());
+	}
+}
@@ -0,0 +1,320 @@
+// SPDX-License-Identifier: 0BSD
+use super::Grid;
+use crate::{
+	ByondBlock, ByondError, ByondResult, ByondValue, ByondXYZ, strid::lookup_string_id,
+	sync::call_on_main_thread, sys::u4c,
+};
+use rayon::prelude::*;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The state of a single tile in a [`GridSim`], which is read from and
+/// written back to a fixed set of turf vars.
+///
+/// ```no_run
+/// use meowtonin::{ByondResult, ByondValue, ToByond, grid::SimCell};
+///
+/// #[derive(Clone, PartialEq)]
+/// struct Heat {
+///     temperature: f32,
+///     conductivity: f32,
+/// }
+///
+/// impl SimCell for Heat {
+///     const VARS: &'static [&'static str] = &["temperature", "thermal_conductivity"];
+///
+///     fn read(values: &[ByondValue]) -> ByondResult<Self> {
+///         Ok(Self {
+///             temperature: values[0].get_number()?,
+///             conductivity: values[1].get_number()?,
+///         })
+///     }
+///
+///     fn write(&self) -> ByondResult<Vec<ByondValue>> {
+///         Ok(vec![self.temperature.to_byond()?, self.conductivity.to_byond()?])
+///     }
+/// }
+/// ```
+pub trait SimCell: Sized + Clone + PartialEq + Send + Sync + 'static {
+	/// The names of the turf vars each cell is made from.
+	const VARS: &'static [&'static str];
+
+	/// Creates a cell from the values of [`VARS`](Self::VARS), in the same
+	/// order.
+	fn read(values: &[ByondValue]) -> ByondResult<Self>;
+
+	/// Returns the values of [`VARS`](Self::VARS) for this cell, in the same
+	/// order.
+	fn write(&self) -> ByondResult<Vec<ByondValue>>;
+}
+
+/// Drives a simulation over the turfs in a block, such as atmos, fire spread
+/// or liquids.
+///
+/// The turf vars are read into a [`Grid`] of plain Rust [`SimCell`]s on the
+/// main thread. Each [`step`](Self::step) then runs across rayon's worker
+/// threads without touching DM at all, and [`commit`](Self::commit) writes
+/// only the vars that actually changed back to the turfs, in a single pass on
+/// the main thread.
+///
+/// Every method can be called from any thread - methods that need DM block
+/// until the main thread has run them with `Byond_ThreadSync`, so the whole
+/// simulation can be driven from a background thread:
+///
+/// ```no_run
+/// use meowtonin::{ByondBlock, ByondDir, ByondXYZ, grid::GridSim};
+/// use std::time::Duration;
+/// # use meowtonin::{ByondResult, ByondValue, ToByond, grid::SimCell};
+/// # #[derive(Clone, PartialEq)]
+/// # struct Heat(f32);
+/// # impl SimCell for Heat {
+/// #     const VARS: &'static [&'static str] = &["temperature"];
+/// #     fn read(values: &[ByondValue]) -> ByondResult<Self> {
+/// #         Ok(Self(values[0].get_number()?))
+/// #     }
+/// #     fn write(&self) -> ByondResult<Vec<ByondValue>> {
+/// #         Ok(vec![self.0.to_byond()?])
+/// #     }
+/// # }
+///
+/// # fn main() -> meowtonin::ByondResult<()> {
+/// let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(255, 255, 1));
+/// let Some(mut sim) = GridSim::<Heat>::capture(block)? else {
+///     return Ok(());
+/// };
+/// std::thread::spawn(move || -> meowtonin::ByondResult<()> {
+///     loop {
+///         sim.step(|grid, xyz, Heat(temperature)| {
+///             let neighbors = ByondDir::CARDINALS
+///                 .iter()
+///                 .filter_map(|dir| grid.get(xyz.step(*dir)))
+///                 .collect::<Vec<_>>();
+///             let total = neighbors.iter().map(|Heat(other)| other - temperature).sum::<f32>();
+///             Heat(temperature + total * 0.1 / neighbors.len().max(1) as f32)
+///         });
+///         // Spread the writes over as many ticks as needed, 5ms at a time.
+///         while !sim.commit(Duration::from_millis(5))? {
+///             std::thread::sleep(Duration::from_millis(50));
+///         }
+///     }
+/// });
+/// # Ok(())
+/// # }
+/// ```
+pub struct GridSim<Cell> {
+	name_ids: Arc<[u4c]>,
+	turfs: Arc<[ByondValue]>,
+	committed: Vec<Cell>,
+	grid: Grid<Cell>,
+	/// The changes being committed, and how many of them have been written.
+	pass: Option<(Arc<[Diff<Cell>]>, usize)>,
+}
+
+/// The index of a changed cell, its new state, and its last committed state.
+type Diff<Cell> = (usize, Cell, Cell);
+
+impl<Cell> GridSim<Cell>
+where
+	Cell: SimCell,
+{
+	/// Reads every cell in a block from its turf.
+	///
+	/// The turfs are fetched with a single `block()` call, and the names of
+	/// the vars are only looked up once. The block is clipped to the edges of
+	/// the map, and `None` is returned if it's entirely off-map.
+	pub fn capture(block: ByondBlock) -> ByondResult<Option<Self>> {
+		call_on_main_thread(move || {
+			let name_ids = Cell::VARS
+				.iter()
+				.map(|var| lookup_string_id(var).ok_or(ByondError::InvalidVariable))
+				.collect::<ByondResult<Arc<[u4c]>>>()?;
+			let Some(block) = ByondBlock::world()?.and_then(|world| block.intersect(&world)) else {
+				return Ok(None);
+			};
+			let turfs = Arc::<[ByondValue]>::from(block.turfs()?);
+			let cells = read_cells::<Cell>(&name_ids, &turfs)?;
+			let grid = Grid::from_vec(block, cells).ok_or(ByondError::UnknownByondError)?;
+			Ok(Some(Self {
+				name_ids,
+				turfs,
+				committed: grid.as_slice().to_vec(),
+				grid,
+				pass: None,
+			}))
+		})?
+	}
+
+	/// Reads every cell from its turf again, discarding any changes that
+	/// haven't been committed yet.
+	pub fn refresh(&mut self) -> ByondResult<()> {
+		let (name_ids, turfs) = (self.name_ids.clone(), self.turfs.clone());
+		let cells = call_on_main_thread(move || read_cells::<Cell>(&name_ids, &turfs))??;
+		self.grid =
+			Grid::from_vec(self.grid.block(), cells).ok_or(ByondError::UnknownByondError)?;
+		self.committed = self.grid.as_slice().to_vec();
+		self.pass = None;
+		Ok(())
+	}
+
+	/// Returns the block this simulation covers.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.grid.block()
+	}
+
+	/// Returns the current state of every cell.
+	#[inline]
+	pub fn grid(&self) -> &Grid<Cell> {
+		&self.grid
+	}
+
+	/// Returns the current state of every cell mutably, for changes that
+	/// don't fit into [`step`](Self::step).
+	#[inline]
+	pub fn grid_mut(&mut self) -> &mut Grid<Cell> {
+		&mut self.grid
+	}
+
+	/// Returns the turf at the given coordinates.
+	pub fn turf(&self, xyz: ByondXYZ) -> Option<&ByondValue> {
+		self.grid.block().index_of(&xyz).map(|idx| &self.turfs[idx])
+	}
+
+	/// Advances the simulation, computing the next state of every cell in
+	/// parallel.
+	///
+	/// `step` is given the whole grid as it was before this step, so cells can
+	/// read their neighbors without seeing any half-updated state. This
+	/// doesn't touch DM, and can be called as many times as needed between
+	/// commits.
+	pub fn step<Step>(&mut self, step: Step)
+	where
+		Step: Fn(&Grid<Cell>, ByondXYZ, &Cell) -> Cell + Sync,
+	{
+		let grid = &self.grid;
+		let next = grid
+			.par_iter()
+			.map(|(xyz, cell)| step(grid, xyz, cell))
+			.collect::<Vec<_>>();
+		self.grid
+			.as_mut_slice()
+			.par_iter_mut()
+			.zip(next)
+			.for_each(|(cell, next)| *cell = next);
+	}
+
+	/// Returns how many cells have changed since they were last committed or
+	/// read.
+	pub fn pending(&self) -> usize {
+		self.grid
+			.as_slice()
+			.par_iter()
+			.zip(&self.committed)
+			.filter(|(cell, committed)| cell != committed)
+			.count()
+	}
+
+	/// Writes changed cells back to their turfs on the main thread, returning
+	/// `true` once every change has been committed.
+	///
+	/// Only the vars whose values changed are written. If writing takes
+	/// longer than `budget`, the remaining cells are left for the next call,
+	/// which picks up where this one left off, so large changes can be spread
+	/// across several ticks. At least one cell is always written.
+	///
+	/// The changes are gathered once when a commit starts, so cells changed by
+	/// steps taken while it's spread across ticks are written by the next
+	/// commit.
+	pub fn commit(&mut self, budget: Duration) -> ByondResult<bool> {
+		let (diffs, cursor) = match self.pass.take() {
+			Some(pass) => pass,
+			None => {
+				let cells = self.grid.as_slice();
+				let diffs = (0..cells.len())
+					.into_par_iter()
+					.filter(|&idx| cells[idx] != self.committed[idx])
+					.map(|idx| (idx, cells[idx].clone(), self.committed[idx].clone()))
+					.collect::<Vec<_>>();
+				if diffs.is_empty() {
+					return Ok(true);
+				}
+				(Arc::from(diffs), 0)
+			}
+		};
+		let (name_ids, turfs, remaining) =
+			(self.name_ids.clone(), self.turfs.clone(), diffs.clone());
+		let (written, result) = call_on_main_thread(move || {
+			write_diffs(&name_ids, &turfs, &remaining[cursor..], budget)
+		})?;
+		for (idx, cell, _) in &diffs[cursor..cursor + written] {
+			self.committed[*idx].clone_from(cell);
+		}
+		let cursor = cursor + written;
+		let done = cursor == diffs.len();
+		if !done {
+			self.pass = Some((diffs, cursor));
+		}
+		result.map(|()| done)
+	}
+}
+
+fn read_cells<Cell>(name_ids: &[u4c], turfs: &[ByondValue]) -> ByondResult<Vec<Cell>>
+where
+	Cell: SimCell,
+{
+	let mut values = Vec::with_capacity(name_ids.len());
+	turfs
+		.iter()
+		.map(|turf| {
+			values.clear();
+			for &name_id in name_ids {
+				values.push(turf.read_var_by_id(name_id)?);
+			}
+			Cell::read(&values)
+		})
+		.collect()
+}
+
+/// Writes as many cells as possible within the budget, returning how many
+/// were written before running out of time or hitting an error.
+fn write_diffs<Cell>(
+	name_ids: &[u4c],
+	turfs: &[ByondValue],
+	diffs: &[Diff<Cell>],
+	budget: Duration,
+) -> (usize, ByondResult<()>)
+where
+	Cell: SimCell,
+{
+	let start = Instant::now();
+	for (written, (idx, cell, committed)) in diffs.iter().enumerate() {
+		if written > 0 && start.elapsed() >= budget {
+			return (written, Ok(()));
+		}
+		if let Err(err) = write_diff(name_ids, &turfs[*idx], cell, committed) {
+			return (written, Err(err));
+		}
+	}
+	(diffs.len(), Ok(()))
+}
+
+fn write_diff<Cell>(
+	name_ids: &[u4c],
+	turf: &ByondValue,
+	cell: &Cell,
+	committed: &Cell,
+) -> ByondResult<()>
+where
+	Cell: SimCell,
+{
+	let mut turf = turf.clone();
+	let values = name_ids.iter().zip(cell.write()?).zip(committed.write()?);
+	for ((&name_id, value), old_value) in values {
+		if value != old_value {
+			turf.write_var_by_id(name_id, value)?;
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: 0BSD
+use super::Grid;
+use crate::{
+	ByondBlock, ByondError, ByondResult, ByondValue, ByondXYZ, FromByond, ToByond,
+	strid::lookup_string_id, sys::u4c,
+};
+use std::ops::{Deref, DerefMut};
+
+/// A snapshot of a variable on every turf in a block, taken with
+/// [`snapshot_var`].
+///
+/// This dereferences to the underlying [`Grid`], which can be modified freely
+/// (including from other threads), then written back to the turfs with
+/// [`write_back`](Self::write_back).
+pub struct VarSnapshot<Value> {
+	name_id: u4c,
+	turfs: Vec<ByondValue>,
+	original: Vec<Value>,
+	grid: Grid<Value>,
+}
+
+/// Reads a variable from every turf in a block, returning it as a dense grid.
+///
+/// The turfs are fetched with a single `block()` call, and the name of the
+/// variable is only looked up once. The block is clipped to the edges of the
+/// map, and `None` is returned if it's entirely off-map.
+///
+/// ```no_run
+/// use meowtonin::{ByondBlock, ByondXYZ, grid::snapshot_var};
+///
+/// # fn main() -> meowtonin::ByondResult<()> {
+/// let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(255, 255, 1));
+/// if let Some(mut heat) = snapshot_var::<f32>(block, "temperature")? {
+///     heat.as_mut_slice().iter_mut().for_each(|temperature| *temperature *= 0.99);
+///     heat.write_back()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn snapshot_var<Value>(block: ByondBlock, var: &str) -> ByondResult<Option<VarSnapshot<Value>>>
+where
+	Value: FromByond + Clone,
+{
+	let name_id = lookup_string_id(var).ok_or(ByondError::InvalidVariable)?;
+	let Some(block) = ByondBlock::world()?.and_then(|world| block.intersect(&world)) else {
+		return Ok(None);
+	};
+	let turfs = block.turfs()?;
+	let cells = turfs
+		.iter()
+		.map(|turf| turf.read_var_by_id(name_id))
+		.collect::<ByondResult<Vec<Value>>>()?;
+	let grid = Grid::from_vec(block, cells).ok_or(ByondError::UnknownByondError)?;
+	Ok(Some(VarSnapshot {
+		name_id,
+		turfs,
+		original: grid.as_slice().to_vec(),
+		grid,
+	}))
+}
+
+impl<Value> VarSnapshot<Value> {
+	/// Returns the turf at the given coordinates.
+	pub fn turf(&self, xyz: ByondXYZ) -> Option<&ByondValue> {
+		self.grid.block().index_of(&xyz).map(|idx| &self.turfs[idx])
+	}
+
+	/// Iterates over the coordinates of every cell that has changed since the
+	/// snapshot was taken (or last written back).
+	pub fn changed(&self) -> impl Iterator<Item = ByondXYZ> + '_
+	where
+		Value: PartialEq,
+	{
+		self.grid
+			.iter()
+			.zip(&self.original)
+			.filter(|((_, value), original)| value != original)
+			.map(|((xyz, _), _)| xyz)
+	}
+
+	/// Writes every changed cell back to its turf, returning how many were
+	/// written.
+	pub fn write_back(&mut self) -> ByondResult<usize>
+	where
+		Value: ToByond + PartialEq + Clone,
+	{
+		let mut written = 0;
+		let cells = self.grid.as_slice();
+		for ((turf, value), original) in self.turfs.iter_mut().zip(cells).zip(&mut self.original) {
+			if value != original {
+				turf.write_var_by_id(self.name_id, value.to_byond()?)?;
+				original.clone_from(value);
+				written += 1;
+			}
+		}
+		Ok(written)
+	}
+
+	/// Discards the turfs and original values, returning only the grid.
+	pub fn into_grid(self) -> Grid<Value> {
+		self.grid
+	}
+}
+
+impl<Value> Deref for VarSnapshot<Value> {
+	type Target = Grid<Value>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.grid
+	}
+}
+
+impl<Value> DerefMut for VarSnapshot<Value> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.grid
+	}
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondBlock, ByondResult, ByondValue, ByondXYZ, Turf, misc};
+use rayon::prelude::*;
+
+/// A cache of every turf on the map, for looking up the turf at a set of
+/// coordinates without calling `locate()`.
+///
+/// Turfs are fetched with a single `block()` call per z-level. Turf references
+/// are tied to their coordinates, so changing a turf's type doesn't affect the
+/// cache, but changing the size of the map does - call
+/// [`refresh`](Self::refresh) after changing `world.maxx`, `world.maxy` or
+/// `world.maxz`.
+///
+/// Turfs aren't reference counted, so the cached values stay valid for as
+/// long as the map size doesn't change. The grid can be read from any thread,
+/// but the turfs themselves can still only be used on the main thread.
+#[derive(Clone, Default)]
+pub struct TurfGrid {
+	block: Option<ByondBlock>,
+	turfs: Vec<ByondValue>,
+}
+
+impl TurfGrid {
+	/// Creates an empty grid, which must be filled with
+	/// [`refresh`](Self::refresh).
+	pub const fn empty() -> Self {
+		Self {
+			block: None,
+			turfs: Vec::new(),
+		}
+	}
+
+	/// Creates a grid containing every turf on the map.
+	pub fn new() -> ByondResult<Self> {
+		let mut grid = Self::empty();
+		grid.refresh()?;
+		Ok(grid)
+	}
+
+	/// Updates the grid to match the current size of the map, returning
+	/// `false` if it hasn't changed.
+	///
+	/// Adding or removing z-levels only fetches or drops those z-levels,
+	/// while changing `world.maxx` or `world.maxy` fetches everything again.
+	pub fn refresh(&mut self) -> ByondResult<bool> {
+		let world = ByondBlock::world()?;
+		if world == self.block {
+			return Ok(false);
+		}
+		let Some(world) = world else {
+			self.invalidate();
+			return Ok(true);
+		};
+		let first_new_z = match self.block {
+			Some(old) if old.width() == world.width() && old.height() == world.height() => {
+				let kept = old.depth().min(world.depth());
+				self.turfs.truncate(kept * world.width() * world.height());
+				kept as i16 + 1
+			}
+			_ => {
+				self.turfs.clear();
+				1
+			}
+		};
+		self.turfs
+			.reserve(world.len().saturating_sub(self.turfs.len()));
+		for z in first_new_z..=world.max().z() {
+			let max = world.max();
+			self.turfs.extend(misc::block(
+				ByondXYZ::new(1, 1, z),
+				ByondXYZ::new(max.x(), max.y(), z),
+			)?);
+		}
+		self.block = Some(world);
+		Ok(true)
+	}
+
+	/// Empties the grid.
+	pub fn invalidate(&mut self) {
+		self.block = None;
+		self.turfs.clear();
+	}
+
+	/// Returns the block covered by the grid, or `None` if it's empty.
+	#[inline]
+	pub fn block(&self) -> Option<ByondBlock> {
+		self.block
+	}
+
+	/// Returns the number of cached turfs.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.turfs.len()
+	}
+
+	/// Returns if the grid is empty.
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.turfs.is_empty()
+	}
+
+	/// Returns the turf at the given coordinates, or `None` if they're
+	/// off-map.
+	pub fn get(&self, xyz: ByondXYZ) -> Option<&ByondValue> {
+		let idx = self.block?.index_of(&xyz)?;
+		self.turfs.get(idx)
+	}
+
+	/// Returns the turf at the given coordinates as a [`Turf`], or `None` if
+	/// they're off-map.
+	pub fn turf(&self, xyz: ByondXYZ) -> Option<Turf> {
+		// SAFETY: the grid only contains values returned by block(), which are
+		// always turfs.
+		self.get(xyz)
+			.map(|turf| unsafe { Turf::new_unchecked(turf.clone()) })
+	}
+
+	/// Returns every cached turf, in the same order as `block()`.
+	#[inline]
+	pub fn as_slice(&self) -> &[ByondValue] {
+		&self.turfs
+	}
+
+	/// Iterates over every turf along with its coordinates, in the same order
+	/// as `block()`.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (ByondXYZ, &ByondValue)> + '_ {
+		// an empty grid has no turfs, so the default block is never used
+		self.block.unwrap_or_default().iter().zip(&self.turfs)
+	}
+
+	/// Iterates over every turf along with its coordinates in parallel.
+	pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (ByondXYZ, &ByondValue)> + '_ {
+		let block = self.block.unwrap_or_default();
+		self.turfs.par_iter().enumerate().map(move |(idx, turf)| {
+			let xyz = block.xyz_at(idx).unwrap_or_default();
+			(xyz, turf)
+		})
+	}
+
+	/// Iterates over the turfs on a single z-level in parallel.
+	pub fn par_iter_z(
+		&self,
+		z: i16,
+	) -> impl IndexedParallelIterator<Item = (ByondXYZ, &ByondValue)> + '_ {
+		let block = self.block.unwrap_or_default();
+		let layer = block.width() * block.height();
+		let range = match self.block {
+			Some(block) if (1..=block.max().z()).contains(&z) => {
+				let start = (z - 1) as usize * layer;
+				start..start + layer
+			}
+			_ => 0..0,
+		};
+		let offset = range.start;
+		self.turfs[range]
+			.par_iter()
+			.enumerate()
+			.map(move |(idx, turf)| (block.xyz_at(offset + idx).unwrap_or_default(), turf))
+	}
+}
@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: 0BSD
+//! Checked wrappers around [`ByondValue`] for specific kinds of values.
+//!
+//! Each wrapper validates the type of the value once, when it's created. This
+//! means they can be used directly as `#[byond_fn]` parameters, and any type
+//! mismatches will be reported when the function is called, rather than
+//! somewhere deep within it.
+//!
+//! ```no_run
+//! #[meowtonin::byond_fn]
+//! pub fn get_ckey(mob: meowtonin::Mob) -> meowtonin::ByondResult<Option<String>> {
+//!     match mob.client()? {
+//!         Some(client) => client.ckey().map(Some),
+//!         None => Ok(None),
+//!     }
+//! }
+//! ```
+//!
+//! Wrappers dereference to their "parent" wrapper, so i.e every method on
+//! [`Atom`] and [`Datum`] can also be called on a [`Turf`].
+mod appearance;
+mod atom;
+mod client;
+mod datum;
+mod list;
+
+pub use self::{
+	appearance::{Filters, Overlays, VisContents},
+	atom::{Area, Atom, Mob, Obj, Turf},
+	client::Client,
+	datum::Datum,
+	list::{ByondList, ByondListIter},
+};
+
+/// Defines a checked wrapper type, either around a [`ByondValue`] or around
+/// another wrapper type.
+macro_rules! define_handle {
+	($(#[$meta:meta])* $name:ident(ByondValue), $expected:literal, $types:expr) => {
+		$(#[$meta])*
+		#[must_use]
+		#[repr(transparent)]
+		#[derive(Clone, PartialEq, Eq, Hash)]
+		pub struct $name($crate::ByondValue);
+
+		impl $name {
+			/// Wraps the given value without checking its type.
+			///
+			/// # Safety
+			/// The value must be one of the types in [`TYPES`](Self::TYPES).
+			#[inline]
+			pub const unsafe fn new_unchecked(value: $crate::ByondValue) -> Self {
+				Self(value)
+			}
+
+			/// Returns a reference to the wrapped value.
+			#[inline]
+			pub const fn as_value(&self) -> &$crate::ByondValue {
+				&self.0
+			}
+
+			/// Returns a mutable reference to the wrapped value.
+			#[inline]
+			pub fn as_value_mut(&mut self) -> &mut $crate::ByondValue {
+				&mut self.0
+			}
+
+			/// Unwraps the inner value.
+			#[inline]
+			pub fn into_inner(self) -> $crate::ByondValue {
+				self.0
+			}
+		}
+
+		impl ::std::ops::Deref for $name {
+			type Target = $crate::ByondValue;
+
+			fn deref(&self) -> &Self::Target {
+				&self.0
+			}
+		}
+
+		impl ::std::ops::DerefMut for $name {
+			fn deref_mut(&mut self) -> &mut Self::Target {
+				&mut self.0
+			}
+		}
+
+		$crate::handle::define_handle!(@common $name, $expected, $types);
+	};
+	($(#[$meta:meta])* $name:ident($parent:ident), $expected:literal, $types:expr) => {
+		$(#[$meta])*
+		#[must_use]
+		#[repr(transparent)]
+		#[derive(Clone, PartialEq, Eq, Hash)]
+		pub struct $name($parent);
+
+		impl $name {
+			/// Wraps the given value without checking its type.
+			///
+			/// # Safety
+			/// The value must be one of the types in [`TYPES`](Self::TYPES).
+			#[inline]
+			pub const unsafe fn new_unchecked(value: $crate::ByondValue) -> Self {
+				Self(unsafe { $parent::new_unchecked(value) })
+			}
+
+			/// Returns a reference to the wrapped value.
+			#[inline]
+			pub const fn as_value(&self) -> &$crate::ByondValue {
+				self.0.as_value()
+			}
+
+			/// Returns a mutable reference to the wrapped value.
+			#[inline]
+			pub fn as_value_mut(&mut self) -> &mut $crate::ByondValue {
+				self.0.as_value_mut()
+			}
+
+			/// Unwraps the inner value.
+			#[inline]
+			pub fn into_inner(self) -> $crate::ByondValue {
+				self.0.into_inner()
+			}
+		}
+
+		impl ::std::ops::Deref for $name {
+			type Target = $parent;
+
+			fn deref(&self) -> &Self::Target {
+				&self.0
+			}
+		}
+
+		impl ::std::ops::DerefMut for $name {
+			fn deref_mut(&mut self) -> &mut Self::Target {
+				&mut self.0
+			}
+		}
+
+		impl From<$name> for $parent {
+			#[inline]
+			fn from(value: $name) -> Self {
+				value.0
+			}
+		}
+
+		$crate::handle::define_handle!(@common $name, $expected, $types);
+	};
+	(@common $name:ident, $expected:literal, $types:expr) => {
+		impl $name {
+			/// The value types that can be wrapped by this type.
+			pub const TYPES: &[$crate::ByondValueType] = $types;
+
+			/// Wraps the given value, returning an error if it isn't the correct
+			/// type.
+			pub fn new(value: $crate::ByondValue) -> $crate::ByondResult<Self> {
+				if Self::matches(&value) {
+					Ok(unsafe { Self::new_unchecked(value) })
+				} else {
+					Err($crate::ByondError::InvalidConversion {
+						expected: ::std::borrow::Cow::Borrowed($expected),
+						got: value.get_type().name(),
+					})
+				}
+			}
+
+			/// Returns if the given value can be wrapped by this type.
+			#[inline]
+			pub fn matches(value: &$crate::ByondValue) -> bool {
+				Self::TYPES.contains(&value.get_type())
+			}
+		}
+
+		impl AsRef<$crate::ByondValue> for $name {
+			fn as_ref(&self) -> &$crate::ByondValue {
+				self.as_value()
+			}
+		}
+
+		impl From<$name> for $crate::ByondValue {
+			#[inline]
+			fn from(value: $name) -> Self {
+				value.into_inner()
+			}
+		}
+
+		impl TryFrom<$crate::ByondValue> for $name {
+			type Error = $crate::ByondError;
+
+			fn try_from(value: $crate::ByondValue) -> $crate::ByondResult<Self> {
+				Self::new(value)
+			}
+		}
+
+		impl $crate::FromByond for $name {
+			fn from_byond(value: $crate::ByondValue) -> $crate::ByondResult<Self> {
+				Self::new(value)
+			}
+		}
+
+		impl $crate::ToByond for $name {
+			fn to_byond(&self) -> $crate::ByondResult<$crate::ByondValue> {
+				Ok(self.as_value().clone())
+			}
+		}
+
+		impl $crate::ToByond for &$name {
+			fn to_byond(&self) -> $crate::ByondResult<$crate::ByondValue> {
+				Ok(self.as_value().clone())
+			}
+		}
+
+		impl ::std::fmt::Display for $name {
+			fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+				self.as_value().fmt(f)
+			}
+		}
+	};
+}
+
+pub(crate) use define_handle;
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondResult, ByondValue, ByondValueType, FromByond,
+	appearance::{Appearance, Filter},
+	handle::{ByondList, define_handle},
+};
+
+define_handle! {
+	/// A reference to an atom's or image's `overlays` or `underlays`.
+	///
+	/// Use [`extend`](ByondList::extend) and
+	/// [`remove_values`](ByondList::remove_values) to change many overlays
+	/// with a single call, or [`Atom::set_overlays`](crate::Atom::set_overlays)
+	/// to replace all of them.
+	Overlays(ByondList), "overlays list", ByondValueType::APPEARANCE_LIST_TYPES
+}
+
+define_handle! {
+	/// A reference to an atom's or image's `vis_contents`.
+	///
+	/// Use [`extend`](ByondList::extend) and
+	/// [`remove_values`](ByondList::remove_values) to change many atoms with
+	/// a single call, or
+	/// [`Atom::set_vis_contents`](crate::Atom::set_vis_contents) to replace
+	/// all of them.
+	VisContents(ByondList), "vis_contents list", ByondValueType::VIS_CONTENTS_TYPES
+}
+
+define_handle! {
+	/// A reference to an atom's or image's `filters`.
+	///
+	/// Use [`Atom::set_filters`](crate::Atom::set_filters) to replace every
+	/// filter at once.
+	Filters(ByondValue), "filters list", &[ByondValueType::Filters]
+}
+
+impl Overlays {
+	/// Reads a snapshot of every overlay's appearance.
+	pub fn appearances(&self) -> ByondResult<Vec<Appearance>> {
+		self.read_list()?
+			.into_iter()
+			.map(Appearance::from_byond)
+			.collect()
+	}
+
+	/// Removes every overlay.
+	///
+	/// Equivalent to `overlays.Cut()` in DM.
+	pub fn clear(&mut self) -> ByondResult<()> {
+		self.call::<_, ByondValue, _, ()>("Cut", [])
+	}
+}
+
+impl VisContents {
+	/// Removes everything from `vis_contents`.
+	///
+	/// Equivalent to `vis_contents.Cut()` in DM.
+	pub fn clear(&mut self) -> ByondResult<()> {
+		self.call::<_, ByondValue, _, ()>("Cut", [])
+	}
+}
+
+impl Filters {
+	/// Returns how many filters there are.
+	pub fn len(&self) -> ByondResult<usize> {
+		self.length()
+	}
+
+	/// Returns if there are no filters.
+	pub fn is_empty(&self) -> ByondResult<bool> {
+		self.len().map(|len| len == 0)
+	}
+
+	/// Returns the filter at the given index, whose vars can be changed or
+	/// animated like in DM.
+	///
+	/// Equivalent to `filters[idx + 1]` in DM.
+	pub fn get(&self, idx: usize) -> ByondResult<ByondValue> {
+		self.read_list_index(&ByondValue::new_num((idx + 1) as f32))
+	}
+
+	/// Adds a filter.
+	///
+	/// Equivalent to `filters += filter(...)` in DM, and requires the same
+	/// proc as [`Filter::create`].
+	pub fn push<Value>(&mut self, filter: Value) -> ByondResult<()>
+	where
+		Value: Into<Filter>,
+	{
+		self.extend([filter])
+	}
+
+	/// Adds all of the given filters with a single call, creating each of
+	/// them with [`Filter::create`].
+	pub fn extend<Values>(&mut self, filters: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: Into<Filter>,
+	{
+		let filters = filters
+			.into_iter()
+			.map(|filter| filter.into().create())
+			.collect::<ByondResult<Vec<_>>>()?;
+		if filters.is_empty() {
+			return Ok(());
+		}
+		self.call::<_, _, _, ()>("Add", filters)
+	}
+
+	/// Removes the filter at the given index.
+	///
+	/// Equivalent to `filters.Cut(idx + 1, idx + 2)` in DM.
+	pub fn remove(&mut self, idx: usize) -> ByondResult<()> {
+		let start = (idx + 1) as f32;
+		self.call::<_, _, _, ()>("Cut", [start, start + 1.0])
+	}
+
+	/// Removes every filter.
+	///
+	/// Equivalent to `filters.Cut()` in DM.
+	pub fn clear(&mut self) -> ByondResult<()> {
+		self.call::<_, ByondValue, _, ()>("Cut", [])
+	}
+}
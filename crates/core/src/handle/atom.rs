@@ -0,0 +1,185 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondError, ByondResult, ByondValue, ByondValueType, ByondXYZ, FromByond, ToByond,
+	appearance::{Appearance, Filter},
+	handle::{ByondList, Client, Datum, Filters, Overlays, VisContents, define_handle},
+};
+
+define_handle! {
+	/// A reference to any atom: a turf, obj, mob or area.
+	Atom(Datum), "atom", ByondValueType::ATOM_TYPES
+}
+
+define_handle! {
+	/// A reference to a turf.
+	Turf(Atom), "turf", &[ByondValueType::Turf]
+}
+
+define_handle! {
+	/// A reference to an obj.
+	Obj(Atom), "obj", &[ByondValueType::Obj]
+}
+
+define_handle! {
+	/// A reference to a mob.
+	Mob(Atom), "mob", &[ByondValueType::Mob]
+}
+
+define_handle! {
+	/// A reference to an area.
+	Area(Atom), "area", &[ByondValueType::Area]
+}
+
+impl Atom {
+	/// Returns the location of the atom, or `None` if it's in nullspace.
+	///
+	/// Equivalent to `src.loc` in DM.
+	pub fn loc(&self) -> ByondResult<Option<Atom>> {
+		self.read_var("loc")
+	}
+
+	/// Returns the tile coordinates of the atom.
+	///
+	/// If the atom is off-map, this will return `0,0,0`.
+	pub fn xyz(&self) -> ByondResult<ByondXYZ> {
+		self.as_value()
+			.xyz()
+			.ok_or_else(ByondError::get_last_byond_error)
+	}
+
+	/// Returns the turf the atom is on, or `None` if it's off-map.
+	///
+	/// Equivalent to `get_turf(src)` in DM.
+	pub fn turf(&self) -> ByondResult<Option<Turf>> {
+		self.xyz()?.turf()
+	}
+
+	/// Returns the contents of the atom.
+	pub fn contents(&self) -> ByondResult<ByondList> {
+		self.read_var("contents")
+	}
+
+	/// Reads a snapshot of the atom's appearance.
+	pub fn appearance(&self) -> ByondResult<Appearance> {
+		Appearance::from_byond(self.as_value().clone())
+	}
+
+	/// Returns the atom's overlays.
+	pub fn overlays(&self) -> ByondResult<Overlays> {
+		self.read_var("overlays")
+	}
+
+	/// Returns the atom's underlays.
+	pub fn underlays(&self) -> ByondResult<Overlays> {
+		self.read_var("underlays")
+	}
+
+	/// Returns the atom's `vis_contents`.
+	pub fn vis_contents(&self) -> ByondResult<VisContents> {
+		self.read_var("vis_contents")
+	}
+
+	/// Returns the atom's filters.
+	pub fn filters(&self) -> ByondResult<Filters> {
+		self.read_var("filters")
+	}
+
+	/// Replaces every overlay with the given values, with a single write.
+	///
+	/// Equivalent to `overlays = list(...)` in DM.
+	pub fn set_overlays<Values>(&mut self, values: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		self.write_list_var("overlays", values)
+	}
+
+	/// Replaces every underlay with the given values, with a single write.
+	///
+	/// Equivalent to `underlays = list(...)` in DM.
+	pub fn set_underlays<Values>(&mut self, values: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		self.write_list_var("underlays", values)
+	}
+
+	/// Replaces everything in `vis_contents` with the given atoms, with a
+	/// single write.
+	///
+	/// Equivalent to `vis_contents = list(...)` in DM.
+	pub fn set_vis_contents<Values>(&mut self, values: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		self.write_list_var("vis_contents", values)
+	}
+
+	/// Replaces every filter with the given filters, creating each of them
+	/// with [`Filter::create`], then setting them with a single write.
+	///
+	/// Equivalent to `filters = list(filter(...), ...)` in DM.
+	pub fn set_filters<Values>(&mut self, filters: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: Into<Filter>,
+	{
+		let filters = filters
+			.into_iter()
+			.map(|filter| filter.into().create())
+			.collect::<ByondResult<Vec<_>>>()?;
+		self.write_list_var("filters", filters)
+	}
+
+	fn write_list_var<Values>(&mut self, name: &str, values: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		let mut list = ByondValue::new_list()?;
+		list.write_list(
+			values
+				.into_iter()
+				.map(|value| value.to_byond())
+				.collect::<ByondResult<Vec<_>>>()?,
+		)?;
+		self.write_var(name, list)
+	}
+}
+
+impl Turf {
+	/// Returns the area this turf is in.
+	pub fn area(&self) -> ByondResult<Area> {
+		self.read_var("loc")
+	}
+}
+
+impl Mob {
+	/// Returns the client controlling this mob, if any.
+	pub fn client(&self) -> ByondResult<Option<Client>> {
+		self.read_var("client")
+	}
+
+	/// Returns the ckey of this mob, if it has one.
+	pub fn ckey(&self) -> ByondResult<Option<String>> {
+		self.read_var("ckey")
+	}
+}
+
+macro_rules! impl_datum_upcast {
+	($($name:ident),*) => {
+		$(
+			impl From<$name> for Datum {
+				#[inline]
+				fn from(value: $name) -> Self {
+					Atom::from(value).into()
+				}
+			}
+		)*
+	};
+}
+
+impl_datum_upcast!(Turf, Obj, Mob, Area);
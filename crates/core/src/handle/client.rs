@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondResult, ByondValueType,
+	handle::{Mob, define_handle},
+};
+
+define_handle! {
+	/// A reference to a client.
+	Client(ByondValue), "client", &[ByondValueType::Client]
+}
+
+impl Client {
+	/// Returns the ckey of the client.
+	pub fn ckey(&self) -> ByondResult<String> {
+		self.read_var("ckey")
+	}
+
+	/// Returns the key of the client.
+	pub fn key(&self) -> ByondResult<String> {
+		self.read_var("key")
+	}
+
+	/// Returns the mob the client is controlling, if any.
+	pub fn mob(&self) -> ByondResult<Option<Mob>> {
+		self.read_var("mob")
+	}
+}
@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondResult, ByondValueType, TypePath, handle::define_handle};
+
+define_handle! {
+	/// A reference to any datum, including atoms and images.
+	Datum(ByondValue), "datum", ByondValueType::DATUM_TYPES
+}
+
+impl Datum {
+	/// Returns the typepath of the datum.
+	pub fn typepath(&self) -> ByondResult<TypePath> {
+		self.read_var("type")
+	}
+}
@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond, byond,
+	handle::define_handle,
+};
+use std::ops::{Bound, RangeBounds};
+
+define_handle! {
+	/// A reference to a list, including special lists such as `contents` or
+	/// `vars`.
+	///
+	/// Unlike DM, indices are zero-based, and are converted to DM's one-based
+	/// indices internally. Note that `alist`s treat numbers as keys rather
+	/// than indices, so use the `_assoc` methods with those.
+	ByondList(ByondValue), "list", ByondValueType::ALL_LIST_TYPES
+}
+
+impl ByondList {
+	/// Creates a new, empty list.
+	pub fn new_empty() -> ByondResult<Self> {
+		ByondValue::new_list().map(|list| unsafe { Self::new_unchecked(list) })
+	}
+
+	/// Creates a new list containing the given values.
+	pub fn from_values<Values>(values: Values) -> ByondResult<Self>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		let values = values
+			.into_iter()
+			.map(|value| value.to_byond())
+			.collect::<ByondResult<Vec<_>>>()?;
+		let mut list = Self::new_empty()?;
+		list.write_list(values)?;
+		Ok(list)
+	}
+
+	/// Returns the length of the list.
+	pub fn len(&self) -> ByondResult<usize> {
+		self.length()
+	}
+
+	/// Returns if the list is empty.
+	pub fn is_empty(&self) -> ByondResult<bool> {
+		self.len().map(|len| len == 0)
+	}
+
+	/// Reads all values of the list at once.
+	pub fn to_vec(&self) -> ByondResult<Vec<ByondValue>> {
+		self.read_list()
+	}
+
+	/// Returns the value at the given index.
+	///
+	/// Equivalent to `list[idx + 1]` in DM.
+	pub fn get<Value>(&self, idx: usize) -> ByondResult<Value>
+	where
+		Value: FromByond,
+	{
+		self.read_list_index(&dm_index(idx))
+	}
+
+	/// Sets the value at the given index.
+	///
+	/// Equivalent to `list[idx + 1] = value` in DM.
+	pub fn set<Value>(&mut self, idx: usize, value: Value) -> ByondResult<()>
+	where
+		Value: ToByond,
+	{
+		self.write_list_index(dm_index(idx), value)
+	}
+
+	/// Returns the value associated with the given key.
+	///
+	/// Equivalent to `list[key]` in DM.
+	pub fn get_assoc<Key, Value>(&self, key: &Key) -> ByondResult<Value>
+	where
+		Key: ToByond,
+		Value: FromByond,
+	{
+		self.read_list_index(key)
+	}
+
+	/// Associates the given value with the given key.
+	///
+	/// Equivalent to `list[key] = value` in DM.
+	pub fn set_assoc<Key, Value>(&mut self, key: Key, value: Value) -> ByondResult<()>
+	where
+		Key: ToByond,
+		Value: ToByond,
+	{
+		self.write_list_index(key, value)
+	}
+
+	/// Returns the first value of the list, or `None` if it's empty.
+	pub fn first(&self) -> ByondResult<Option<ByondValue>> {
+		match self.len()? {
+			0 => Ok(None),
+			_ => self.get(0).map(Some),
+		}
+	}
+
+	/// Returns the last value of the list, or `None` if it's empty.
+	pub fn last(&self) -> ByondResult<Option<ByondValue>> {
+		match self.len()? {
+			0 => Ok(None),
+			len => self.get(len - 1).map(Some),
+		}
+	}
+
+	/// Appends a value to the end of the list.
+	///
+	/// Unlike `list.Add()` in DM, lists are added as-is, rather than having
+	/// their contents added.
+	pub fn push<Value>(&mut self, value: Value) -> ByondResult<()>
+	where
+		Value: ToByond,
+	{
+		self.push_list(value.to_byond()?)
+	}
+
+	/// Removes the last value of the list and returns it, or `None` if it's
+	/// empty.
+	pub fn pop(&mut self) -> ByondResult<Option<ByondValue>> {
+		self.pop_list()
+	}
+
+	/// Inserts a value at the given index, shifting everything after it to
+	/// the right.
+	///
+	/// Equivalent to `list.Insert(idx + 1, value)` in DM, except lists are
+	/// inserted as-is, rather than having their contents inserted.
+	pub fn insert<Value>(&mut self, idx: usize, value: Value) -> ByondResult<()>
+	where
+		Value: ToByond,
+	{
+		let value = as_single_item(value.to_byond()?)?;
+		self.call::<_, _, _, ()>("Insert", [dm_index(idx), value])
+	}
+
+	/// Removes the value at the given index and returns it, shifting
+	/// everything after it to the left.
+	pub fn remove(&mut self, idx: usize) -> ByondResult<ByondValue> {
+		let value = self.get(idx)?;
+		self.cut(idx..=idx)?;
+		Ok(value)
+	}
+
+	/// Removes the last occurrence of the given value from the list,
+	/// returning if anything was removed.
+	///
+	/// Equivalent to `list.Remove(value)` in DM.
+	pub fn remove_value<Value>(&mut self, value: &Value) -> ByondResult<bool>
+	where
+		Value: ToByond,
+	{
+		let value = as_single_item(value.to_byond()?)?;
+		self.call("Remove", [value])
+	}
+
+	/// Removes all occurrences of the given value from the list, returning
+	/// how many were removed.
+	///
+	/// Equivalent to `list.RemoveAll(value)` in DM.
+	pub fn remove_all<Value>(&mut self, value: &Value) -> ByondResult<usize>
+	where
+		Value: ToByond,
+	{
+		let value = as_single_item(value.to_byond()?)?;
+		self.call("RemoveAll", [value])
+	}
+
+	/// Removes all values within the given range.
+	///
+	/// Equivalent to `list.Cut(start, end)` in DM.
+	pub fn cut<Range>(&mut self, range: Range) -> ByondResult<()>
+	where
+		Range: RangeBounds<usize>,
+	{
+		let (start, end) = dm_range(range);
+		self.call::<_, _, _, ()>("Cut", [start, end])
+	}
+
+	/// Returns a new list containing the values within the given range,
+	/// including any associated values.
+	///
+	/// Equivalent to `list.Copy(start, end)` in DM.
+	pub fn copy<Range>(&self, range: Range) -> ByondResult<Self>
+	where
+		Range: RangeBounds<usize>,
+	{
+		let (start, end) = dm_range(range);
+		self.call("Copy", [start, end])
+	}
+
+	/// Returns the index of the first occurrence of the given value, or
+	/// `None` if the list doesn't contain it.
+	///
+	/// Equivalent to `list.Find(value)` in DM.
+	pub fn find<Value>(&self, value: &Value) -> ByondResult<Option<usize>>
+	where
+		Value: ToByond,
+	{
+		let idx: usize = self.call("Find", [value.to_byond()?])?;
+		Ok(idx.checked_sub(1))
+	}
+
+	/// Returns if the list contains the given value.
+	///
+	/// Equivalent to `value in list` in DM.
+	pub fn contains<Value>(&self, value: &Value) -> ByondResult<bool>
+	where
+		Value: ToByond,
+	{
+		self.find(value).map(|idx| idx.is_some())
+	}
+
+	/// Swaps the values at the given indices, along with any associated
+	/// values.
+	///
+	/// Equivalent to `list.Swap(a + 1, b + 1)` in DM.
+	pub fn swap(&mut self, a: usize, b: usize) -> ByondResult<()> {
+		self.call::<_, _, _, ()>("Swap", [dm_index(a), dm_index(b)])
+	}
+
+	/// Replaces the values within the given range with the given values.
+	///
+	/// Equivalent to `list.Splice(start, end, values...)` in DM, except lists
+	/// are inserted as-is, rather than having their contents inserted.
+	pub fn splice<Range, Values>(&mut self, range: Range, values: Values) -> ByondResult<()>
+	where
+		Range: RangeBounds<usize>,
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		let (start, end) = dm_range(range);
+		let mut args = vec![start, end];
+		for value in values {
+			args.push(as_single_item(value.to_byond()?)?);
+		}
+		self.call::<_, _, _, ()>("Splice", args)
+	}
+
+	/// Appends all of the given values to the end of the list.
+	///
+	/// Unlike `list.Add()` in DM, lists are added as-is, rather than having
+	/// their contents added.
+	pub fn extend<Values>(&mut self, values: Values) -> ByondResult<()>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		let values = values
+			.into_iter()
+			.map(|value| as_single_item(value.to_byond()?))
+			.collect::<ByondResult<Vec<_>>>()?;
+		if values.is_empty() {
+			return Ok(());
+		}
+		self.call::<_, _, _, ()>("Add", values)
+	}
+
+	/// Removes the last occurrence of each of the given values from the list,
+	/// returning if anything was removed.
+	///
+	/// Equivalent to `list.Remove(values...)` in DM, except lists are removed
+	/// as-is, rather than having their contents removed.
+	pub fn remove_values<Values>(&mut self, values: Values) -> ByondResult<bool>
+	where
+		Values: IntoIterator,
+		Values::Item: ToByond,
+	{
+		let values = values
+			.into_iter()
+			.map(|value| as_single_item(value.to_byond()?))
+			.collect::<ByondResult<Vec<_>>>()?;
+		if values.is_empty() {
+			return Ok(false);
+		}
+		self.call("Remove", values)
+	}
+
+	/// Removes everything from the list.
+	pub fn clear(&mut self) -> ByondResult<()> {
+		map_byond_error!(byond().Byond_WriteList(&self.as_value().0, std::ptr::null(), 0))
+	}
+}
+
+impl IntoIterator for ByondList {
+	type Item = ByondResult<ByondValue>;
+	type IntoIter = ByondListIter;
+
+	/// Reads the entire list at once, and iterates over the values.
+	///
+	/// If the list can't be read, this yields the error once and then ends.
+	fn into_iter(self) -> Self::IntoIter {
+		(&self).into_iter()
+	}
+}
+
+impl IntoIterator for &ByondList {
+	type Item = ByondResult<ByondValue>;
+	type IntoIter = ByondListIter;
+
+	/// Reads the entire list at once, and iterates over the values.
+	///
+	/// If the list can't be read, this yields the error once and then ends.
+	fn into_iter(self) -> Self::IntoIter {
+		ByondListIter(self.read_list().map(Vec::into_iter).map_err(Some))
+	}
+}
+
+/// An iterator over the values of a [`ByondList`], which are all read at once
+/// when it's created.
+pub struct ByondListIter(Result<std::vec::IntoIter<ByondValue>, Option<ByondError>>);
+
+impl Iterator for ByondListIter {
+	type Item = ByondResult<ByondValue>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match &mut self.0 {
+			Ok(values) => values.next().map(Ok),
+			Err(err) => err.take().map(Err),
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match &self.0 {
+			Ok(values) => values.size_hint(),
+			Err(err) => (err.is_some() as usize, Some(err.is_some() as usize)),
+		}
+	}
+}
+
+/// Converts a zero-based index into a DM list index.
+#[inline]
+fn dm_index(idx: usize) -> ByondValue {
+	ByondValue::new_num((idx + 1) as f32)
+}
+
+/// Converts a zero-based range into DM's `start, end` list proc arguments,
+/// where an end of 0 means the end of the list.
+fn dm_range<Range>(range: Range) -> (ByondValue, ByondValue)
+where
+	Range: RangeBounds<usize>,
+{
+	let (start, end) = dm_range_bounds(range);
+	(
+		ByondValue::new_num(start as f32),
+		ByondValue::new_num(end as f32),
+	)
+}
+
+fn dm_range_bounds<Range>(range: Range) -> (usize, usize)
+where
+	Range: RangeBounds<usize>,
+{
+	let start = match range.start_bound() {
+		Bound::Included(&start) => start + 1,
+		Bound::Excluded(&start) => start + 2,
+		Bound::Unbounded => 1,
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&end) => end + 2,
+		Bound::Excluded(&end) => end + 1,
+		Bound::Unbounded => 0,
+	};
+	(start, end)
+}
+
+/// List procs such as `Add()` add the contents of lists passed to them,
+/// rather than the list itself - but they only unwrap one level, so wrapping
+/// a list in another list makes it get added as-is.
+fn as_single_item(value: ByondValue) -> ByondResult<ByondValue> {
+	if !value.is_list() {
+		return Ok(value);
+	}
+	let mut wrapper = ByondValue::new_list()?;
+	wrapper.write_list([value])?;
+	Ok(wrapper)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ranges() {
+		assert_eq!(dm_range_bounds(..), (1, 0));
+		assert_eq!(dm_range_bounds(2..), (3, 0));
+		assert_eq!(dm_range_bounds(..3), (1, 4));
+		assert_eq!(dm_range_bounds(..=3), (1, 5));
+		assert_eq!(dm_range_bounds(1..3), (2, 4));
+		assert_eq!(dm_range_bounds(4..=4), (5, 6));
+		assert_eq!(
+			dm_range_bounds((Bound::Excluded(0), Bound::Excluded(2))),
+			(2, 3)
+		);
+	}
+}
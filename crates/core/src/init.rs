@@ -19,6 +19,8 @@
 pub fn do_init() {
 	// Clear string ID cache, just in case anything's changed.
 	crate::strid::STRID_CACHE.pin().clear();
+	crate::typepath::TYPESOF_CACHE.pin().clear();
+	crate::typepath::TYPESOF_MISSING.store(false, std::sync::atomic::Ordering::Relaxed);
 
 	// Run any custom initialization functions.
 	for func in inventory::iter::<InitFunc> {
@@ -9,19 +9,34 @@
 #![allow(unused_unsafe, clippy::missing_safety_doc)]
 #![cfg_attr(debug_assertions, allow(dead_code))]
 
+pub mod appearance;
+pub mod block;
 pub mod byond;
+pub mod color;
+pub mod dir;
 #[macro_use]
 pub mod error;
 pub mod from;
+pub mod geometry;
+pub mod grid;
+pub mod handle;
 pub mod init;
+pub mod los;
+pub mod mapgen;
+pub mod matrix;
 pub mod misc;
 pub mod panic;
+pub mod path;
 pub mod pixloc;
 pub mod proc;
+pub mod regions;
+pub mod spatial;
 pub mod strid;
 pub mod sync;
 pub mod to;
+pub mod typepath;
 pub mod value;
+pub mod vector;
 pub mod xyz;
 
 pub mod sys {
@@ -29,13 +44,28 @@ pub mod sys {
 }
 
 pub use crate::{
+	block::ByondBlock,
 	byond::byond,
+	color::{ByondColor, ColorMatrix},
+	dir::ByondDir,
 	error::{ByondError, ByondResult},
 	from::FromByond,
+	handle::{
+		Area, Atom, ByondList, Client, Datum, Filters, Mob, Obj, Overlays, Turf, VisContents,
+	},
+	matrix::ByondMatrix,
+	pixloc::ByondPixLoc,
 	proc::call_global,
-	sys::ByondVersion,
+	sys::{ByondVersion, Capabilities},
 	to::ToByond,
-	value::{ByondValue, reference::RcByondValue, typecheck::ByondValueType},
+	typepath::TypePath,
+	value::{
+		ByondValue,
+		alist::Alist,
+		reference::{RcByondValue, WeakByondValue},
+		typecheck::ByondValueType,
+	},
+	vector::ByondVector,
 	xyz::ByondXYZ,
 };
 pub use inventory;
@@ -63,6 +93,25 @@ macro_rules! byondval {
 	};
 }
 
+/// Ensures that the running version of BYOND is at least the given version
+/// and build, returning an [`Unsupported`](ByondError::Unsupported) error
+/// from the calling function otherwise.
+///
+/// ```no_run
+/// #[meowtonin::byond_fn]
+/// pub fn do_fancy_thing() -> meowtonin::ByondResult<()> {
+///     meowtonin::require_byond!(516, 1650);
+///     // ...
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! require_byond {
+	($version:expr, $build:expr) => {
+		$crate::require_byond_version($version, $build)?
+	};
+}
+
 /// # Safety
 /// Don't pass in a null argv pointer please god
 /// Just give this what BYOND gives you and pray for the best
@@ -82,6 +131,25 @@ pub fn byond_version() -> ByondVersion {
 	byond().get_version()
 }
 
+/// Returns the optional functionality supported by the running version of
+/// BYOND.
+pub fn capabilities() -> Capabilities {
+	byond().capabilities()
+}
+
+/// Returns an [`Unsupported`](ByondError::Unsupported) error if the running
+/// version of BYOND is older than the given version and build.
+///
+/// See also [`require_byond!`], which returns the error from the calling
+/// function.
+pub fn require_byond_version(version: sys::u4c, build: sys::u4c) -> ByondResult<()> {
+	if byond_version() >= (ByondVersion { version, build }) {
+		Ok(())
+	} else {
+		Err(ByondError::unsupported("this library", version, build))
+	}
+}
+
 /// Returns the version number the current .dmb was built with
 pub fn dmb_version() -> sys::u4c {
 	unsafe { byond().Byond_GetDMBVersion() }
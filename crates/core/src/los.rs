@@ -0,0 +1,435 @@
+// SPDX-License-Identifier: 0BSD
+//! Line of sight, visibility and raycasting over a snapshot of turf opacity.
+//!
+//! An [`OpacityGrid`] is captured once on the main thread, after which every
+//! function in this module is plain computation over it, so they can all be
+//! run on worker threads.
+//!
+//! ```no_run
+//! #[meowtonin::byond_fn]
+//! pub fn visible_turfs(
+//!     eye: meowtonin::ByondXYZ,
+//!     radius: u16,
+//! ) -> meowtonin::ByondResult<meowtonin::los::Visibility> {
+//!     use meowtonin::los::*;
+//!
+//!     let grid = OpacityGrid::capture(meowtonin::ByondBlock::around(eye, radius))?;
+//!     Ok(view(&grid, eye, radius))
+//! }
+//! ```
+mod fov;
+
+pub use self::fov::field_of_view;
+
+use crate::{
+	ByondBlock, ByondDir, ByondPixLoc, ByondResult, ByondValue, ByondXYZ, ToByond, geometry::line,
+};
+
+/// A snapshot of which tiles in a block block vision.
+///
+/// Tiles outside of the block are treated as opaque.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpacityGrid {
+	block: ByondBlock,
+	opaque: Vec<bool>,
+}
+
+impl OpacityGrid {
+	/// Creates a grid where every tile has the same opacity.
+	pub fn new(block: ByondBlock, opaque: bool) -> Self {
+		Self {
+			block,
+			opaque: vec![opaque; block.len()],
+		}
+	}
+
+	/// Creates a grid by calling `opaque` for every tile in the block.
+	pub fn from_fn<Func>(block: ByondBlock, opaque: Func) -> Self
+	where
+		Func: FnMut(ByondXYZ) -> bool,
+	{
+		Self {
+			block,
+			opaque: block.iter().map(opaque).collect(),
+		}
+	}
+
+	/// Snapshots the map, where a tile is opaque if the turf or anything in
+	/// its contents has `opacity` set, like DM's own visibility checks.
+	///
+	/// Must be called on the main thread.
+	pub fn capture(block: ByondBlock) -> ByondResult<Self> {
+		Self::capture_with(block, |turf| {
+			if turf.read_var::<_, bool>("opacity")? {
+				return Ok(true);
+			}
+			let contents: ByondValue = turf.read_var("contents")?;
+			for atom in contents.read_list()? {
+				if atom.read_var::<_, bool>("opacity")? {
+					return Ok(true);
+				}
+			}
+			Ok(false)
+		})
+	}
+
+	/// Snapshots the map by calling `opaque` with every turf in the block,
+	/// which are all fetched with a single `block()` call.
+	///
+	/// Tiles outside of the map are opaque. Must be called on the main
+	/// thread.
+	pub fn capture_with<Func>(block: ByondBlock, mut opaque: Func) -> ByondResult<Self>
+	where
+		Func: FnMut(&ByondValue) -> ByondResult<bool>,
+	{
+		let mut grid = Self::new(block, true);
+		let Some(on_map) = ByondBlock::world()?.and_then(|world| block.intersect(&world)) else {
+			return Ok(grid);
+		};
+		for (xyz, turf) in on_map.iter().zip(on_map.turfs()?) {
+			grid.set(xyz, opaque(&turf)?);
+		}
+		Ok(grid)
+	}
+
+	/// Returns the block this grid covers.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.block
+	}
+
+	/// Returns if the given tile blocks vision.
+	pub fn is_opaque(&self, xyz: ByondXYZ) -> bool {
+		self.block.index_of(&xyz).is_none_or(|idx| self.opaque[idx])
+	}
+
+	/// Sets whether the given tile blocks vision, returning `false` if it's
+	/// outside of the grid.
+	pub fn set(&mut self, xyz: ByondXYZ, opaque: bool) -> bool {
+		match self.block.index_of(&xyz) {
+			Some(idx) => {
+				self.opaque[idx] = opaque;
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+/// A set of visible tiles around a single point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visibility {
+	block: ByondBlock,
+	visible: Vec<bool>,
+}
+
+impl Visibility {
+	fn new(eye: ByondXYZ, radius: u16) -> Self {
+		let block = ByondBlock::around(eye, radius);
+		Self {
+			block,
+			visible: vec![false; block.len()],
+		}
+	}
+
+	fn set(&mut self, xyz: ByondXYZ, visible: bool) {
+		if let Some(idx) = self.block.index_of(&xyz) {
+			self.visible[idx] = visible;
+		}
+	}
+
+	/// Returns the block containing every tile that could be visible.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.block
+	}
+
+	/// Returns if the given tile is visible.
+	pub fn contains(&self, xyz: &ByondXYZ) -> bool {
+		self.block
+			.index_of(xyz)
+			.is_some_and(|idx| self.visible[idx])
+	}
+
+	/// Iterates over every visible tile, in the same order as `block()`.
+	pub fn iter(&self) -> impl Iterator<Item = ByondXYZ> + '_ {
+		self.block
+			.iter()
+			.zip(&self.visible)
+			.filter_map(|(xyz, visible)| visible.then_some(xyz))
+	}
+
+	/// Returns the number of visible tiles.
+	pub fn len(&self) -> usize {
+		self.visible.iter().filter(|visible| **visible).count()
+	}
+
+	/// Returns if no tiles are visible.
+	pub fn is_empty(&self) -> bool {
+		!self.visible.contains(&true)
+	}
+}
+
+impl ToByond for Visibility {
+	/// Converts into a list of the visible turfs.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		self.iter().collect::<Vec<_>>().to_byond()
+	}
+}
+
+/// Computes the tiles visible from `eye` within `radius` tiles, like
+/// `view(radius, eye)` in DM, ignoring lighting and sight flags.
+///
+/// This follows BYOND's own algorithm: a tile is visible if either a
+/// "diagonal, then straight" or a "straight, then diagonal" walk from the eye
+/// reaches it without passing through an opaque tile. Opaque tiles facing a
+/// visible, transparent tile are then revealed as well, so the faces of walls
+/// are seen.
+pub fn view(grid: &OpacityGrid, eye: ByondXYZ, radius: u16) -> Visibility {
+	let mut visibility = Visibility::new(eye, radius);
+	let candidates = visibility
+		.block
+		.intersect(&grid.block())
+		.into_iter()
+		.flat_map(ByondBlock::into_iter);
+	for xyz in candidates.clone() {
+		if xyz == eye || clear_walk(grid, eye, xyz, true) || clear_walk(grid, eye, xyz, false) {
+			visibility.set(xyz, true);
+		}
+	}
+	let revealed = candidates
+		.filter(|xyz| grid.is_opaque(*xyz) && !visibility.contains(xyz))
+		.filter(|xyz| {
+			let towards_eye = xyz.dir_to(eye).horizontal();
+			ByondDir::HORIZONTAL
+				.into_iter()
+				.filter(|dir| towards_eye.contains(*dir))
+				.map(|dir| xyz.step(dir))
+				.any(|neighbor| visibility.contains(&neighbor) && !grid.is_opaque(neighbor))
+		})
+		.collect::<Vec<_>>();
+	for xyz in revealed {
+		visibility.set(xyz, true);
+	}
+	visibility
+}
+
+/// Like [`view`], but without the eye's own tile, like `oview(radius, eye)`
+/// in DM.
+pub fn oview(grid: &OpacityGrid, eye: ByondXYZ, radius: u16) -> Visibility {
+	let mut visibility = view(grid, eye, radius);
+	visibility.set(eye, false);
+	visibility
+}
+
+/// Walks from `from` to `to`, moving diagonally first (or straight first),
+/// returning if every tile between them is transparent.
+fn clear_walk(grid: &OpacityGrid, from: ByondXYZ, to: ByondXYZ, diagonal_first: bool) -> bool {
+	let mut current = from;
+	loop {
+		let (dx, dy) = (to.x() - current.x(), to.y() - current.y());
+		let diagonal = if diagonal_first {
+			dx != 0 && dy != 0
+		} else {
+			dx.abs() == dy.abs()
+		};
+		let step = if diagonal {
+			current.dir_to(to).horizontal()
+		} else if dx.abs() > dy.abs() {
+			current.dir_to(to) & (ByondDir::EAST | ByondDir::WEST)
+		} else {
+			current.dir_to(to) & (ByondDir::NORTH | ByondDir::SOUTH)
+		};
+		current = current.step(step);
+		if current == to {
+			return true;
+		}
+		if grid.is_opaque(current) {
+			return false;
+		}
+	}
+}
+
+/// Returns the first opaque tile on the line from `from` to `to` (including
+/// `to`, but not `from`), or `None` if the line is clear.
+pub fn raycast(grid: &OpacityGrid, from: ByondXYZ, to: ByondXYZ) -> Option<ByondXYZ> {
+	line(from, to).skip(1).find(|xyz| grid.is_opaque(*xyz))
+}
+
+/// Returns if there are no opaque tiles on the line between two tiles. Either
+/// end may be opaque.
+pub fn line_of_sight(grid: &OpacityGrid, from: ByondXYZ, to: ByondXYZ) -> bool {
+	from.z() == to.z() && raycast(grid, from, to).is_none_or(|hit| hit == to)
+}
+
+/// Where a ray hit an opaque tile.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit {
+	/// The opaque tile that was hit.
+	pub xyz: ByondXYZ,
+	/// The point where the ray entered the tile.
+	pub pixloc: ByondPixLoc,
+}
+
+/// Casts a ray between two pixel positions, returning the first opaque tile
+/// it enters (not counting the tile it starts in), or `None` if it reaches
+/// `to` without hitting one.
+///
+/// `icon_size` is the size of a tile in pixels, see
+/// [`world_icon_size`](crate::pixloc::world_icon_size). The ray is cast on the
+/// z-level of `from`.
+pub fn raycast_pixels(
+	grid: &OpacityGrid,
+	from: ByondPixLoc,
+	to: ByondPixLoc,
+	icon_size: (u16, u16),
+) -> Option<RayHit> {
+	let (tile_width, tile_height) = (icon_size.0 as f32, icon_size.1 as f32);
+	// positions in tiles, starting from 0
+	let (start_x, start_y) = (
+		(from.x() - 1.0) / tile_width,
+		(from.y() - 1.0) / tile_height,
+	);
+	let (dx, dy) = (
+		(to.x() - from.x()) / tile_width,
+		(to.y() - from.y()) / tile_height,
+	);
+	let mut current = from.to_xyz(icon_size);
+	let end = to.to_xyz(icon_size);
+	let end = ByondXYZ::new(end.x(), end.y(), from.z());
+
+	// distances along the ray (from 0 to 1) to the next tile boundary on each
+	// axis, and between boundaries
+	let axis = |start: f32, delta: f32| -> (f32, f32) {
+		if delta > 0.0 {
+			((start.floor() + 1.0 - start) / delta, 1.0 / delta)
+		} else if delta < 0.0 {
+			((start - start.floor()) / -delta, 1.0 / -delta)
+		} else {
+			(f32::INFINITY, f32::INFINITY)
+		}
+	};
+	let (mut next_x, step_x) = axis(start_x, dx);
+	let (mut next_y, step_y) = axis(start_y, dy);
+	let dir_x = if dx > 0.0 {
+		ByondDir::EAST
+	} else {
+		ByondDir::WEST
+	};
+	let dir_y = if dy > 0.0 {
+		ByondDir::NORTH
+	} else {
+		ByondDir::SOUTH
+	};
+
+	while current != end {
+		let distance;
+		if next_x < next_y {
+			current = current.step(dir_x);
+			distance = next_x;
+			next_x += step_x;
+		} else {
+			current = current.step(dir_y);
+			distance = next_y;
+			next_y += step_y;
+		}
+		if distance > 1.0 {
+			break;
+		}
+		if grid.is_opaque(current) {
+			let pixloc = ByondPixLoc::new(
+				from.x() + (to.x() - from.x()) * distance,
+				from.y() + (to.y() - from.y()) * distance,
+				from.z(),
+			);
+			return Some(RayHit {
+				xyz: current,
+				pixloc,
+			});
+		}
+	}
+	None
+}
+
+/// Returns if a ray between two pixel positions doesn't pass through any
+/// opaque tiles, other than the one containing `to`.
+pub fn line_of_sight_pixels(
+	grid: &OpacityGrid,
+	from: ByondPixLoc,
+	to: ByondPixLoc,
+	icon_size: (u16, u16),
+) -> bool {
+	from.z() == to.z()
+		&& raycast_pixels(grid, from, to, icon_size)
+			.is_none_or(|hit| hit.xyz == to.to_xyz(icon_size))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	pub(super) fn grid(map: &str) -> OpacityGrid {
+		let map = crate::grid::ascii_map(map);
+		OpacityGrid::from_fn(map.block(), |xyz| !map[xyz])
+	}
+
+	#[test]
+	fn open_view() {
+		let grid = grid(".......\n.......\n.......\n.......\n.......");
+		let eye = ByondXYZ::new(4, 3, 1);
+		assert_eq!(view(&grid, eye, 2).len(), 25);
+		assert_eq!(oview(&grid, eye, 2).len(), 24);
+		assert!(!oview(&grid, eye, 2).contains(&eye));
+		// clipped to the edge of the grid
+		assert_eq!(view(&grid, eye, 3).len(), 35);
+	}
+
+	#[test]
+	fn walls_block_view() {
+		let grid = grid("..#..\n..#..\n..#..");
+		let eye = ByondXYZ::new(1, 2, 1);
+		let visible = view(&grid, eye, 5);
+		assert!(visible.contains(&ByondXYZ::new(3, 2, 1)));
+		assert!(visible.contains(&ByondXYZ::new(3, 3, 1)));
+		assert!(visible.contains(&ByondXYZ::new(3, 1, 1)));
+		assert!(visible.iter().all(|xyz| xyz.x() <= 3));
+	}
+
+	#[test]
+	fn raycasts() {
+		let grid = grid(".....\n..#..\n.....");
+		let (from, to) = (ByondXYZ::new(1, 2, 1), ByondXYZ::new(5, 2, 1));
+		assert_eq!(raycast(&grid, from, to), Some(ByondXYZ::new(3, 2, 1)));
+		assert!(!line_of_sight(&grid, from, to));
+		assert!(line_of_sight(&grid, from, ByondXYZ::new(3, 2, 1)));
+		assert!(line_of_sight(
+			&grid,
+			ByondXYZ::new(1, 1, 1),
+			ByondXYZ::new(5, 1, 1)
+		));
+
+		let icon_size = (32, 32);
+		let hit = raycast_pixels(
+			&grid,
+			ByondPixLoc::center_of(from, icon_size),
+			ByondPixLoc::center_of(to, icon_size),
+			icon_size,
+		)
+		.unwrap();
+		assert_eq!(hit.xyz, ByondXYZ::new(3, 2, 1));
+		assert_eq!((hit.pixloc.x(), hit.pixloc.y()), (65.0, 49.0));
+		let pixels = |xyz| ByondPixLoc::center_of(xyz, icon_size);
+		assert!(line_of_sight_pixels(
+			&grid,
+			pixels(ByondXYZ::new(1, 1, 1)),
+			pixels(ByondXYZ::new(5, 1, 1)),
+			icon_size,
+		));
+		assert!(!line_of_sight_pixels(
+			&grid,
+			pixels(ByondXYZ::new(1, 3, 1)),
+			pixels(ByondXYZ::new(5, 1, 1)),
+			icon_size,
+		));
+	}
+}
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: 0BSD
+use super::{OpacityGrid, Visibility};
+use crate::{
+	ByondXYZ,
+	geometry::{circle_limit, squared_distance},
+};
+
+/// Computes a circular field of view around `eye` using symmetric
+/// shadowcasting.
+///
+/// Unlike [`view`](super::view), this is symmetric: if a transparent tile `A`
+/// can see a transparent tile `B`, then `B` can also see `A`. Opaque tiles are
+/// visible if any part of them is.
+pub fn field_of_view(grid: &OpacityGrid, eye: ByondXYZ, radius: u16) -> Visibility {
+	let mut visibility = Visibility::new(eye, radius);
+	let limit = circle_limit(radius);
+	let mut reveal = |xyz: ByondXYZ| {
+		if grid.block().contains(&xyz) && squared_distance(eye, xyz) <= limit {
+			visibility.set(xyz, true);
+		}
+	};
+	reveal(eye);
+	for quadrant in Quadrant::ALL {
+		let mut rows = vec![Row {
+			depth: 1,
+			start: Slope::new(-1, 1),
+			end: Slope::new(1, 1),
+		}];
+		while let Some(mut row) = rows.pop() {
+			if row.depth > radius as i32 {
+				continue;
+			}
+			let mut previous_opaque = None;
+			for col in row.min_col()..=row.max_col() {
+				let xyz = quadrant.transform(eye, row.depth, col);
+				let opaque = grid.is_opaque(xyz);
+				if opaque || row.is_symmetric(col) {
+					reveal(xyz);
+				}
+				match previous_opaque {
+					Some(true) if !opaque => row.start = Slope::of_tile(row.depth, col),
+					Some(false) if opaque => rows.push(Row {
+						depth: row.depth + 1,
+						start: row.start,
+						end: Slope::of_tile(row.depth, col),
+					}),
+					_ => {}
+				}
+				previous_opaque = Some(opaque);
+			}
+			if previous_opaque == Some(false) {
+				rows.push(Row {
+					depth: row.depth + 1,
+					..row
+				});
+			}
+		}
+	}
+	visibility
+}
+
+#[derive(Copy, Clone)]
+enum Quadrant {
+	North,
+	South,
+	East,
+	West,
+}
+
+impl Quadrant {
+	const ALL: [Self; 4] = [Self::North, Self::South, Self::East, Self::West];
+
+	fn transform(self, eye: ByondXYZ, depth: i32, col: i32) -> ByondXYZ {
+		let (dx, dy) = match self {
+			Self::North => (col, depth),
+			Self::South => (col, -depth),
+			Self::East => (depth, col),
+			Self::West => (-depth, col),
+		};
+		ByondXYZ::new(
+			(eye.x() as i32 + dx) as i16,
+			(eye.y() as i32 + dy) as i16,
+			eye.z(),
+		)
+	}
+}
+
+/// An exact fraction, so slopes compare without rounding errors.
+#[derive(Copy, Clone)]
+struct Slope {
+	num: i32,
+	den: i32,
+}
+
+impl Slope {
+	const fn new(num: i32, den: i32) -> Self {
+		Self { num, den }
+	}
+
+	/// The slope of the left edge of a tile.
+	const fn of_tile(depth: i32, col: i32) -> Self {
+		Self::new(2 * col - 1, 2 * depth)
+	}
+}
+
+#[derive(Copy, Clone)]
+struct Row {
+	depth: i32,
+	start: Slope,
+	end: Slope,
+}
+
+impl Row {
+	/// `depth * start`, rounded with ties going up.
+	fn min_col(&self) -> i32 {
+		(2 * self.depth * self.start.num + self.start.den).div_euclid(2 * self.start.den)
+	}
+
+	/// `depth * end`, rounded with ties going down.
+	fn max_col(&self) -> i32 {
+		-(self.end.den - 2 * self.depth * self.end.num).div_euclid(2 * self.end.den)
+	}
+
+	/// Returns if the center of the tile is within the row's slopes.
+	fn is_symmetric(&self, col: i32) -> bool {
+		col * self.start.den >= self.depth * self.start.num
+			&& col * self.end.den <= self.depth * self.end.num
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{super::tests::grid, *};
+
+	#[test]
+	fn open_field() {
+		let grid = grid(".........\n.........\n.........\n.........\n.........");
+		let eye = ByondXYZ::new(5, 3, 1);
+		assert_eq!(field_of_view(&grid, eye, 0).len(), 1);
+		assert_eq!(field_of_view(&grid, eye, 1).len(), 9);
+		assert_eq!(field_of_view(&grid, eye, 2).len(), 21);
+	}
+
+	#[test]
+	fn symmetry() {
+		let grid = grid(
+			"..........\n.#....#...\n...#......\n..........\n.....##...\n.#........\n..........",
+		);
+		let floors = grid
+			.block()
+			.iter()
+			.filter(|xyz| !grid.is_opaque(*xyz))
+			.collect::<Vec<_>>();
+		let fovs = floors
+			.iter()
+			.map(|xyz| field_of_view(&grid, *xyz, 12))
+			.collect::<Vec<_>>();
+		for (a, a_fov) in floors.iter().zip(&fovs) {
+			for (b, b_fov) in floors.iter().zip(&fovs) {
+				assert_eq!(a_fov.contains(b), b_fov.contains(a), "{a:?} and {b:?}");
+			}
+		}
+		let eye = ByondXYZ::new(1, 5, 1);
+		let fov = field_of_view(&grid, eye, 12);
+		assert!(fov.contains(&ByondXYZ::new(4, 5, 1)));
+		assert!(!fov.contains(&ByondXYZ::new(6, 5, 1)));
+	}
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: 0BSD
+//! Procedural map generation.
+//!
+//! Generation is split into two halves. Everything that decides what goes
+//! where - [noise](Perlin), [caves](cave), [rooms](bsp) and
+//! [spawn tables](SpawnTable) - works on plain Rust data, so it can run on
+//! worker threads. The result is a [`Plan`] of atoms to create, which is then
+//! applied on the main thread a chunk at a time, so large maps don't stall the
+//! server.
+//!
+//! Everything random takes an [`Rng`], so the same seed always generates the
+//! same map.
+//!
+//! ```no_run
+//! use meowtonin::{
+//!     ByondBlock, ByondXYZ, TypePath,
+//!     mapgen::{CaveOptions, Plan, Rng, SpawnTable, cave},
+//! };
+//! use std::time::Duration;
+//!
+//! # fn main() -> meowtonin::ByondResult<()> {
+//! let block = ByondBlock::new(ByondXYZ::new(1, 1, 2), ByondXYZ::new(255, 255, 2));
+//! let rock = TypePath::new("/turf/closed/mineral")?;
+//! let floor = TypePath::new("/turf/open/floor/plating/asteroid")?;
+//! let ores = SpawnTable::new()
+//!     .with(None, 90.0)
+//!     .with(Some(TypePath::new("/obj/item/stack/ore/iron")?), 8.0)
+//!     .with(Some(TypePath::new("/obj/item/stack/ore/gold")?), 2.0);
+//!
+//! let mut rng = Rng::with_seed(1234);
+//! let walls = cave(block, &mut rng, &CaveOptions::default());
+//! let mut plan = Plan::new();
+//! for (xyz, wall) in walls.iter() {
+//!     if *wall {
+//!         plan.place(xyz, rock.clone());
+//!     } else {
+//!         plan.place(xyz, floor.clone());
+//!         if let Some(Some(ore)) = ores.pick(&mut rng) {
+//!             plan.place(xyz, ore.clone()).var("amount", rng.u32(1..=5));
+//!         }
+//!     }
+//! }
+//! while !plan.apply(Duration::from_millis(10))? {
+//!     // ...wait for the next tick...
+//! }
+//! # Ok(())
+//! # }
+//! ```
+mod bsp;
+mod cave;
+mod noise;
+mod plan;
+mod spawn;
+
+pub use self::{
+	bsp::{BspOptions, Dungeon, bsp},
+	cave::{CaveOptions, cave},
+	noise::{Fractal, Noise, Perlin, Simplex},
+	plan::{Placement, Plan, PlanValue},
+	spawn::SpawnTable,
+};
+pub use fastrand::Rng;
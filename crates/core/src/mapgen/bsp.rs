@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondBlock, ByondXYZ, grid::Grid};
+use fastrand::Rng;
+
+/// Options for [`bsp`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct BspOptions {
+	min_leaf: u16,
+	min_room: u16,
+	padding: u16,
+}
+
+impl Default for BspOptions {
+	fn default() -> Self {
+		Self {
+			min_leaf: 10,
+			min_room: 4,
+			padding: 1,
+		}
+	}
+}
+
+impl BspOptions {
+	/// Sets the smallest width or height a partition can be split into.
+	/// Defaults to 10.
+	///
+	/// This is never less than the minimum room size plus padding.
+	pub fn min_leaf(mut self, min_leaf: u16) -> Self {
+		self.min_leaf = min_leaf;
+		self
+	}
+
+	/// Sets the smallest width or height of a room. Defaults to 4.
+	pub fn min_room(mut self, min_room: u16) -> Self {
+		self.min_room = min_room.max(1);
+		self
+	}
+
+	/// Sets how many tiles are left between a room and the edges of its
+	/// partition, which keeps rooms from touching. Defaults to 1.
+	pub fn padding(mut self, padding: u16) -> Self {
+		self.padding = padding;
+		self
+	}
+}
+
+/// The rooms and corridors generated by [`bsp`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dungeon {
+	/// The rooms, none of which overlap.
+	pub rooms: Vec<ByondBlock>,
+	/// Every corridor tile, which may pass through rooms.
+	pub corridors: Vec<ByondXYZ>,
+}
+
+impl Dungeon {
+	/// Returns a grid covering `block`, with `true` for every tile in a room
+	/// or corridor.
+	pub fn floors(&self, block: ByondBlock) -> Grid<bool> {
+		let mut floors = Grid::new(block, false);
+		let tiles = self.rooms.iter().flat_map(ByondBlock::iter);
+		for xyz in tiles.chain(self.corridors.iter().copied()) {
+			floors.set(xyz, true);
+		}
+		floors
+	}
+}
+
+/// Places rooms with binary space partitioning, connecting them with
+/// corridors.
+///
+/// The block is split in half recursively until the partitions are too small
+/// to split again, then a randomly sized room is placed in each partition.
+/// Sibling partitions are joined with L-shaped corridors, so every room is
+/// reachable. Only the lowest z-level of the block is used.
+pub fn bsp(block: ByondBlock, rng: &mut Rng, options: &BspOptions) -> Dungeon {
+	let min_leaf = options
+		.min_leaf
+		.max(options.min_room + 2 * options.padding)
+		.max(1);
+	let mut partitioner = Partitioner {
+		rng,
+		min_leaf: min_leaf as i32,
+		min_room: options.min_room as i32,
+		padding: options.padding as i32,
+		z: block.min().z(),
+		dungeon: Dungeon::default(),
+	};
+	let (min, max) = (block.min(), block.max());
+	partitioner.split(
+		(min.x() as i32, min.y() as i32),
+		(max.x() as i32, max.y() as i32),
+	);
+	let mut dungeon = partitioner.dungeon;
+	dungeon
+		.corridors
+		.sort_unstable_by_key(|xyz| (xyz.y(), xyz.x()));
+	dungeon.corridors.dedup();
+	dungeon
+}
+
+struct Partitioner<'a> {
+	rng: &'a mut Rng,
+	min_leaf: i32,
+	min_room: i32,
+	padding: i32,
+	z: i16,
+	dungeon: Dungeon,
+}
+
+impl Partitioner<'_> {
+	/// Splits a partition, returning the center of one of the rooms inside
+	/// it, if any.
+	fn split(&mut self, min: (i32, i32), max: (i32, i32)) -> Option<(i32, i32)> {
+		let (width, height) = (max.0 - min.0 + 1, max.1 - min.1 + 1);
+		let split_x = match (width >= 2 * self.min_leaf, height >= 2 * self.min_leaf) {
+			(false, false) => return self.place_room(min, max),
+			(true, false) => true,
+			(false, true) => false,
+			// Prefer splitting long partitions across their length.
+			(true, true) if width * 4 > height * 5 => true,
+			(true, true) if height * 4 > width * 5 => false,
+			(true, true) => self.rng.bool(),
+		};
+		let (a, b) = if split_x {
+			let at = self
+				.rng
+				.i32(min.0 + self.min_leaf - 1..=max.0 - self.min_leaf);
+			(
+				self.split(min, (at, max.1)),
+				self.split((at + 1, min.1), max),
+			)
+		} else {
+			let at = self
+				.rng
+				.i32(min.1 + self.min_leaf - 1..=max.1 - self.min_leaf);
+			(
+				self.split(min, (max.0, at)),
+				self.split((min.0, at + 1), max),
+			)
+		};
+		if let (Some(a), Some(b)) = (a, b) {
+			self.connect(a, b);
+		}
+		if self.rng.bool() { a.or(b) } else { b.or(a) }
+	}
+
+	fn place_room(&mut self, min: (i32, i32), max: (i32, i32)) -> Option<(i32, i32)> {
+		let available_x = max.0 - min.0 + 1 - 2 * self.padding;
+		let available_y = max.1 - min.1 + 1 - 2 * self.padding;
+		if available_x < self.min_room || available_y < self.min_room {
+			return None;
+		}
+		let width = self.rng.i32(self.min_room..=available_x);
+		let height = self.rng.i32(self.min_room..=available_y);
+		let x = self
+			.rng
+			.i32(min.0 + self.padding..=max.0 - self.padding - width + 1);
+		let y = self
+			.rng
+			.i32(min.1 + self.padding..=max.1 - self.padding - height + 1);
+		self.dungeon.rooms.push(ByondBlock::new(
+			ByondXYZ::new(x as i16, y as i16, self.z),
+			ByondXYZ::new((x + width - 1) as i16, (y + height - 1) as i16, self.z),
+		));
+		Some((x + width / 2, y + height / 2))
+	}
+
+	/// Digs an L-shaped corridor between two points.
+	fn connect(&mut self, a: (i32, i32), b: (i32, i32)) {
+		let corner = if self.rng.bool() {
+			(b.0, a.1)
+		} else {
+			(a.0, b.1)
+		};
+		for (from, to) in [(a, corner), (corner, b)] {
+			for x in from.0.min(to.0)..=from.0.max(to.0) {
+				for y in from.1.min(to.1)..=from.1.max(to.1) {
+					self.dungeon
+						.corridors
+						.push(ByondXYZ::new(x as i16, y as i16, self.z));
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::regions::{Connectivity, Regions};
+
+	#[test]
+	fn rooms_are_connected() {
+		let block = ByondBlock::new(ByondXYZ::new(1, 1, 3), ByondXYZ::new(80, 60, 3));
+		for seed in 0..20 {
+			let dungeon = bsp(block, &mut Rng::with_seed(seed), &BspOptions::default());
+			assert!(dungeon.rooms.len() >= 4, "seed {seed}");
+			for (idx, room) in dungeon.rooms.iter().enumerate() {
+				assert!(block.contains_block(room));
+				assert!(
+					dungeon.rooms[idx + 1..]
+						.iter()
+						.all(|other| room.intersect(other).is_none())
+				);
+			}
+			let regions = Regions::from_passable(dungeon.floors(block), Connectivity::new());
+			assert_eq!(regions.len(), 1, "seed {seed}");
+		}
+	}
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondBlock, ByondXYZ, grid::Grid};
+use fastrand::Rng;
+
+/// Options for [`cave`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[must_use]
+pub struct CaveOptions {
+	fill: f32,
+	steps: u32,
+	birth: u8,
+	survival: u8,
+}
+
+impl Default for CaveOptions {
+	fn default() -> Self {
+		Self {
+			fill: 0.45,
+			steps: 5,
+			birth: 5,
+			survival: 4,
+		}
+	}
+}
+
+impl CaveOptions {
+	/// Sets the chance of each tile starting out as a wall, from 0 to 1.
+	/// Defaults to 0.45.
+	pub fn fill(mut self, fill: f32) -> Self {
+		self.fill = fill;
+		self
+	}
+
+	/// Sets how many smoothing steps to run. Defaults to 5.
+	pub fn steps(mut self, steps: u32) -> Self {
+		self.steps = steps;
+		self
+	}
+
+	/// Sets how many of its 8 neighbors must be walls for a floor to become a
+	/// wall. Defaults to 5.
+	pub fn birth(mut self, birth: u8) -> Self {
+		self.birth = birth;
+		self
+	}
+
+	/// Sets how many of its 8 neighbors must be walls for a wall to stay a
+	/// wall. Defaults to 4.
+	pub fn survival(mut self, survival: u8) -> Self {
+		self.survival = survival;
+		self
+	}
+}
+
+/// Generates caves with a cellular automaton, returning `true` for walls.
+///
+/// Every tile starts out as a wall at random, then each step turns tiles into
+/// walls or floors depending on how many of their neighbors are walls.
+/// Tiles outside of the block count as walls, so caves are closed off at the
+/// edges. Each z-level is generated separately.
+pub fn cave(block: ByondBlock, rng: &mut Rng, options: &CaveOptions) -> Grid<bool> {
+	let mut walls = Grid::from_fn(block, |_| rng.f32() < options.fill);
+	for _ in 0..options.steps {
+		walls = Grid::from_fn(block, |xyz| {
+			let neighbors = (-1..=1)
+				.flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+				.filter(|&offset| offset != (0, 0))
+				.filter(|&(dx, dy)| {
+					let neighbor = ByondXYZ::new(xyz.x() + dx, xyz.y() + dy, xyz.z());
+					walls.get(neighbor).copied().unwrap_or(true)
+				})
+				.count() as u8;
+			if walls[xyz] {
+				neighbors >= options.survival
+			} else {
+				neighbors >= options.birth
+			}
+		});
+	}
+	walls
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn caves() {
+		let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), ByondXYZ::new(40, 30, 2));
+		let generate = |seed, options| cave(block, &mut Rng::with_seed(seed), &options);
+		let walls = generate(5, CaveOptions::default());
+		assert_eq!(walls, generate(5, CaveOptions::default()));
+		assert_ne!(walls, generate(6, CaveOptions::default()));
+		let wall_count = walls.as_slice().iter().filter(|wall| **wall).count();
+		assert!(wall_count > 0 && wall_count < walls.len());
+
+		let open = generate(5, CaveOptions::default().fill(0.0).steps(0));
+		assert!(open.as_slice().iter().all(|wall| !wall));
+		let solid = generate(5, CaveOptions::default().fill(1.0));
+		assert!(solid.as_slice().iter().all(|wall| *wall));
+	}
+}
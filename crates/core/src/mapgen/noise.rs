@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: 0BSD
+use fastrand::Rng;
+
+/// A source of smooth 2D noise.
+pub trait Noise: Send + Sync {
+	/// Returns the noise at the given point, roughly between -1 and 1.
+	///
+	/// Noise changes over a distance of about 1, so tile coordinates should
+	/// usually be scaled down first, or wrapped in a [`Fractal`] with a low
+	/// frequency.
+	fn get(&self, x: f32, y: f32) -> f32;
+}
+
+impl<Source> Noise for &Source
+where
+	Source: Noise + ?Sized,
+{
+	fn get(&self, x: f32, y: f32) -> f32 {
+		(**self).get(x, y)
+	}
+}
+
+/// A shuffled table of 0-255, repeated twice so lookups don't need to wrap.
+#[derive(Clone)]
+struct Permutation([u8; 512]);
+
+impl Permutation {
+	fn new(seed: u64) -> Self {
+		let mut shuffled = std::array::from_fn::<u8, 256, _>(|idx| idx as u8);
+		Rng::with_seed(seed).shuffle(&mut shuffled);
+		Self(std::array::from_fn(|idx| shuffled[idx & 255]))
+	}
+
+	#[inline]
+	fn hash(&self, x: i32, y: i32) -> u8 {
+		let x = (x & 255) as usize;
+		let y = (y & 255) as usize;
+		self.0[self.0[x] as usize + y]
+	}
+}
+
+/// Ken Perlin's improved gradient noise.
+#[derive(Clone)]
+pub struct Perlin {
+	permutation: Permutation,
+}
+
+impl Perlin {
+	/// Creates Perlin noise with the given seed.
+	pub fn new(seed: u64) -> Self {
+		Self {
+			permutation: Permutation::new(seed),
+		}
+	}
+
+	fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+		match hash & 7 {
+			0 => x + y,
+			1 => -x + y,
+			2 => x - y,
+			3 => -x - y,
+			4 => x,
+			5 => -x,
+			6 => y,
+			_ => -y,
+		}
+	}
+}
+
+impl Noise for Perlin {
+	fn get(&self, x: f32, y: f32) -> f32 {
+		let (cell_x, cell_y) = (x.floor(), y.floor());
+		let (x, y) = (x - cell_x, y - cell_y);
+		let (cell_x, cell_y) = (cell_x as i32, cell_y as i32);
+		let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+		let lerp = |t: f32, a: f32, b: f32| a + t * (b - a);
+		let (u, v) = (fade(x), fade(y));
+		let corner = |dx: i32, dy: i32| {
+			let hash = self.permutation.hash(cell_x + dx, cell_y + dy);
+			Self::gradient(hash, x - dx as f32, y - dy as f32)
+		};
+		let bottom = lerp(u, corner(0, 0), corner(1, 0));
+		let top = lerp(u, corner(0, 1), corner(1, 1));
+		lerp(v, bottom, top).clamp(-1.0, 1.0)
+	}
+}
+
+/// 2D simplex noise, which has fewer directional artifacts than [`Perlin`]
+/// noise.
+#[derive(Clone)]
+pub struct Simplex {
+	permutation: Permutation,
+}
+
+impl Simplex {
+	const GRADIENTS: [(f32, f32); 12] = [
+		(1.0, 1.0),
+		(-1.0, 1.0),
+		(1.0, -1.0),
+		(-1.0, -1.0),
+		(1.0, 0.0),
+		(-1.0, 0.0),
+		(1.0, 0.0),
+		(-1.0, 0.0),
+		(0.0, 1.0),
+		(0.0, -1.0),
+		(0.0, 1.0),
+		(0.0, -1.0),
+	];
+
+	/// Creates simplex noise with the given seed.
+	pub fn new(seed: u64) -> Self {
+		Self {
+			permutation: Permutation::new(seed),
+		}
+	}
+}
+
+impl Noise for Simplex {
+	fn get(&self, x: f32, y: f32) -> f32 {
+		const SKEW: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+		const UNSKEW: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+		let skew = (x + y) * SKEW;
+		let (cell_x, cell_y) = ((x + skew).floor() as i32, (y + skew).floor() as i32);
+		let unskew = (cell_x + cell_y) as f32 * UNSKEW;
+		let x0 = x - (cell_x as f32 - unskew);
+		let y0 = y - (cell_y as f32 - unskew);
+		let (step_x, step_y) = if x0 > y0 { (1, 0) } else { (0, 1) };
+		let corners = [
+			(0, 0, x0, y0),
+			(
+				step_x,
+				step_y,
+				x0 - step_x as f32 + UNSKEW,
+				y0 - step_y as f32 + UNSKEW,
+			),
+			(1, 1, x0 - 1.0 + 2.0 * UNSKEW, y0 - 1.0 + 2.0 * UNSKEW),
+		];
+		let total = corners
+			.into_iter()
+			.map(|(dx, dy, x, y)| {
+				let falloff = 0.5 - x * x - y * y;
+				if falloff <= 0.0 {
+					return 0.0;
+				}
+				let hash = self.permutation.hash(cell_x + dx, cell_y + dy);
+				let (gradient_x, gradient_y) = Self::GRADIENTS[hash as usize % 12];
+				falloff.powi(4) * (gradient_x * x + gradient_y * y)
+			})
+			.sum::<f32>();
+		(70.0 * total).clamp(-1.0, 1.0)
+	}
+}
+
+/// Layers several octaves of another noise source at increasing frequencies
+/// and decreasing amplitudes, for more natural-looking detail.
+#[derive(Clone)]
+#[must_use]
+pub struct Fractal<Source> {
+	source: Source,
+	octaves: u32,
+	frequency: f32,
+	lacunarity: f32,
+	persistence: f32,
+}
+
+impl<Source> Fractal<Source>
+where
+	Source: Noise,
+{
+	/// Creates fractal noise with 4 octaves, a frequency of 1, a lacunarity
+	/// of 2 and a persistence of 0.5.
+	pub fn new(source: Source) -> Self {
+		Self {
+			source,
+			octaves: 4,
+			frequency: 1.0,
+			lacunarity: 2.0,
+			persistence: 0.5,
+		}
+	}
+
+	/// Sets how many layers of noise are combined.
+	pub fn octaves(mut self, octaves: u32) -> Self {
+		self.octaves = octaves.max(1);
+		self
+	}
+
+	/// Sets the frequency of the first octave, which coordinates are
+	/// multiplied by - i.e `0.05` for features around 20 tiles across.
+	pub fn frequency(mut self, frequency: f32) -> Self {
+		self.frequency = frequency;
+		self
+	}
+
+	/// Sets how much the frequency is multiplied by for each octave.
+	pub fn lacunarity(mut self, lacunarity: f32) -> Self {
+		self.lacunarity = lacunarity;
+		self
+	}
+
+	/// Sets how much the amplitude is multiplied by for each octave.
+	pub fn persistence(mut self, persistence: f32) -> Self {
+		self.persistence = persistence;
+		self
+	}
+}
+
+impl<Source> Noise for Fractal<Source>
+where
+	Source: Noise,
+{
+	fn get(&self, x: f32, y: f32) -> f32 {
+		let mut frequency = self.frequency;
+		let mut amplitude = 1.0;
+		let mut total = 0.0;
+		let mut max = 0.0;
+		for octave in 0..self.octaves {
+			// Offset each octave, so they don't all line up at the origin.
+			let offset = octave as f32 * 31.7;
+			total += self
+				.source
+				.get(x * frequency + offset, y * frequency + offset)
+				* amplitude;
+			max += amplitude;
+			frequency *= self.lacunarity;
+			amplitude *= self.persistence;
+		}
+		total / max
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn samples(noise: &impl Noise) -> Vec<f32> {
+		(0..1000)
+			.map(|idx| noise.get(idx as f32 * 0.137, idx as f32 * 0.071 - 20.0))
+			.collect()
+	}
+
+	#[test]
+	fn deterministic_and_bounded() {
+		for noise in [
+			&Perlin::new(7) as &dyn Noise,
+			&Simplex::new(7),
+			&Fractal::new(Simplex::new(7)).frequency(0.3),
+		] {
+			let values = samples(&noise);
+			assert_eq!(values, samples(&noise));
+			assert!(values.iter().all(|value| (-1.0..=1.0).contains(value)));
+			assert!(values.iter().any(|value| *value > 0.1));
+			assert!(values.iter().any(|value| *value < -0.1));
+		}
+		assert_ne!(samples(&Perlin::new(1)), samples(&Perlin::new(2)));
+	}
+
+	#[test]
+	fn perlin_is_zero_on_lattice() {
+		let noise = Perlin::new(3);
+		assert_eq!(noise.get(4.0, -9.0), 0.0);
+	}
+}
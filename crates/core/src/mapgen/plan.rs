@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondDir, ByondResult, ByondValue, ByondXYZ, ToByond, TypePath, misc, sync::call_on_main_thread,
+};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The value of a var in a [`Placement`].
+///
+/// Unlike [`ByondValue`], these can be created off the main thread, as
+/// they're only converted when the plan is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanValue {
+	/// `null`.
+	Null,
+	/// A number, which is also used for booleans and directions.
+	Number(f32),
+	/// A string.
+	String(String),
+	/// A typepath, converted with `text2path()`.
+	Path(TypePath),
+	/// A list of values.
+	List(Vec<PlanValue>),
+}
+
+impl ToByond for PlanValue {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		match self {
+			Self::Null => Ok(ByondValue::NULL),
+			Self::Number(number) => number.to_byond(),
+			Self::String(string) => string.to_byond(),
+			Self::Path(path) => path.to_byond(),
+			Self::List(values) => values
+				.iter()
+				.map(ToByond::to_byond)
+				.collect::<ByondResult<Vec<_>>>()?
+				.to_byond(),
+		}
+	}
+}
+
+impl ToByond for &PlanValue {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		(*self).to_byond()
+	}
+}
+
+macro_rules! impl_plan_value_from {
+	($($ty:ty => |$value:ident| $convert:expr),+ $(,)?) => {
+		$(
+			impl From<$ty> for PlanValue {
+				fn from($value: $ty) -> Self {
+					$convert
+				}
+			}
+		)+
+	};
+}
+
+impl_plan_value_from! {
+	bool => |value| Self::Number(value as u8 as f32),
+	f32 => |value| Self::Number(value),
+	i32 => |value| Self::Number(value as f32),
+	u32 => |value| Self::Number(value as f32),
+	&str => |value| Self::String(value.to_owned()),
+	String => |value| Self::String(value),
+	TypePath => |value| Self::Path(value),
+	ByondDir => |value| Self::Number(value.bits() as f32),
+	Vec<PlanValue> => |value| Self::List(value),
+}
+
+impl<Value> From<Option<Value>> for PlanValue
+where
+	Value: Into<PlanValue>,
+{
+	fn from(value: Option<Value>) -> Self {
+		value.map_or(Self::Null, Into::into)
+	}
+}
+
+/// An atom to create as part of a [`Plan`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+	/// The tile to create the atom on.
+	pub xyz: ByondXYZ,
+	/// The type of atom to create. Turf types replace the existing turf.
+	pub typepath: TypePath,
+	/// Vars to set on the atom after it's created.
+	pub vars: Vec<(String, PlanValue)>,
+}
+
+impl Placement {
+	/// Creates a placement with no vars.
+	pub fn new(xyz: ByondXYZ, typepath: TypePath) -> Self {
+		Self {
+			xyz,
+			typepath,
+			vars: Vec::new(),
+		}
+	}
+
+	/// Sets a var on the atom after it's created.
+	pub fn var<Name, Value>(&mut self, name: Name, value: Value) -> &mut Self
+	where
+		Name: Into<String>,
+		Value: Into<PlanValue>,
+	{
+		self.vars.push((name.into(), value.into()));
+		self
+	}
+}
+
+/// A list of atoms to create, built off the main thread and then applied a
+/// chunk at a time.
+///
+/// Placements are applied in the order they were added, so turfs should
+/// usually be placed before anything on top of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+	/// Shared with the main thread while applying, so nothing is lost if the
+	/// main thread can't be reached.
+	placements: Arc<Vec<Placement>>,
+	applied: usize,
+}
+
+impl Plan {
+	/// Creates an empty plan.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the number of placements in the plan.
+	pub fn len(&self) -> usize {
+		self.placements.len()
+	}
+
+	/// Returns if the plan has no placements.
+	pub fn is_empty(&self) -> bool {
+		self.placements.is_empty()
+	}
+
+	/// Returns how many placements haven't been applied yet.
+	pub fn remaining(&self) -> usize {
+		self.placements.len() - self.applied
+	}
+
+	/// Returns every placement in the plan.
+	pub fn placements(&self) -> &[Placement] {
+		&self.placements
+	}
+
+	/// Adds an atom to create, returning its placement so vars can be set on
+	/// it.
+	pub fn place(&mut self, xyz: ByondXYZ, typepath: TypePath) -> &mut Placement {
+		let placements = Arc::make_mut(&mut self.placements);
+		placements.push(Placement::new(xyz, typepath));
+		placements.last_mut().unwrap()
+	}
+
+	/// Creates atoms on the main thread, returning `true` once the whole plan
+	/// has been applied.
+	///
+	/// Each atom is created with `new typepath(locate(x, y, z))`, then has its
+	/// vars set - so they're set after `New()` has already run. Placements on
+	/// tiles that are off the map are skipped.
+	///
+	/// If applying takes longer than `budget`, the remaining placements are
+	/// left for the next call, so large plans can be spread across several
+	/// ticks. At least one placement is always applied. If a placement fails,
+	/// the error is returned and that placement is skipped.
+	///
+	/// This can be called from any thread; from other threads, it blocks until
+	/// the main thread has finished applying this chunk.
+	pub fn apply(&mut self, budget: Duration) -> ByondResult<bool> {
+		if self.remaining() == 0 {
+			return Ok(true);
+		}
+		let placements = self.placements.clone();
+		let start = self.applied;
+		let (applied, result) =
+			call_on_main_thread(move || apply_placements(&placements, start, budget))?;
+		self.applied = applied;
+		result.map(|()| self.remaining() == 0)
+	}
+}
+
+impl Extend<Placement> for Plan {
+	fn extend<Iter>(&mut self, iter: Iter)
+	where
+		Iter: IntoIterator<Item = Placement>,
+	{
+		Arc::make_mut(&mut self.placements).extend(iter);
+	}
+}
+
+impl FromIterator<Placement> for Plan {
+	fn from_iter<Iter>(iter: Iter) -> Self
+	where
+		Iter: IntoIterator<Item = Placement>,
+	{
+		Self {
+			placements: Arc::new(iter.into_iter().collect()),
+			applied: 0,
+		}
+	}
+}
+
+/// Applies placements starting from `start` until the budget runs out,
+/// returning the index of the next placement to apply.
+fn apply_placements(
+	placements: &[Placement],
+	start: usize,
+	budget: Duration,
+) -> (usize, ByondResult<()>) {
+	let started = Instant::now();
+	for (idx, placement) in placements.iter().enumerate().skip(start) {
+		if idx > start && started.elapsed() >= budget {
+			return (idx, Ok(()));
+		}
+		if let Err(err) = apply_placement(placement) {
+			return (idx + 1, Err(err));
+		}
+	}
+	(placements.len(), Ok(()))
+}
+
+fn apply_placement(placement: &Placement) -> ByondResult<()> {
+	let turf = misc::locate_xyz(placement.xyz)?;
+	if turf.is_null() {
+		return Ok(());
+	}
+	let mut atom = ByondValue::new(placement.typepath.as_str(), [turf])?;
+	for (name, value) in &placement.vars {
+		atom.write_var(name, value)?;
+	}
+	Ok(())
+}
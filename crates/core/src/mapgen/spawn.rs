@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: 0BSD
+use fastrand::Rng;
+
+/// A table of items picked at random, weighted by how common each one is.
+///
+/// Like DM's `pickweight()`. To sometimes spawn nothing, use `Option` items
+/// with a weight for `None`.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct SpawnTable<Item> {
+	entries: Vec<(Item, f32)>,
+	total: f32,
+}
+
+impl<Item> Default for SpawnTable<Item> {
+	fn default() -> Self {
+		Self {
+			entries: Vec::new(),
+			total: 0.0,
+		}
+	}
+}
+
+impl<Item> SpawnTable<Item> {
+	/// Creates an empty table.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an item with the given weight.
+	pub fn with(mut self, item: Item, weight: f32) -> Self {
+		self.add(item, weight);
+		self
+	}
+
+	/// Adds an item with the given weight.
+	///
+	/// Items with a weight that isn't a positive number are ignored.
+	pub fn add(&mut self, item: Item, weight: f32) {
+		if weight > 0.0 && weight.is_finite() {
+			self.entries.push((item, weight));
+			self.total += weight;
+		}
+	}
+
+	/// Returns the number of items in the table.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns if the table has no items.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Returns the sum of every item's weight.
+	pub fn total_weight(&self) -> f32 {
+		self.total
+	}
+
+	/// Iterates over every item along with its weight.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Item, f32)> + '_ {
+		self.entries.iter().map(|(item, weight)| (item, *weight))
+	}
+
+	/// Picks a random item, or `None` if the table is empty.
+	pub fn pick(&self, rng: &mut Rng) -> Option<&Item> {
+		let mut roll = rng.f32() * self.total;
+		for (item, weight) in &self.entries {
+			if roll < *weight {
+				return Some(item);
+			}
+			roll -= weight;
+		}
+		// Rounding errors can leave a tiny bit of the roll over.
+		self.entries.last().map(|(item, _)| item)
+	}
+}
+
+impl<Item> FromIterator<(Item, f32)> for SpawnTable<Item> {
+	fn from_iter<Iter>(iter: Iter) -> Self
+	where
+		Iter: IntoIterator<Item = (Item, f32)>,
+	{
+		let mut table = Self::new();
+		table.extend(iter);
+		table
+	}
+}
+
+impl<Item> Extend<(Item, f32)> for SpawnTable<Item> {
+	fn extend<Iter>(&mut self, iter: Iter)
+	where
+		Iter: IntoIterator<Item = (Item, f32)>,
+	{
+		for (item, weight) in iter {
+			self.add(item, weight);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn weighted_picks() {
+		let table = SpawnTable::new()
+			.with("common", 9.0)
+			.with("rare", 1.0)
+			.with("never", 0.0)
+			.with("invalid", f32::NAN);
+		assert_eq!(table.len(), 2);
+		assert_eq!(table.total_weight(), 10.0);
+		let mut rng = Rng::with_seed(42);
+		let rare = (0..10_000)
+			.filter(|_| table.pick(&mut rng) == Some(&"rare"))
+			.count();
+		assert!((800..1200).contains(&rare), "{rare}");
+		assert_eq!(SpawnTable::<()>::new().pick(&mut rng), None);
+	}
+}
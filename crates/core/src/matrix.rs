@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond};
+use std::{
+	borrow::Cow,
+	ops::{Mul, MulAssign},
+};
+
+/// A 2D affine transform, equivalent to DM's `/matrix`.
+///
+/// A point `(x, y)` is transformed to `(a*x + b*y + c, d*x + e*y + f)`.
+/// Angles are in degrees, clockwise, like `matrix.Turn()`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ByondMatrix {
+	pub a: f32,
+	pub b: f32,
+	pub c: f32,
+	pub d: f32,
+	pub e: f32,
+	pub f: f32,
+}
+
+impl ByondMatrix {
+	/// The identity matrix, which doesn't change anything.
+	pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+
+	/// Creates a new matrix with the given components.
+	#[inline]
+	pub const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+		Self { a, b, c, d, e, f }
+	}
+
+	/// Creates a matrix that rotates clockwise by `angle` degrees.
+	pub fn rotation(angle: f32) -> Self {
+		let (sin, cos) = angle.to_radians().sin_cos();
+		Self::new(cos, sin, 0.0, -sin, cos, 0.0)
+	}
+
+	/// Creates a matrix that scales by `x` horizontally and `y` vertically.
+	#[inline]
+	pub const fn scaling(x: f32, y: f32) -> Self {
+		Self::new(x, 0.0, 0.0, 0.0, y, 0.0)
+	}
+
+	/// Creates a matrix that moves by `x` horizontally and `y` vertically.
+	#[inline]
+	pub const fn translation(x: f32, y: f32) -> Self {
+		Self::new(1.0, 0.0, x, 0.0, 1.0, y)
+	}
+
+	/// Returns a matrix that applies this transform, then `other`.
+	///
+	/// Equivalent to `matrix.Multiply(other)` or `matrix * other` in DM.
+	pub fn multiply(&self, other: &Self) -> Self {
+		Self::new(
+			other.a * self.a + other.b * self.d,
+			other.a * self.b + other.b * self.e,
+			other.a * self.c + other.b * self.f + other.c,
+			other.d * self.a + other.e * self.d,
+			other.d * self.b + other.e * self.e,
+			other.d * self.c + other.e * self.f + other.f,
+		)
+	}
+
+	/// Returns the determinant, which is how much the transform scales areas
+	/// by, and negative if it flips.
+	pub fn determinant(&self) -> f32 {
+		self.a * self.e - self.b * self.d
+	}
+
+	/// Returns the inverse of the matrix, which undoes the transform, or
+	/// `None` if it can't be undone because it scales something to 0.
+	///
+	/// Equivalent to `matrix.Invert()` in DM.
+	pub fn invert(&self) -> Option<Self> {
+		let det = self.determinant();
+		if det == 0.0 || !det.is_finite() {
+			return None;
+		}
+		Some(Self::new(
+			self.e / det,
+			-self.b / det,
+			(self.b * self.f - self.c * self.e) / det,
+			-self.d / det,
+			self.a / det,
+			(self.c * self.d - self.a * self.f) / det,
+		))
+	}
+
+	/// Returns a matrix that applies this transform, then rotates clockwise
+	/// by `angle` degrees.
+	///
+	/// Equivalent to `matrix.Turn(angle)` in DM.
+	pub fn rotate(&self, angle: f32) -> Self {
+		self.multiply(&Self::rotation(angle))
+	}
+
+	/// Returns a matrix that applies this transform, then scales.
+	///
+	/// Equivalent to `matrix.Scale(x, y)` in DM.
+	pub fn scale(&self, x: f32, y: f32) -> Self {
+		self.multiply(&Self::scaling(x, y))
+	}
+
+	/// Returns a matrix that applies this transform, then moves.
+	///
+	/// Equivalent to `matrix.Translate(x, y)` in DM.
+	pub fn translate(&self, x: f32, y: f32) -> Self {
+		Self::new(self.a, self.b, self.c + x, self.d, self.e, self.f + y)
+	}
+
+	/// Transforms a point.
+	pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+		(
+			self.a * x + self.b * y + self.c,
+			self.d * x + self.e * y + self.f,
+		)
+	}
+
+	/// Interpolates between two matrices, where a `t` of 0 returns `self` and
+	/// a `t` of 1 returns `other`.
+	///
+	/// Like `matrix.Interpolate()` in DM, and the way `animate()` blends
+	/// transforms, the rotation, scale and translation are interpolated
+	/// separately, so rotations turn rather than squashing through zero.
+	/// Rotations take the shortest way around.
+	pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+		let (from, to) = (self.decompose(), other.decompose());
+		let mut turn = (to.angle - from.angle) % 360.0;
+		if turn > 180.0 {
+			turn -= 360.0;
+		} else if turn < -180.0 {
+			turn += 360.0;
+		}
+		let lerp = |from: f32, to: f32| from + (to - from) * t;
+		let shape = Self::new(
+			lerp(from.scale_x, to.scale_x),
+			lerp(from.shear, to.shear),
+			0.0,
+			0.0,
+			lerp(from.scale_y, to.scale_y),
+			0.0,
+		);
+		shape
+			.rotate(from.angle + turn * t)
+			.translate(lerp(self.c, other.c), lerp(self.f, other.f))
+	}
+
+	/// Splits the non-translation part of the matrix into a scale and shear,
+	/// followed by a rotation.
+	fn decompose(&self) -> Decomposed {
+		let angle = (-self.d).atan2(self.a);
+		let (sin, cos) = angle.sin_cos();
+		Decomposed {
+			angle: angle.to_degrees(),
+			scale_x: cos * self.a - sin * self.d,
+			shear: cos * self.b - sin * self.e,
+			scale_y: sin * self.b + cos * self.e,
+		}
+	}
+}
+
+/// A matrix without its translation, as a scale and shear followed by a
+/// rotation.
+struct Decomposed {
+	angle: f32,
+	scale_x: f32,
+	shear: f32,
+	scale_y: f32,
+}
+
+impl Default for ByondMatrix {
+	#[inline]
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl FromByond for ByondMatrix {
+	/// Converts either a `/matrix`, or a list of 6 numbers.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let value_type = value.get_type();
+		if value.is_list() {
+			if let Ok(components) = <[f32; 6]>::try_from(Vec::<f32>::from_byond(value)?) {
+				return Ok(components.into());
+			}
+		} else if ByondValueType::DATUM_TYPES.contains(&value_type) {
+			return Ok(Self::new(
+				value.read_var("a")?,
+				value.read_var("b")?,
+				value.read_var("c")?,
+				value.read_var("d")?,
+				value.read_var("e")?,
+				value.read_var("f")?,
+			));
+		}
+		Err(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("matrix"),
+			got: value_type.name(),
+		})
+	}
+}
+
+impl ToByond for ByondMatrix {
+	/// Creates a new `/matrix`.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		ByondValue::new("/matrix", <[f32; 6]>::from(*self).map(ByondValue::new_num))
+	}
+}
+
+impl ByondMatrix {
+	/// Converts the matrix to a list of its 6 components, rather than a
+	/// `/matrix`.
+	pub fn to_list(&self) -> ByondResult<ByondValue> {
+		<[f32; 6]>::from(*self).to_byond()
+	}
+}
+
+impl From<[f32; 6]> for ByondMatrix {
+	#[inline]
+	fn from([a, b, c, d, e, f]: [f32; 6]) -> Self {
+		Self::new(a, b, c, d, e, f)
+	}
+}
+
+impl From<ByondMatrix> for [f32; 6] {
+	#[inline]
+	fn from(matrix: ByondMatrix) -> Self {
+		[matrix.a, matrix.b, matrix.c, matrix.d, matrix.e, matrix.f]
+	}
+}
+
+/// Applies `self`, then `other`, like `matrix * matrix` in DM.
+impl Mul for ByondMatrix {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		self.multiply(&other)
+	}
+}
+
+impl MulAssign for ByondMatrix {
+	fn mul_assign(&mut self, other: Self) {
+		*self = *self * other;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_close(a: ByondMatrix, b: ByondMatrix) {
+		let (a, b) = (<[f32; 6]>::from(a), <[f32; 6]>::from(b));
+		assert!(
+			a.iter().zip(&b).all(|(a, b)| (a - b).abs() < 1e-4),
+			"{a:?} != {b:?}"
+		);
+	}
+
+	#[test]
+	fn transforms() {
+		let (x, y) = ByondMatrix::rotation(90.0).apply(0.0, 1.0);
+		assert!((x - 1.0).abs() < 1e-6 && y.abs() < 1e-6);
+
+		let matrix = ByondMatrix::IDENTITY
+			.scale(2.0, 3.0)
+			.rotate(30.0)
+			.translate(5.0, -4.0);
+		let (x, y) = matrix.apply(1.0, 1.0);
+		let (x, y) = matrix.invert().unwrap().apply(x, y);
+		assert!((x - 1.0).abs() < 1e-5 && (y - 1.0).abs() < 1e-5);
+		assert_close(matrix * matrix.invert().unwrap(), ByondMatrix::IDENTITY);
+		assert_eq!(ByondMatrix::scaling(0.0, 1.0).invert(), None);
+
+		// Scale then translate is different from translate then scale.
+		assert_eq!(
+			ByondMatrix::scaling(2.0, 2.0) * ByondMatrix::translation(1.0, 0.0),
+			ByondMatrix::new(2.0, 0.0, 1.0, 0.0, 2.0, 0.0)
+		);
+		assert_eq!(
+			ByondMatrix::translation(1.0, 0.0) * ByondMatrix::scaling(2.0, 2.0),
+			ByondMatrix::new(2.0, 0.0, 2.0, 0.0, 2.0, 0.0)
+		);
+	}
+
+	#[test]
+	fn interpolate() {
+		let from = ByondMatrix::IDENTITY;
+		let to = ByondMatrix::scaling(3.0, 3.0)
+			.rotate(90.0)
+			.translate(10.0, 0.0);
+		assert_close(from.interpolate(&to, 0.0), from);
+		assert_close(from.interpolate(&to, 1.0), to);
+		assert_close(
+			from.interpolate(&to, 0.5),
+			ByondMatrix::scaling(2.0, 2.0)
+				.rotate(45.0)
+				.translate(5.0, 0.0),
+		);
+
+		// Takes the shortest way around, rather than through 180.
+		let from = ByondMatrix::rotation(170.0);
+		let to = ByondMatrix::rotation(-170.0);
+		assert_close(from.interpolate(&to, 0.5), ByondMatrix::rotation(180.0));
+	}
+}
@@ -0,0 +1,485 @@
+// SPDX-License-Identifier: 0BSD
+//! A* and Dijkstra pathfinding over the tile grid.
+//!
+//! Searches run against a [`PathGrid`], which decides which steps are
+//! possible and how much they cost. That's either a [`CostGrid`] snapshot,
+//! taken on the main thread so the search itself can run anywhere, or any
+//! `Fn(ByondXYZ, ByondXYZ) -> Option<f32>` closure.
+//!
+//! ```no_run
+//! #[meowtonin::byond_fn]
+//! pub fn find_path_between(
+//!     start: meowtonin::ByondXYZ,
+//!     goal: meowtonin::ByondXYZ,
+//! ) -> meowtonin::ByondResult<Option<Vec<meowtonin::ByondXYZ>>> {
+//!     use meowtonin::path::*;
+//!
+//!     let block = meowtonin::ByondBlock::around(start, 32);
+//!     let grid = CostGrid::capture_density(block)?;
+//!     let options = PathOptions::default().diagonals(Diagonals::Never);
+//!     Ok(find_path(&grid, start, goal, &options))
+//! }
+//! ```
+//!
+//! Returning a `Vec<ByondXYZ>` to DM gives a list of turfs.
+mod cache;
+mod grid;
+
+pub use self::{
+	cache::PathCache,
+	grid::{CostGrid, PathGrid},
+};
+
+use crate::{
+	ByondDir, ByondError, ByondResult, ByondValue, ByondXYZ, ToByond, byond, proc::byond_return,
+	sync::thread_sync,
+};
+use ahash::AHashMap;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Which search algorithm to use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Algorithm {
+	/// A* search, using the octile (or Manhattan) distance to the goal as a
+	/// heuristic.
+	///
+	/// This assumes every step costs at least 1, and that links don't move
+	/// horizontally - otherwise, the path found may not be the cheapest one.
+	#[default]
+	AStar,
+	/// Dijkstra's algorithm, which always finds the cheapest path, but
+	/// explores far more tiles than A* to do so.
+	Dijkstra,
+}
+
+/// Whether diagonal steps are allowed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Diagonals {
+	/// Only cardinal steps are allowed.
+	Never,
+	/// Diagonal steps are allowed, even between two blocked tiles.
+	Always,
+	/// Diagonal steps are allowed, but only if both of the cardinal tiles
+	/// beside it can be stepped onto, so corners can't be cut.
+	#[default]
+	NoCornerCutting,
+}
+
+/// Options for a pathfinding search.
+#[derive(Debug, Clone)]
+pub struct PathOptions {
+	/// The search algorithm to use.
+	pub algorithm: Algorithm,
+	/// Whether diagonal steps are allowed.
+	pub diagonals: Diagonals,
+	/// How much more a diagonal step costs than a cardinal one.
+	///
+	/// Defaults to √2, set this to 1 to match BYOND's tile distance.
+	pub diagonal_cost: f32,
+	/// The maximum total cost of a path, after which the search gives up.
+	pub max_distance: Option<f32>,
+	/// The maximum number of tiles to explore, after which the search gives
+	/// up.
+	pub max_nodes: Option<usize>,
+	/// Extra one-way links between tiles, such as stairs or ladders between
+	/// z-levels, along with the cost of using them.
+	pub links: AHashMap<ByondXYZ, Vec<(ByondXYZ, f32)>>,
+}
+
+impl Default for PathOptions {
+	fn default() -> Self {
+		Self {
+			algorithm: Algorithm::default(),
+			diagonals: Diagonals::default(),
+			diagonal_cost: std::f32::consts::SQRT_2,
+			max_distance: None,
+			max_nodes: None,
+			links: AHashMap::new(),
+		}
+	}
+}
+
+impl PathOptions {
+	pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+		self.algorithm = algorithm;
+		self
+	}
+
+	pub fn diagonals(mut self, diagonals: Diagonals) -> Self {
+		self.diagonals = diagonals;
+		self
+	}
+
+	pub fn diagonal_cost(mut self, diagonal_cost: f32) -> Self {
+		self.diagonal_cost = diagonal_cost;
+		self
+	}
+
+	pub fn max_distance(mut self, max_distance: f32) -> Self {
+		self.max_distance = Some(max_distance);
+		self
+	}
+
+	pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+		self.max_nodes = Some(max_nodes);
+		self
+	}
+
+	/// Adds a one-way link from one tile to another.
+	pub fn link(mut self, from: ByondXYZ, to: ByondXYZ, cost: f32) -> Self {
+		self.links.entry(from).or_default().push((to, cost));
+		self
+	}
+
+	/// Adds a link that can be used in both directions.
+	pub fn link_both(self, a: ByondXYZ, b: ByondXYZ, cost: f32) -> Self {
+		self.link(a, b, cost).link(b, a, cost)
+	}
+
+	/// Calls `callback` with every tile that can be reached in one step from
+	/// `from`, along with the cost of that step.
+	fn for_each_neighbor<Grid, Callback>(&self, grid: &Grid, from: ByondXYZ, mut callback: Callback)
+	where
+		Grid: PathGrid + ?Sized,
+		Callback: FnMut(ByondXYZ, f32),
+	{
+		for dir in ByondDir::CARDINALS {
+			let to = from.step(dir);
+			if let Some(cost) = grid.cost(from, to) {
+				callback(to, cost);
+			}
+		}
+		if self.diagonals != Diagonals::Never {
+			for dir in ByondDir::DIAGONALS {
+				if self.diagonals == Diagonals::NoCornerCutting {
+					let vertical = from.step(dir & (ByondDir::NORTH | ByondDir::SOUTH));
+					let horizontal = from.step(dir & (ByondDir::EAST | ByondDir::WEST));
+					if grid.cost(from, vertical).is_none() || grid.cost(from, horizontal).is_none()
+					{
+						continue;
+					}
+				}
+				let to = from.step(dir);
+				if let Some(cost) = grid.cost(from, to) {
+					callback(to, cost * self.diagonal_cost);
+				}
+			}
+		}
+		if let Some(links) = self.links.get(&from) {
+			for &(to, cost) in links {
+				callback(to, cost);
+			}
+		}
+	}
+
+	fn heuristic(&self, from: ByondXYZ, to: ByondXYZ) -> f32 {
+		if self.algorithm == Algorithm::Dijkstra {
+			return 0.0;
+		}
+		let dx = (from.x() as f32 - to.x() as f32).abs();
+		let dy = (from.y() as f32 - to.y() as f32).abs();
+		match self.diagonals {
+			Diagonals::Never => dx + dy,
+			_ => {
+				let diagonal_cost = self.diagonal_cost.min(2.0);
+				dx.max(dy) + (diagonal_cost - 1.0) * dx.min(dy)
+			}
+		}
+	}
+}
+
+/// An entry in the open set, ordered so the [`BinaryHeap`] pops the lowest
+/// estimated cost first.
+#[derive(Debug, Copy, Clone)]
+struct OpenNode {
+	estimate: f32,
+	cost: f32,
+	xyz: ByondXYZ,
+}
+
+impl PartialEq for OpenNode {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for OpenNode {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other
+			.estimate
+			.total_cmp(&self.estimate)
+			.then_with(|| self.cost.total_cmp(&other.cost))
+	}
+}
+
+/// Finds the cheapest path from `start` to `goal`, including both ends.
+///
+/// Returns `None` if there is no path, or if the search was cut off by
+/// [`max_distance`](PathOptions::max_distance) or
+/// [`max_nodes`](PathOptions::max_nodes).
+pub fn find_path<Grid>(
+	grid: &Grid,
+	start: ByondXYZ,
+	goal: ByondXYZ,
+	options: &PathOptions,
+) -> Option<Vec<ByondXYZ>>
+where
+	Grid: PathGrid + ?Sized,
+{
+	let mut open = BinaryHeap::new();
+	let mut costs = AHashMap::<ByondXYZ, f32>::new();
+	let mut came_from = AHashMap::<ByondXYZ, ByondXYZ>::new();
+	let mut explored = 0_usize;
+
+	costs.insert(start, 0.0);
+	open.push(OpenNode {
+		estimate: options.heuristic(start, goal),
+		cost: 0.0,
+		xyz: start,
+	});
+	while let Some(node) = open.pop() {
+		if node.xyz == goal {
+			return Some(reconstruct_path(&came_from, goal));
+		}
+		// skip stale entries that were already reached more cheaply
+		if costs.get(&node.xyz).is_some_and(|&cost| node.cost > cost) {
+			continue;
+		}
+		explored += 1;
+		if options.max_nodes.is_some_and(|max| explored > max) {
+			return None;
+		}
+		options.for_each_neighbor(grid, node.xyz, |next, step_cost| {
+			let cost = node.cost + step_cost;
+			if options.max_distance.is_some_and(|max| cost > max) {
+				return;
+			}
+			if costs.get(&next).is_none_or(|&existing| cost < existing) {
+				costs.insert(next, cost);
+				came_from.insert(next, node.xyz);
+				open.push(OpenNode {
+					estimate: cost + options.heuristic(next, goal),
+					cost,
+					xyz: next,
+				});
+			}
+		});
+	}
+	None
+}
+
+/// Finds the cost of reaching every tile reachable from `start`, using
+/// Dijkstra's algorithm, respecting [`max_distance`](PathOptions::max_distance)
+/// and [`max_nodes`](PathOptions::max_nodes).
+///
+/// Useful for things like "how far can this mob move" or flow-field
+/// pathing towards a single target.
+pub fn reachable<Grid>(
+	grid: &Grid,
+	start: ByondXYZ,
+	options: &PathOptions,
+) -> AHashMap<ByondXYZ, f32>
+where
+	Grid: PathGrid + ?Sized,
+{
+	let mut open = BinaryHeap::new();
+	let mut costs = AHashMap::<ByondXYZ, f32>::new();
+	let mut explored = 0_usize;
+
+	costs.insert(start, 0.0);
+	open.push(OpenNode {
+		estimate: 0.0,
+		cost: 0.0,
+		xyz: start,
+	});
+	while let Some(node) = open.pop() {
+		if costs.get(&node.xyz).is_some_and(|&cost| node.cost > cost) {
+			continue;
+		}
+		explored += 1;
+		if options.max_nodes.is_some_and(|max| explored > max) {
+			break;
+		}
+		options.for_each_neighbor(grid, node.xyz, |next, step_cost| {
+			let cost = node.cost + step_cost;
+			if options.max_distance.is_some_and(|max| cost > max) {
+				return;
+			}
+			if costs.get(&next).is_none_or(|&existing| cost < existing) {
+				costs.insert(next, cost);
+				open.push(OpenNode {
+					estimate: cost,
+					cost,
+					xyz: next,
+				});
+			}
+		});
+	}
+	costs
+}
+
+fn reconstruct_path(came_from: &AHashMap<ByondXYZ, ByondXYZ>, goal: ByondXYZ) -> Vec<ByondXYZ> {
+	let mut path = vec![goal];
+	let mut current = goal;
+	while let Some(&previous) = came_from.get(&current) {
+		path.push(previous);
+		current = previous;
+	}
+	path.reverse();
+	path
+}
+
+/// Runs [`find_path`] on rayon's thread pool, and returns the result (a list of
+/// turfs, or null if there's no path) to a proc sleeping on a `byond,await:`
+/// call.
+///
+/// `waiting_proc` is the `/callee` passed to await-style functions, see
+/// [`byond_return`]. Requires BYOND 516.1664 or later.
+///
+/// ```no_run
+/// use meowtonin::{ByondBlock, ByondValue, ByondXYZ, FromByond, path::*, sys::CByondValue};
+///
+/// // var/list/path = call_ext("mylib", "byond,await:find_path_async")(start, goal)
+/// #[unsafe(no_mangle)]
+/// pub unsafe extern "C-unwind" fn find_path_async(
+///     argc: meowtonin::sys::u4c,
+///     argv: *const CByondValue,
+///     waiting_proc: CByondValue,
+/// ) {
+///     let args = unsafe { std::slice::from_raw_parts(argv.cast::<ByondValue>(), argc as usize) };
+///     let waiting_proc = ByondValue(waiting_proc);
+///     let (Ok(start), Ok(goal)) = (
+///         ByondXYZ::from_byond(args[0].clone()),
+///         ByondXYZ::from_byond(args[1].clone()),
+///     ) else {
+///         let _ = meowtonin::proc::byond_return(&waiting_proc, ());
+///         return;
+///     };
+///     let grid = CostGrid::capture_density(ByondBlock::around(start, 64)).unwrap();
+///     find_path_await(&waiting_proc, grid, start, goal, PathOptions::default()).unwrap();
+/// }
+/// ```
+pub fn find_path_await<Grid>(
+	waiting_proc: &ByondValue,
+	grid: Grid,
+	start: ByondXYZ,
+	goal: ByondXYZ,
+	options: PathOptions,
+) -> ByondResult<()>
+where
+	Grid: PathGrid + Send + 'static,
+{
+	if !byond().capabilities().await_return {
+		return Err(ByondError::unsupported("Byond_Return", 516, 1664));
+	}
+	let waiting_proc = waiting_proc.clone();
+	rayon::spawn(move || {
+		let path = find_path(&grid, start, goal, &options);
+		thread_sync(
+			move || {
+				let result = path
+					.and_then(|path| path.to_byond().ok())
+					.unwrap_or_default();
+				let _ = byond_return(&waiting_proc, result);
+				ByondValue::NULL
+			},
+			false,
+		);
+	});
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn grid(map: &str) -> CostGrid {
+		let map = crate::grid::ascii_map(map);
+		CostGrid::from_fn(map.block(), |xyz| map[xyz].then_some(1.0))
+	}
+
+	#[test]
+	fn straight_line() {
+		let grid = grid(".....\n.....\n.....");
+		let start = ByondXYZ::new(1, 2, 1);
+		let goal = ByondXYZ::new(5, 2, 1);
+		let path = find_path(&grid, start, goal, &PathOptions::default()).unwrap();
+		assert_eq!(path.len(), 5);
+		assert_eq!(path.first(), Some(&start));
+		assert_eq!(path.last(), Some(&goal));
+		assert_eq!(
+			find_path(&grid, start, start, &PathOptions::default()),
+			Some(vec![start])
+		);
+	}
+
+	#[test]
+	fn around_walls() {
+		let grid = grid(".#...\n.#.#.\n...#.");
+		let start = ByondXYZ::new(1, 3, 1);
+		let goal = ByondXYZ::new(5, 1, 1);
+		for algorithm in [Algorithm::AStar, Algorithm::Dijkstra] {
+			let options = PathOptions::default()
+				.algorithm(algorithm)
+				.diagonals(Diagonals::Never);
+			let path = find_path(&grid, start, goal, &options).unwrap();
+			assert_eq!(path.len(), 11);
+			assert!(path.windows(2).all(|pair| pair[0].distance(&pair[1]) == 1));
+			assert!(path.iter().all(|xyz| grid.get(*xyz).is_some()));
+		}
+		let mut blocked = grid.clone();
+		blocked.set(ByondXYZ::new(3, 3, 1), None);
+		assert_eq!(
+			find_path(&blocked, start, goal, &PathOptions::default()),
+			None
+		);
+	}
+
+	#[test]
+	fn diagonals() {
+		let grid = grid("..\n#.");
+		let start = ByondXYZ::new(1, 2, 1);
+		let goal = ByondXYZ::new(2, 1, 1);
+		let cutting = PathOptions::default().diagonals(Diagonals::Always);
+		assert_eq!(find_path(&grid, start, goal, &cutting).unwrap().len(), 2);
+		let no_cutting = PathOptions::default().diagonals(Diagonals::NoCornerCutting);
+		assert_eq!(find_path(&grid, start, goal, &no_cutting).unwrap().len(), 3);
+	}
+
+	#[test]
+	fn cutoffs_and_links() {
+		let grid = grid("..........");
+		let start = ByondXYZ::new(1, 1, 1);
+		let goal = ByondXYZ::new(10, 1, 1);
+		let options = PathOptions::default().max_distance(5.0);
+		assert_eq!(find_path(&grid, start, goal, &options), None);
+		assert_eq!(reachable(&grid, start, &options).len(), 6);
+
+		let upstairs = ByondXYZ::new(10, 1, 2);
+		let options = PathOptions::default().link(goal, upstairs, 1.0);
+		let path = find_path(&grid, start, upstairs, &options).unwrap();
+		assert_eq!(path.last(), Some(&upstairs));
+		assert_eq!(path.len(), 11);
+	}
+
+	#[test]
+	fn closures() {
+		let open = |_: ByondXYZ, to: ByondXYZ| (to.x() != 3 || to.y() == 5).then_some(1.0);
+		let path = find_path(
+			&open,
+			ByondXYZ::new(1, 1, 1),
+			ByondXYZ::new(5, 1, 1),
+			&PathOptions::default().max_nodes(1000),
+		)
+		.unwrap();
+		assert!(path.contains(&ByondXYZ::new(3, 5, 1)));
+	}
+}
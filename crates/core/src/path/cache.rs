@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: 0BSD
+use super::{PathGrid, PathOptions, find_path};
+use crate::ByondXYZ;
+use ahash::AHashMap;
+use parking_lot::Mutex;
+use std::{collections::VecDeque, sync::Arc};
+
+type PathKey = (ByondXYZ, ByondXYZ);
+
+/// A cache of pathfinding results, including failed searches.
+///
+/// The cache doesn't know when the map changes, so it assumes every search
+/// uses the same grid and options. Use [`invalidate`](Self::invalidate) or
+/// [`clear`](Self::clear) when they change.
+pub struct PathCache {
+	capacity: usize,
+	inner: Mutex<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+	paths: AHashMap<PathKey, Option<Arc<[ByondXYZ]>>>,
+	order: VecDeque<PathKey>,
+}
+
+impl PathCache {
+	/// Creates a cache holding up to `capacity` results, evicting the oldest
+	/// ones first.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity: capacity.max(1),
+			inner: Mutex::new(CacheInner::default()),
+		}
+	}
+
+	/// Returns the cached path from `start` to `goal`, or runs [`find_path`]
+	/// and caches the result if there isn't one.
+	pub fn find_path<Grid>(
+		&self,
+		grid: &Grid,
+		start: ByondXYZ,
+		goal: ByondXYZ,
+		options: &PathOptions,
+	) -> Option<Arc<[ByondXYZ]>>
+	where
+		Grid: PathGrid + ?Sized,
+	{
+		let key = (start, goal);
+		if let Some(path) = self.inner.lock().paths.get(&key) {
+			return path.clone();
+		}
+		let path: Option<Arc<[ByondXYZ]>> = find_path(grid, start, goal, options).map(Into::into);
+		let mut inner = self.inner.lock();
+		if inner.paths.insert(key, path.clone()).is_none() {
+			inner.order.push_back(key);
+		}
+		while inner.order.len() > self.capacity {
+			if let Some(oldest) = inner.order.pop_front() {
+				inner.paths.remove(&oldest);
+			}
+		}
+		path
+	}
+
+	/// Removes every cached path that passes through the given tile, along with
+	/// every failed search, as the tile may have just become passable.
+	pub fn invalidate(&self, xyz: ByondXYZ) {
+		let mut inner = self.inner.lock();
+		inner
+			.paths
+			.retain(|_, path| path.as_ref().is_some_and(|path| !path.contains(&xyz)));
+		let CacheInner { paths, order } = &mut *inner;
+		order.retain(|key| paths.contains_key(key));
+	}
+
+	/// Removes every cached path.
+	pub fn clear(&self) {
+		let mut inner = self.inner.lock();
+		inner.paths.clear();
+		inner.order.clear();
+	}
+
+	/// Returns the number of cached results.
+	pub fn len(&self) -> usize {
+		self.inner.lock().paths.len()
+	}
+
+	/// Returns if there are no cached results.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
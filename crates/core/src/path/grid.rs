@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{ByondBlock, ByondResult, ByondValue, ByondXYZ};
+use std::{rc::Rc, sync::Arc};
+
+/// A source of passability and movement costs for pathfinding.
+pub trait PathGrid {
+	/// Returns the cost of stepping from `from` onto the adjacent tile `to`,
+	/// or `None` if that step isn't possible.
+	fn cost(&self, from: ByondXYZ, to: ByondXYZ) -> Option<f32>;
+}
+
+impl<Func> PathGrid for Func
+where
+	Func: Fn(ByondXYZ, ByondXYZ) -> Option<f32>,
+{
+	#[inline]
+	fn cost(&self, from: ByondXYZ, to: ByondXYZ) -> Option<f32> {
+		self(from, to)
+	}
+}
+
+impl<Grid> PathGrid for Arc<Grid>
+where
+	Grid: PathGrid + ?Sized,
+{
+	#[inline]
+	fn cost(&self, from: ByondXYZ, to: ByondXYZ) -> Option<f32> {
+		(**self).cost(from, to)
+	}
+}
+
+impl<Grid> PathGrid for Rc<Grid>
+where
+	Grid: PathGrid + ?Sized,
+{
+	#[inline]
+	fn cost(&self, from: ByondXYZ, to: ByondXYZ) -> Option<f32> {
+		(**self).cost(from, to)
+	}
+}
+
+/// A snapshot of the cost of entering each tile in a block, where `None`
+/// means the tile can't be entered.
+///
+/// Tiles outside of the block can't be entered either.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostGrid {
+	block: ByondBlock,
+	costs: Vec<Option<f32>>,
+}
+
+impl CostGrid {
+	/// Creates a grid where every tile has the same cost.
+	pub fn new(block: ByondBlock, cost: Option<f32>) -> Self {
+		Self {
+			block,
+			costs: vec![cost; block.len()],
+		}
+	}
+
+	/// Creates a grid by calling `cost` for every tile in the block.
+	pub fn from_fn<Func>(block: ByondBlock, cost: Func) -> Self
+	where
+		Func: FnMut(ByondXYZ) -> Option<f32>,
+	{
+		Self {
+			block,
+			costs: block.iter().map(cost).collect(),
+		}
+	}
+
+	/// Snapshots the map by calling `cost` with every turf in the block, which
+	/// are all fetched with a single `block()` call.
+	///
+	/// Tiles outside of the map can't be entered.
+	///
+	/// Must be called on the main thread, but the resulting grid can be used
+	/// on any thread.
+	pub fn capture<Func>(block: ByondBlock, mut cost: Func) -> ByondResult<Self>
+	where
+		Func: FnMut(&ByondValue) -> ByondResult<Option<f32>>,
+	{
+		let mut grid = Self::new(block, None);
+		let Some(world) = ByondBlock::world()? else {
+			return Ok(grid);
+		};
+		let Some(on_map) = block.intersect(&world) else {
+			return Ok(grid);
+		};
+		for (xyz, turf) in on_map.iter().zip(on_map.turfs()?) {
+			grid.set(xyz, cost(&turf)?);
+		}
+		Ok(grid)
+	}
+
+	/// Snapshots the map, where dense turfs can't be entered, and every other
+	/// turf costs 1.
+	pub fn capture_density(block: ByondBlock) -> ByondResult<Self> {
+		Self::capture(block, |turf| {
+			let dense: bool = turf.read_var("density")?;
+			Ok((!dense).then_some(1.0))
+		})
+	}
+
+	/// Returns the block this grid covers.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.block
+	}
+
+	/// Returns the cost of entering the given tile, or `None` if it can't be
+	/// entered.
+	pub fn get(&self, xyz: ByondXYZ) -> Option<f32> {
+		self.block.index_of(&xyz).and_then(|idx| self.costs[idx])
+	}
+
+	/// Sets the cost of entering the given tile, returning `false` if it's
+	/// outside of the grid.
+	pub fn set(&mut self, xyz: ByondXYZ, cost: Option<f32>) -> bool {
+		match self.block.index_of(&xyz) {
+			Some(idx) => {
+				self.costs[idx] = cost;
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+impl PathGrid for CostGrid {
+	#[inline]
+	fn cost(&self, _from: ByondXYZ, to: ByondXYZ) -> Option<f32> {
+		self.get(to)
+	}
+}
@@ -1,6 +1,16 @@
 // SPDX-License-Identifier: 0BSD
-use crate::sys::{CByondPixLoc, s2c};
-use std::hash::Hash;
+use crate::{
+	ByondDir, ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond, byond,
+	sys::{CByondPixLoc, s2c},
+	vector::ByondVector,
+	xyz::ByondXYZ,
+};
+use std::{
+	borrow::Cow,
+	hash::Hash,
+	mem::MaybeUninit,
+	ops::{Add, AddAssign, Sub, SubAssign},
+};
 
 #[derive(Copy, Clone)]
 #[repr(transparent)]
@@ -33,6 +43,185 @@ pub const fn y(&self) -> f32 {
 	pub const fn z(&self) -> i16 {
 		self.0.z
 	}
+
+	/// Returns the tile containing this pixloc, given the size of a tile
+	/// (`world.icon_size`).
+	///
+	/// Pixel coordinates start at 1, so `1,1` is the bottom-left pixel of
+	/// the `1,1` tile.
+	pub fn to_xyz(&self, (tile_width, tile_height): (u16, u16)) -> ByondXYZ {
+		let x = ((self.x() - 1.0) / tile_width as f32).floor() as i16 + 1;
+		let y = ((self.y() - 1.0) / tile_height as f32).floor() as i16 + 1;
+		ByondXYZ::new(x, y, self.z())
+	}
+
+	/// Returns the pixloc of the bottom-left pixel of the given tile, given
+	/// the size of a tile (`world.icon_size`).
+	pub fn from_xyz(xyz: ByondXYZ, (tile_width, tile_height): (u16, u16)) -> Self {
+		Self::new(
+			((xyz.x() as i32 - 1) * tile_width as i32 + 1) as f32,
+			((xyz.y() as i32 - 1) * tile_height as i32 + 1) as f32,
+			xyz.z(),
+		)
+	}
+
+	/// Returns the pixloc at the center of the given tile, given the size of
+	/// a tile (`world.icon_size`).
+	pub fn center_of(xyz: ByondXYZ, (tile_width, tile_height): (u16, u16)) -> Self {
+		let corner = Self::from_xyz(xyz, (tile_width, tile_height));
+		Self::new(
+			corner.x() + tile_width as f32 / 2.0,
+			corner.y() + tile_height as f32 / 2.0,
+			corner.z(),
+		)
+	}
+}
+
+/// Returns the size of a tile in pixels, from `world.icon_size`.
+pub fn world_icon_size() -> ByondResult<(u16, u16)> {
+	let icon_size: ByondValue = ByondValue::world().read_var("icon_size")?;
+	if icon_size.is_number() {
+		let size = icon_size.get_number()? as u16;
+		return Ok((size, size));
+	}
+	let icon_size = icon_size.get_string()?;
+	icon_size
+		.split_once('x')
+		.and_then(|(width, height)| Some((width.trim().parse().ok()?, height.trim().parse().ok()?)))
+		.ok_or_else(|| ByondError::InvalidConversion {
+			expected: Cow::Borrowed("icon size"),
+			got: Cow::Owned(icon_size),
+		})
+}
+
+/// A pixel bounding box, such as an atom's bounds.
+///
+/// Both corners are inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ByondPixBounds {
+	/// The bottom-left corner.
+	pub min: ByondPixLoc,
+	/// The top-right corner.
+	pub max: ByondPixLoc,
+}
+
+impl ByondPixBounds {
+	/// Creates a bounding box from any two opposite corners.
+	///
+	/// The Z level is taken from the first corner.
+	pub fn from_corners(a: ByondPixLoc, b: ByondPixLoc) -> Self {
+		Self {
+			min: ByondPixLoc::new(a.x().min(b.x()), a.y().min(b.y()), a.z()),
+			max: ByondPixLoc::new(a.x().max(b.x()), a.y().max(b.y()), a.z()),
+		}
+	}
+
+	/// Returns the width of the bounding box, in pixels.
+	pub fn width(&self) -> f32 {
+		self.max.x() - self.min.x()
+	}
+
+	/// Returns the height of the bounding box, in pixels.
+	pub fn height(&self) -> f32 {
+		self.max.y() - self.min.y()
+	}
+
+	/// Returns the Z level of the bounding box.
+	pub fn z(&self) -> i16 {
+		self.min.z()
+	}
+
+	/// Returns the center of the bounding box.
+	pub fn center(&self) -> ByondPixLoc {
+		ByondPixLoc::new(
+			(self.min.x() + self.max.x()) / 2.0,
+			(self.min.y() + self.max.y()) / 2.0,
+			self.z(),
+		)
+	}
+
+	/// Returns if the given pixloc is within the bounding box.
+	pub fn contains(&self, pixloc: &ByondPixLoc) -> bool {
+		pixloc.z() == self.z()
+			&& (self.min.x()..=self.max.x()).contains(&pixloc.x())
+			&& (self.min.y()..=self.max.y()).contains(&pixloc.y())
+	}
+
+	/// Returns if the other bounding box is entirely within this one.
+	pub fn contains_bounds(&self, other: &Self) -> bool {
+		self.contains(&other.min) && self.contains(&other.max)
+	}
+
+	/// Returns if the two bounding boxes overlap at all.
+	pub fn overlaps(&self, other: &Self) -> bool {
+		self.z() == other.z()
+			&& self.min.x() <= other.max.x()
+			&& other.min.x() <= self.max.x()
+			&& self.min.y() <= other.max.y()
+			&& other.min.y() <= self.max.y()
+	}
+
+	/// Returns the area where two bounding boxes overlap, if they do.
+	pub fn intersection(&self, other: &Self) -> Option<Self> {
+		if !self.overlaps(other) {
+			return None;
+		}
+		Some(Self {
+			min: ByondPixLoc::new(
+				self.min.x().max(other.min.x()),
+				self.min.y().max(other.min.y()),
+				self.z(),
+			),
+			max: ByondPixLoc::new(
+				self.max.x().min(other.max.x()),
+				self.max.y().min(other.max.y()),
+				self.z(),
+			),
+		})
+	}
+
+	/// Returns a copy of the bounding box moved by the given offset.
+	pub fn offset(&self, offset: ByondVector) -> Self {
+		Self {
+			min: self.min + offset,
+			max: self.max + offset,
+		}
+	}
+}
+
+impl ByondValue {
+	/// Gets the pixloc of one side or corner of an atom's bounding box, where
+	/// `dir` is a direction such as `NORTH` or `SOUTHWEST`.
+	///
+	/// Returns `None` if the value doesn't have pixloc coordinates, such as if
+	/// value is not an atom.
+	///
+	/// If the atom is off-map, this will return [ByondPixLoc::ZERO].
+	pub fn bound_pixloc<Dir>(&self, dir: Dir) -> Option<ByondPixLoc>
+	where
+		Dir: Into<u8>,
+	{
+		let Ok(byond_bound_pixloc) = &byond().optional().Byond_BoundPixLoc else {
+			return None;
+		};
+		let mut pixloc = MaybeUninit::uninit();
+		if unsafe { byond_bound_pixloc(&self.0, dir.into(), pixloc.as_mut_ptr()) } {
+			Some(ByondPixLoc(unsafe { pixloc.assume_init() }))
+		} else {
+			None
+		}
+	}
+
+	/// Gets the pixel bounding box of an atom.
+	///
+	/// Returns `None` if the value doesn't have pixloc coordinates, such as if
+	/// value is not an atom.
+	pub fn pixel_bounds(&self) -> Option<ByondPixBounds> {
+		Some(ByondPixBounds::from_corners(
+			self.bound_pixloc(ByondDir::SOUTHWEST)?,
+			self.bound_pixloc(ByondDir::NORTHEAST)?,
+		))
+	}
 }
 
 impl Default for ByondPixLoc {
@@ -41,6 +230,16 @@ fn default() -> Self {
 	}
 }
 
+impl std::fmt::Debug for ByondPixLoc {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ByondPixLoc")
+			.field("x", &self.x())
+			.field("y", &self.y())
+			.field("z", &self.z())
+			.finish()
+	}
+}
+
 // the automatic PartialEq deriver would also compare the junk data, and we
 // don't want that.
 impl PartialEq for ByondPixLoc {
@@ -57,6 +256,108 @@ fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
 	}
 }
 
+impl FromByond for ByondPixLoc {
+	/// Converts either a `pixloc`, or the pixloc of an atom.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let value_type = value.get_type();
+		if value_type == ByondValueType::PixLoc {
+			return Ok(Self::new(
+				value.read_var("x")?,
+				value.read_var("y")?,
+				value.read_var("z")?,
+			));
+		}
+		if ByondValueType::ATOM_TYPES.contains(&value_type) {
+			if let Some(pixloc) = value.pixloc() {
+				return Ok(pixloc);
+			}
+		}
+		Err(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("pixloc"),
+			got: value_type.name(),
+		})
+	}
+}
+
+impl ToByond for ByondPixLoc {
+	/// Creates a new `pixloc`, which requires BYOND 516 or later.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		if !byond().capabilities().pixloc {
+			return Err(ByondError::unsupported("pixloc", 516, 0));
+		}
+		ByondValue::new("/pixloc", [
+			ByondValue::new_num(self.x()),
+			ByondValue::new_num(self.y()),
+			ByondValue::new_num(self.z() as f32),
+		])
+	}
+}
+
+impl Add for ByondPixLoc {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self::new(
+			self.x() + other.x(),
+			self.y() + other.y(),
+			self.z().saturating_add(other.z()),
+		)
+	}
+}
+
+impl AddAssign for ByondPixLoc {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+impl Sub for ByondPixLoc {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self {
+		Self::new(
+			self.x() - other.x(),
+			self.y() - other.y(),
+			self.z().saturating_sub(other.z()),
+		)
+	}
+}
+
+impl SubAssign for ByondPixLoc {
+	fn sub_assign(&mut self, other: Self) {
+		*self = *self - other;
+	}
+}
+
+/// Offsets a pixloc by a vector, like `pixloc + vector` in DM.
+impl Add<ByondVector> for ByondPixLoc {
+	type Output = Self;
+
+	fn add(self, offset: ByondVector) -> Self {
+		self + Self::from(offset)
+	}
+}
+
+impl AddAssign<ByondVector> for ByondPixLoc {
+	fn add_assign(&mut self, offset: ByondVector) {
+		*self = *self + offset;
+	}
+}
+
+impl Sub<ByondVector> for ByondPixLoc {
+	type Output = Self;
+
+	fn sub(self, offset: ByondVector) -> Self {
+		self - Self::from(offset)
+	}
+}
+
+impl SubAssign<ByondVector> for ByondPixLoc {
+	fn sub_assign(&mut self, offset: ByondVector) {
+		*self = *self - offset;
+	}
+}
+
 impl AsRef<CByondPixLoc> for ByondPixLoc {
 	fn as_ref(&self) -> &CByondPixLoc {
 		&self.0
@@ -68,3 +369,57 @@ fn as_mut(&mut self) -> &mut CByondPixLoc {
 		&mut self.0
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tile_conversion() {
+		let size = (32, 32);
+		assert_eq!(
+			ByondPixLoc::new(1.0, 1.0, 1).to_xyz(size),
+			ByondXYZ::new(1, 1, 1)
+		);
+		assert_eq!(
+			ByondPixLoc::new(32.0, 32.0, 1).to_xyz(size),
+			ByondXYZ::new(1, 1, 1)
+		);
+		assert_eq!(
+			ByondPixLoc::new(33.0, 64.5, 2).to_xyz(size),
+			ByondXYZ::new(2, 2, 2)
+		);
+		let xyz = ByondXYZ::new(5, 7, 1);
+		assert_eq!(
+			ByondPixLoc::from_xyz(xyz, size),
+			ByondPixLoc::new(129.0, 193.0, 1)
+		);
+		assert_eq!(ByondPixLoc::from_xyz(xyz, size).to_xyz(size), xyz);
+		assert_eq!(ByondPixLoc::center_of(xyz, size).to_xyz(size), xyz);
+	}
+
+	#[test]
+	fn bounds() {
+		let a = ByondPixBounds::from_corners(
+			ByondPixLoc::new(10.0, 10.0, 1),
+			ByondPixLoc::new(0.0, 0.0, 1),
+		);
+		let b = ByondPixBounds::from_corners(
+			ByondPixLoc::new(5.0, 5.0, 1),
+			ByondPixLoc::new(15.0, 15.0, 1),
+		);
+		let c = a.offset(ByondVector::new(20.0, 0.0, 0.0));
+		assert!(a.contains(&ByondPixLoc::new(10.0, 0.0, 1)));
+		assert!(!a.contains(&ByondPixLoc::new(5.0, 5.0, 2)));
+		assert!(a.overlaps(&b));
+		assert!(!a.overlaps(&c));
+		assert_eq!(
+			a.intersection(&b),
+			Some(ByondPixBounds::from_corners(
+				ByondPixLoc::new(5.0, 5.0, 1),
+				ByondPixLoc::new(10.0, 10.0, 1),
+			))
+		);
+		assert!(a.contains_bounds(&a.intersection(&b).unwrap()));
+	}
+}
@@ -30,3 +30,19 @@ pub fn call_global<Name, Arg, ArgList, Return>(name: Name, args: ArgList) -> Byo
 		Return::from_byond(ByondValue(unsafe { result.assume_init() }))
 	}
 }
+
+/// Returns a value to a proc that is sleeping on a `byond,await:` call.
+///
+/// `waiting_proc` is the `/callee` of the sleeping proc, which is passed to
+/// await-style functions. Returns [`ByondError::Unsupported`] on versions of
+/// BYOND older than 516.1664.
+pub fn byond_return<Value>(waiting_proc: &ByondValue, value: Value) -> ByondResult<()>
+where
+	Value: ToByond,
+{
+	let Ok(byond_return) = &byond().optional().Byond_Return else {
+		return Err(ByondError::unsupported("Byond_Return", 516, 1664));
+	};
+	let value = value.to_byond()?;
+	map_byond_error!(byond_return(&waiting_proc.0, &value.0))
+}
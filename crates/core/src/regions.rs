@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: 0BSD
+//! Flood fills and connected-component labelling, for finding rooms, areas or
+//! networks such as pipes and power cables.
+//!
+//! Regions can be found in anything implementing [`RegionSource`], such as a
+//! [`TurfGrid`] or a [`Grid`] snapshot, using a predicate to decide which
+//! tiles are passable, and a [`Connectivity`] to decide which passable tiles
+//! are connected to each other.
+//!
+//! ```no_run
+//! use meowtonin::{
+//!     ByondValue,
+//!     grid::TurfGrid,
+//!     regions::{Connectivity, Regions},
+//! };
+//!
+//! # fn main() -> meowtonin::ByondResult<()> {
+//! let turfs = TurfGrid::new()?;
+//! let open = |turf: &ByondValue| !turf.read_var::<_, bool>("density").unwrap_or(true);
+//! if let Some(rooms) = Regions::label(&turfs, open, Connectivity::new()) {
+//!     for (_, room) in rooms.components() {
+//!         println!("found a room with {} tiles", room.len());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+	ByondBlock, ByondDir, ByondValue, ByondXYZ,
+	grid::{Grid, GridSim, SimCell, TurfGrid, VarSnapshot},
+};
+use std::collections::VecDeque;
+
+/// Something that regions can be found in, with a cell for each tile in a
+/// block.
+pub trait RegionSource {
+	/// The data stored for each tile.
+	type Cell;
+
+	/// Returns the block covered by the source, or `None` if it's empty.
+	fn block(&self) -> Option<ByondBlock>;
+
+	/// Returns the cell at the given coordinates, or `None` if they're
+	/// outside of the source.
+	fn cell(&self, xyz: ByondXYZ) -> Option<&Self::Cell>;
+}
+
+impl<Value> RegionSource for Grid<Value> {
+	type Cell = Value;
+
+	fn block(&self) -> Option<ByondBlock> {
+		Some(Grid::block(self))
+	}
+
+	fn cell(&self, xyz: ByondXYZ) -> Option<&Self::Cell> {
+		self.get(xyz)
+	}
+}
+
+impl<Value> RegionSource for VarSnapshot<Value> {
+	type Cell = Value;
+
+	fn block(&self) -> Option<ByondBlock> {
+		Some(Grid::block(self))
+	}
+
+	fn cell(&self, xyz: ByondXYZ) -> Option<&Self::Cell> {
+		self.get(xyz)
+	}
+}
+
+impl<Cell> RegionSource for GridSim<Cell>
+where
+	Cell: SimCell,
+{
+	type Cell = Cell;
+
+	fn block(&self) -> Option<ByondBlock> {
+		Some(GridSim::block(self))
+	}
+
+	fn cell(&self, xyz: ByondXYZ) -> Option<&Self::Cell> {
+		self.grid().get(xyz)
+	}
+}
+
+impl RegionSource for TurfGrid {
+	type Cell = ByondValue;
+
+	fn block(&self) -> Option<ByondBlock> {
+		TurfGrid::block(self)
+	}
+
+	fn cell(&self, xyz: ByondXYZ) -> Option<&Self::Cell> {
+		self.get(xyz)
+	}
+}
+
+type EdgeRule<'a> = Box<dyn Fn(ByondXYZ, ByondDir) -> bool + 'a>;
+
+/// Decides which neighboring tiles are connected.
+///
+/// By default, passable tiles are connected to the passable tiles next to
+/// them in the four cardinal directions.
+#[derive(Default)]
+#[must_use]
+pub struct Connectivity<'a> {
+	diagonals: bool,
+	vertical: bool,
+	edge: Option<EdgeRule<'a>>,
+}
+
+impl<'a> Connectivity<'a> {
+	/// Creates the default connectivity, with only cardinal neighbors.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets whether tiles are connected to their diagonal neighbors.
+	pub fn diagonals(mut self, diagonals: bool) -> Self {
+		self.diagonals = diagonals;
+		self
+	}
+
+	/// Sets whether tiles are connected to the tiles directly above and below
+	/// them.
+	pub fn vertical(mut self, vertical: bool) -> Self {
+		self.vertical = vertical;
+		self
+	}
+
+	/// Only connects a tile to its neighbor in a given direction if `edge`
+	/// returns true, such as for directional windows, or cables that only
+	/// connect in the directions they face.
+	///
+	/// This is only called when both tiles are passable. For
+	/// [`Regions`], the rule should be symmetric - if `edge(a, dir)` is true,
+	/// `edge(a.step(dir), dir.reverse())` should be too.
+	pub fn edge<Func>(mut self, edge: Func) -> Self
+	where
+		Func: Fn(ByondXYZ, ByondDir) -> bool + 'a,
+	{
+		self.edge = Some(Box::new(edge));
+		self
+	}
+
+	fn dirs(&self) -> impl Iterator<Item = ByondDir> {
+		let diagonals = if self.diagonals {
+			&ByondDir::DIAGONALS[..]
+		} else {
+			&[]
+		};
+		let vertical = if self.vertical {
+			&[ByondDir::UP, ByondDir::DOWN][..]
+		} else {
+			&[]
+		};
+		ByondDir::CARDINALS
+			.into_iter()
+			.chain(diagonals.iter().copied())
+			.chain(vertical.iter().copied())
+	}
+
+	fn connects(&self, from: ByondXYZ, dir: ByondDir) -> bool {
+		self.edge.as_ref().is_none_or(|edge| edge(from, dir))
+	}
+}
+
+/// Returns every tile connected to `start`, in the order they were reached,
+/// starting with `start` itself.
+///
+/// Returns nothing if `start` is impassable, or outside of the source.
+pub fn flood_fill<Source, Pass>(
+	source: &Source,
+	start: ByondXYZ,
+	mut passable: Pass,
+	connectivity: &Connectivity,
+) -> Vec<ByondXYZ>
+where
+	Source: RegionSource,
+	Pass: FnMut(&Source::Cell) -> bool,
+{
+	let Some(block) = source.block() else {
+		return Vec::new();
+	};
+	let mut is_passable = |xyz: ByondXYZ| source.cell(xyz).is_some_and(&mut passable);
+	let Some(start_idx) = block.index_of(&start).filter(|_| is_passable(start)) else {
+		return Vec::new();
+	};
+	let mut seen = vec![false; block.len()];
+	seen[start_idx] = true;
+	let mut queue = VecDeque::from([start]);
+	let mut region = Vec::new();
+	while let Some(xyz) = queue.pop_front() {
+		region.push(xyz);
+		for dir in connectivity.dirs() {
+			let next = xyz.step(dir);
+			let Some(idx) = block.index_of(&next) else {
+				continue;
+			};
+			if !seen[idx] && is_passable(next) && connectivity.connects(xyz, dir) {
+				seen[idx] = true;
+				queue.push_back(next);
+			}
+		}
+	}
+	region
+}
+
+/// Every connected region of passable tiles in a block, each with a numeric
+/// ID.
+///
+/// After the initial labelling, single tiles can be updated with
+/// [`set_passable`](Self::set_passable) and [`update`](Self::update), which
+/// only relabel the regions around that tile rather than the whole block.
+/// IDs are stable, except for regions affected by an update, and the IDs of
+/// removed regions are reused.
+pub struct Regions<'a> {
+	connectivity: Connectivity<'a>,
+	passable: Grid<bool>,
+	labels: Grid<Option<u32>>,
+	components: Vec<Vec<ByondXYZ>>,
+	free: Vec<u32>,
+}
+
+impl<'a> Regions<'a> {
+	/// Labels every connected region in the source, returning `None` if the
+	/// source is empty.
+	pub fn label<Source, Pass>(
+		source: &Source,
+		mut passable: Pass,
+		connectivity: Connectivity<'a>,
+	) -> Option<Self>
+	where
+		Source: RegionSource,
+		Pass: FnMut(&Source::Cell) -> bool,
+	{
+		let block = source.block()?;
+		let passable = Grid::from_fn(block, |xyz| source.cell(xyz).is_some_and(&mut passable));
+		Some(Self::from_passable(passable, connectivity))
+	}
+
+	/// Labels every connected region in a grid of passable tiles.
+	pub fn from_passable(passable: Grid<bool>, connectivity: Connectivity<'a>) -> Self {
+		let mut regions = Self {
+			connectivity,
+			labels: Grid::new(passable.block(), None),
+			passable,
+			components: Vec::new(),
+			free: Vec::new(),
+		};
+		for xyz in regions.passable.block() {
+			regions.fill_if_unlabelled(xyz);
+		}
+		regions
+	}
+
+	/// Returns the block covered by the regions.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.passable.block()
+	}
+
+	/// Returns the number of regions.
+	pub fn len(&self) -> usize {
+		self.components.len() - self.free.len()
+	}
+
+	/// Returns if there are no regions, meaning every tile is impassable.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the ID of the region containing the given tile, or `None` if
+	/// it's impassable.
+	pub fn id_at(&self, xyz: ByondXYZ) -> Option<u32> {
+		self.labels.get(xyz).copied().flatten()
+	}
+
+	/// Returns the ID of every tile, or `None` for impassable tiles.
+	#[inline]
+	pub fn labels(&self) -> &Grid<Option<u32>> {
+		&self.labels
+	}
+
+	/// Returns if the given tile is passable.
+	pub fn is_passable(&self, xyz: ByondXYZ) -> bool {
+		self.passable.get(xyz).copied().unwrap_or(false)
+	}
+
+	/// Returns if two tiles are in the same region.
+	pub fn connected(&self, a: ByondXYZ, b: ByondXYZ) -> bool {
+		self.id_at(a).is_some_and(|id| self.id_at(b) == Some(id))
+	}
+
+	/// Returns the coordinates of every tile in a region, or `None` if there's
+	/// no region with that ID.
+	pub fn component(&self, id: u32) -> Option<&[ByondXYZ]> {
+		self.components
+			.get(id as usize)
+			.filter(|tiles| !tiles.is_empty())
+			.map(Vec::as_slice)
+	}
+
+	/// Returns the coordinates of every tile in the same region as the given
+	/// tile.
+	pub fn component_at(&self, xyz: ByondXYZ) -> Option<&[ByondXYZ]> {
+		self.id_at(xyz).and_then(|id| self.component(id))
+	}
+
+	/// Iterates over every region, along with its ID.
+	pub fn components(&self) -> impl Iterator<Item = (u32, &[ByondXYZ])> + '_ {
+		self.components
+			.iter()
+			.enumerate()
+			.filter(|(_, tiles)| !tiles.is_empty())
+			.map(|(id, tiles)| (id as u32, tiles.as_slice()))
+	}
+
+	/// Returns the turfs in a region, looked up from a [`TurfGrid`], or `None`
+	/// if there's no region with that ID.
+	///
+	/// Tiles that aren't in the turf grid are skipped.
+	pub fn turfs(&self, id: u32, turfs: &TurfGrid) -> Option<Vec<ByondValue>> {
+		self.component(id).map(|tiles| {
+			tiles
+				.iter()
+				.filter_map(|xyz| turfs.get(*xyz).cloned())
+				.collect()
+		})
+	}
+
+	/// Changes whether a tile is passable, relabelling the regions around it,
+	/// and returning `true` if anything changed.
+	///
+	/// Making a tile passable may merge regions together, and making it
+	/// impassable may split a region apart.
+	pub fn set_passable(&mut self, xyz: ByondXYZ, passable: bool) -> bool {
+		match self.passable.set(xyz, passable) {
+			Some(old) if old != passable => {
+				self.update(xyz);
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/// Relabels the regions around a tile, for when the edge rule's result
+	/// for that tile has changed.
+	pub fn update(&mut self, xyz: ByondXYZ) {
+		let mut affected = Vec::new();
+		for tile in std::iter::once(xyz).chain(self.connectivity.dirs().map(|dir| xyz.step(dir))) {
+			if let Some(id) = self.id_at(tile) {
+				if !affected.contains(&id) {
+					affected.push(id);
+				}
+			}
+		}
+		let mut tiles = Vec::new();
+		for id in affected {
+			let component = std::mem::take(&mut self.components[id as usize]);
+			for tile in &component {
+				if let Some(label) = self.labels.get_mut(*tile) {
+					*label = None;
+				}
+			}
+			tiles.extend(component);
+			self.free.push(id);
+		}
+		for tile in std::iter::once(xyz).chain(tiles) {
+			self.fill_if_unlabelled(tile);
+		}
+	}
+
+	/// Labels the region containing `start`, if it's passable and hasn't been
+	/// labelled yet.
+	fn fill_if_unlabelled(&mut self, start: ByondXYZ) {
+		if !self.is_passable(start) || self.id_at(start).is_some() {
+			return;
+		}
+		let id = self.free.pop().unwrap_or_else(|| {
+			self.components.push(Vec::new());
+			(self.components.len() - 1) as u32
+		});
+		let mut component = Vec::new();
+		let mut queue = VecDeque::from([start]);
+		self.labels[start] = Some(id);
+		while let Some(xyz) = queue.pop_front() {
+			component.push(xyz);
+			for dir in self.connectivity.dirs() {
+				let next = xyz.step(dir);
+				if self.is_passable(next)
+					&& self.labels[next].is_none()
+					&& self.connectivity.connects(xyz, dir)
+				{
+					self.labels[next] = Some(id);
+					queue.push_back(next);
+				}
+			}
+		}
+		self.components[id as usize] = component;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::grid::ascii_map as grid;
+
+	#[test]
+	fn labelling() {
+		let map = grid("..#..\n..#..\n###.#\n.#...");
+		let regions = Regions::from_passable(map.clone(), Connectivity::new());
+		assert_eq!(regions.len(), 3);
+		assert!(regions.connected(ByondXYZ::new(4, 4, 1), ByondXYZ::new(5, 1, 1)));
+		assert!(!regions.connected(ByondXYZ::new(1, 4, 1), ByondXYZ::new(4, 4, 1)));
+		assert_eq!(
+			regions.component_at(ByondXYZ::new(1, 4, 1)).map(<[_]>::len),
+			Some(4)
+		);
+		assert_eq!(
+			regions.component_at(ByondXYZ::new(1, 1, 1)).map(<[_]>::len),
+			Some(1)
+		);
+		assert_eq!(regions.id_at(ByondXYZ::new(3, 4, 1)), None);
+
+		let checkerboard = grid("#.#\n.#.\n#.#");
+		let cardinal = Regions::from_passable(checkerboard.clone(), Connectivity::new());
+		assert_eq!(cardinal.len(), 4);
+		let diagonal = Regions::from_passable(checkerboard, Connectivity::new().diagonals(true));
+		assert_eq!(diagonal.len(), 1);
+	}
+
+	#[test]
+	fn incremental() {
+		let mut regions = Regions::from_passable(grid(".....\n.....\n....."), Connectivity::new());
+		assert_eq!(regions.len(), 1);
+		for y in 1..=3 {
+			regions.set_passable(ByondXYZ::new(3, y, 1), false);
+		}
+		assert_eq!(regions.len(), 2);
+		assert_eq!(
+			regions.component_at(ByondXYZ::new(1, 1, 1)).map(<[_]>::len),
+			Some(6)
+		);
+		assert!(!regions.set_passable(ByondXYZ::new(3, 2, 1), false));
+		assert!(regions.set_passable(ByondXYZ::new(3, 2, 1), true));
+		assert_eq!(regions.len(), 1);
+		assert_eq!(
+			regions.component_at(ByondXYZ::new(5, 3, 1)).map(<[_]>::len),
+			Some(13)
+		);
+	}
+
+	#[test]
+	fn edge_rules() {
+		let map = grid("...\n...\n...");
+		// A wall between x = 1 and x = 2, in both directions.
+		let connectivity = Connectivity::new().edge(|xyz, dir| {
+			!((xyz.x() == 1 && dir == ByondDir::EAST) || (xyz.x() == 2 && dir == ByondDir::WEST))
+		});
+		let filled = flood_fill(&map, ByondXYZ::new(1, 1, 1), |open| *open, &connectivity);
+		assert_eq!(filled.len(), 3);
+		assert_eq!(filled[0], ByondXYZ::new(1, 1, 1));
+		let regions = Regions::from_passable(map, connectivity);
+		assert_eq!(regions.len(), 2);
+	}
+}
@@ -0,0 +1,357 @@
+// SPDX-License-Identifier: 0BSD
+//! Equivalents of DM's `range()`, `orange()`, `view()`, `oview()` and
+//! `hearers()`, along with the common `circlerange()` helper.
+//!
+//! Rather than looping over every turf in DM, each query fetches all of the
+//! turfs with a single `block()` call, then reads each turf's contents list
+//! with one bulk read, looking up the name of the `contents` var only once,
+//! and filters the results in Rust before building the final list. byondapi
+//! has no way to read a var from many values at once, so areas are still
+//! found by reading each turf's `loc`, and only when areas are wanted.
+//!
+//! ```no_run
+//! #[meowtonin::byond_fn]
+//! pub fn nearby_mobs(center: meowtonin::ByondValue) -> meowtonin::ByondResult<meowtonin::ByondValue> {
+//!     meowtonin::spatial::orange(center, 7)
+//!         .of_type(meowtonin::ByondValueType::Mob)
+//!         .filter(|mob| mob.read_var::<_, bool>("stat").is_ok_and(|stat| !stat))
+//!         .to_list()
+//! }
+//! ```
+//!
+//! For repeated queries among many moving atoms, see [`SpatialIndex`].
+mod index;
+
+pub use self::index::SpatialIndex;
+
+use crate::{
+	Area, Atom, ByondBlock, ByondError, ByondResult, ByondValue, ByondValueType, ByondXYZ, Mob,
+	Obj, ToByond, Turf, TypePath,
+	geometry::squared_distance,
+	los::{self, OpacityGrid},
+	strid::lookup_string_id,
+};
+
+/// The center of a spatial query, either an atom or a set of coordinates.
+#[derive(Clone, PartialEq)]
+pub enum Center {
+	/// An atom, which is excluded from `orange()` and `oview()`.
+	Atom(ByondValue),
+	/// A tile, which is treated like its turf.
+	Xyz(ByondXYZ),
+}
+
+impl Center {
+	fn xyz(&self) -> ByondResult<ByondXYZ> {
+		match self {
+			Self::Atom(atom) => atom.xyz().ok_or_else(ByondError::get_last_byond_error),
+			Self::Xyz(xyz) => Ok(*xyz),
+		}
+	}
+
+	fn is_turf(&self) -> bool {
+		match self {
+			Self::Atom(atom) => atom.get_type() == ByondValueType::Turf,
+			Self::Xyz(_) => true,
+		}
+	}
+}
+
+impl From<ByondXYZ> for Center {
+	fn from(xyz: ByondXYZ) -> Self {
+		Self::Xyz(xyz)
+	}
+}
+
+impl From<ByondValue> for Center {
+	fn from(atom: ByondValue) -> Self {
+		Self::Atom(atom)
+	}
+}
+
+impl From<&ByondValue> for Center {
+	fn from(atom: &ByondValue) -> Self {
+		Self::Atom(atom.clone())
+	}
+}
+
+macro_rules! impl_center_from_handle {
+	($($name:ident),+) => {
+		$(
+			impl From<$name> for Center {
+				fn from(atom: $name) -> Self {
+					Self::Atom(atom.into_inner())
+				}
+			}
+
+			impl From<&$name> for Center {
+				fn from(atom: &$name) -> Self {
+					Self::Atom(atom.as_value().clone())
+				}
+			}
+		)+
+	};
+}
+
+impl_center_from_handle!(Atom, Turf, Obj, Mob, Area);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Shape {
+	Square,
+	Circle,
+	View,
+}
+
+type Predicate<'a> = Box<dyn FnMut(&ByondValue) -> bool + 'a>;
+
+/// A spatial query, built by one of the functions in this module.
+#[must_use]
+pub struct Query<'a> {
+	shape: Shape,
+	center: Center,
+	radius: u16,
+	exclude_center: bool,
+	value_type: Option<ByondValueType>,
+	typepath: Option<TypePath>,
+	predicate: Option<Predicate<'a>>,
+}
+
+/// Equivalent to `range(radius, center)` in DM: every turf within `radius`
+/// tiles, along with their contents and areas.
+pub fn range<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	Query::new(Shape::Square, center.into(), radius, false)
+}
+
+/// Equivalent to `orange(radius, center)` in DM, which is [`range`] without
+/// the center. If the center is a turf, its contents are excluded as well.
+pub fn orange<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	Query::new(Shape::Square, center.into(), radius, true)
+}
+
+/// Equivalent to `view(radius, center)` in DM: every turf visible from the
+/// center, along with their contents and areas.
+///
+/// Visibility is computed with [`los::view`], so it ignores lighting and sight
+/// flags.
+pub fn view<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	Query::new(Shape::View, center.into(), radius, false)
+}
+
+/// Equivalent to `oview(radius, center)` in DM, which is [`view`] without the
+/// center.
+pub fn oview<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	Query::new(Shape::View, center.into(), radius, true)
+}
+
+/// Equivalent to `hearers(radius, center)` in DM: every mob that can see the
+/// center, ignoring darkness.
+pub fn hearers<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	view(center, radius).of_type(ByondValueType::Mob)
+}
+
+/// Equivalent to the common `circlerange(center, radius)` helper, which is
+/// [`range`], limited to the turfs whose squared distance from the center is
+/// at most `radius * (radius + 0.5)`.
+pub fn circlerange<'a, Origin>(center: Origin, radius: u16) -> Query<'a>
+where
+	Origin: Into<Center>,
+{
+	Query::new(Shape::Circle, center.into(), radius, false)
+}
+
+impl<'a> Query<'a> {
+	fn new(shape: Shape, center: Center, radius: u16, exclude_center: bool) -> Self {
+		Self {
+			shape,
+			center,
+			radius,
+			exclude_center,
+			value_type: None,
+			typepath: None,
+			predicate: None,
+		}
+	}
+
+	/// Only returns values of the given type, such as
+	/// [`ByondValueType::Mob`].
+	///
+	/// Turf contents aren't read at all if only turfs or areas are wanted.
+	pub fn of_type(mut self, value_type: ByondValueType) -> Self {
+		self.value_type = Some(value_type);
+		self
+	}
+
+	/// Only returns values that are `istype()` of the given typepath.
+	pub fn istype(mut self, typepath: TypePath) -> Self {
+		self.typepath = Some(typepath);
+		self
+	}
+
+	/// Only returns values that match the given predicate, which is only
+	/// called for values that pass the type filters.
+	pub fn filter<Func>(mut self, predicate: Func) -> Self
+	where
+		Func: FnMut(&ByondValue) -> bool + 'a,
+	{
+		self.predicate = Some(Box::new(predicate));
+		self
+	}
+
+	/// Runs the query, returning every matching value.
+	///
+	/// Results are ordered by turf, in the same order as `block()`, with each
+	/// turf followed by its contents. Areas come last.
+	pub fn collect(mut self) -> ByondResult<Vec<ByondValue>> {
+		let center = self.center.xyz()?;
+		let block = ByondBlock::around(center, self.radius);
+		let Some(block) = ByondBlock::world()?.and_then(|world| block.intersect(&world)) else {
+			return Ok(Vec::new());
+		};
+		let turfs = block.turfs()?;
+		let contents = if self.shape == Shape::View
+			|| self.wants(ByondValueType::Obj)
+			|| self.wants(ByondValueType::Mob)
+		{
+			let contents_id = lookup_string_id("contents").ok_or(ByondError::InvalidVariable)?;
+			turfs
+				.iter()
+				.map(|turf| turf.read_var_by_id::<ByondValue>(contents_id)?.read_list())
+				.collect::<ByondResult<Vec<_>>>()?
+		} else {
+			Vec::new()
+		};
+		let in_shape = self.in_shape(center, block, &turfs, &contents)?;
+		let center_value = match &self.center {
+			Center::Atom(atom) if self.exclude_center => Some(atom.clone()),
+			_ => None,
+		};
+		let exclude_center_turf = self.exclude_center && self.center.is_turf();
+		let wants_areas = self.wants(ByondValueType::Area);
+
+		let mut results = Vec::new();
+		let mut areas = Vec::<ByondValue>::new();
+		for (idx, (xyz, turf)) in block.iter().zip(&turfs).enumerate() {
+			if !in_shape[idx] {
+				continue;
+			}
+			if wants_areas {
+				let area: ByondValue = turf.read_var("loc")?;
+				if !areas.contains(&area) {
+					areas.push(area);
+				}
+			}
+			if exclude_center_turf && xyz == center {
+				continue;
+			}
+			self.push_if_matches(&mut results, turf)?;
+			for atom in contents.get(idx).into_iter().flatten() {
+				if center_value.as_ref() != Some(atom) {
+					self.push_if_matches(&mut results, atom)?;
+				}
+			}
+		}
+		for area in &areas {
+			self.push_if_matches(&mut results, area)?;
+		}
+		Ok(results)
+	}
+
+	/// Runs the query, returning the results as a DM list.
+	pub fn to_list(self) -> ByondResult<ByondValue> {
+		self.collect()?.to_byond()
+	}
+
+	/// Returns if values of the given type could pass the type filters.
+	fn wants(&self, value_type: ByondValueType) -> bool {
+		if self.value_type.is_some_and(|wanted| wanted != value_type) {
+			return false;
+		}
+		let Some(typepath) = &self.typepath else {
+			return true;
+		};
+		let root = match typepath.components().next() {
+			Some("turf") => ByondValueType::Turf,
+			Some("obj") => ByondValueType::Obj,
+			Some("mob") => ByondValueType::Mob,
+			Some("area") => ByondValueType::Area,
+			_ => return true,
+		};
+		root == value_type
+	}
+
+	fn in_shape(
+		&self,
+		center: ByondXYZ,
+		block: ByondBlock,
+		turfs: &[ByondValue],
+		contents: &[Vec<ByondValue>],
+	) -> ByondResult<Vec<bool>> {
+		match self.shape {
+			Shape::Square => Ok(vec![true; block.len()]),
+			Shape::Circle => {
+				let radius = self.radius as i64;
+				let limit = radius * (2 * radius + 1) / 2;
+				Ok(block
+					.iter()
+					.map(|xyz| squared_distance(center, xyz) <= limit)
+					.collect())
+			}
+			Shape::View => {
+				let mut grid = OpacityGrid::new(block, true);
+				for ((xyz, turf), contents) in block.iter().zip(turfs).zip(contents) {
+					let mut opaque = turf.read_var::<_, bool>("opacity")?;
+					for atom in contents {
+						if opaque {
+							break;
+						}
+						opaque = atom.read_var::<_, bool>("opacity")?;
+					}
+					grid.set(xyz, opaque);
+				}
+				let visibility = los::view(&grid, center, self.radius);
+				Ok(block.iter().map(|xyz| visibility.contains(&xyz)).collect())
+			}
+		}
+	}
+
+	fn push_if_matches(
+		&mut self,
+		results: &mut Vec<ByondValue>,
+		value: &ByondValue,
+	) -> ByondResult<()> {
+		if self
+			.value_type
+			.is_some_and(|value_type| value.get_type() != value_type)
+		{
+			return Ok(());
+		}
+		if let Some(typepath) = &self.typepath {
+			if !value.is_type(typepath.as_str()) {
+				return Ok(());
+			}
+		}
+		if self
+			.predicate
+			.as_mut()
+			.is_none_or(|predicate| predicate(value))
+		{
+			results.push(value.clone());
+		}
+		Ok(())
+	}
+}
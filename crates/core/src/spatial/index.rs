@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondBlock, ByondPixLoc, ByondResult, ByondValue, ByondXYZ, WeakByondValue,
+	pixloc::{ByondPixBounds, world_icon_size},
+};
+use ahash::AHashMap;
+
+type CellKey = (i32, i32, i16);
+
+/// A spatial hash of atoms, for fast proximity queries among large numbers of
+/// moving atoms, such as mobs or projectiles.
+///
+/// Atoms are tracked by [`WeakByondValue`], so the index never keeps them
+/// alive. Positions are kept up to date either by calling
+/// [`update`](Self::update) whenever an atom moves (i.e from `Moved()`), or by
+/// calling [`poll`](Self::poll) once per tick, which also drops deleted atoms.
+///
+/// ```no_run
+/// use meowtonin::{ByondValue, spatial::SpatialIndex};
+/// use parking_lot::Mutex;
+/// use std::sync::LazyLock;
+///
+/// static MOBS: LazyLock<Mutex<SpatialIndex>> =
+///     LazyLock::new(|| Mutex::new(SpatialIndex::new((32, 32), 8)));
+///
+/// #[meowtonin::byond_fn]
+/// pub fn mob_moved(mob: ByondValue) {
+///     MOBS.lock().update(&mob);
+/// }
+///
+/// #[meowtonin::byond_fn]
+/// pub fn mobs_near(center: meowtonin::ByondXYZ, radius: u16) -> Vec<ByondValue> {
+///     MOBS.lock()
+///         .query_range(center, radius)
+///         .into_iter()
+///         .filter_map(|mob| mob.upgrade())
+///         .collect()
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+	icon_size: (u16, u16),
+	cell_width: f32,
+	cell_height: f32,
+	cells: AHashMap<CellKey, Vec<WeakByondValue>>,
+	positions: AHashMap<WeakByondValue, Option<ByondPixLoc>>,
+}
+
+impl SpatialIndex {
+	/// Creates an empty index, given the size of a tile in pixels
+	/// (`world.icon_size`), and the size of each cell of the hash in tiles.
+	///
+	/// Cells should be around the size of a typical query.
+	pub fn new(icon_size: (u16, u16), cell_size: u16) -> Self {
+		let cell_size = cell_size.max(1) as f32;
+		Self {
+			icon_size,
+			cell_width: icon_size.0.max(1) as f32 * cell_size,
+			cell_height: icon_size.1.max(1) as f32 * cell_size,
+			cells: AHashMap::new(),
+			positions: AHashMap::new(),
+		}
+	}
+
+	/// Creates an empty index, using the current `world.icon_size`.
+	pub fn with_world_icon_size(cell_size: u16) -> ByondResult<Self> {
+		Ok(Self::new(world_icon_size()?, cell_size))
+	}
+
+	/// Returns the number of tracked atoms, including ones that are off-map.
+	pub fn len(&self) -> usize {
+		self.positions.len()
+	}
+
+	/// Returns if no atoms are being tracked.
+	pub fn is_empty(&self) -> bool {
+		self.positions.is_empty()
+	}
+
+	/// Returns if the given atom is being tracked.
+	pub fn contains(&self, atom: &WeakByondValue) -> bool {
+		self.positions.contains_key(atom)
+	}
+
+	/// Returns the last known position of the given atom, or `None` if it's
+	/// off-map or not being tracked.
+	pub fn position(&self, atom: &WeakByondValue) -> Option<ByondPixLoc> {
+		self.positions.get(atom).copied().flatten()
+	}
+
+	/// Iterates over every tracked atom.
+	pub fn iter(&self) -> impl Iterator<Item = WeakByondValue> + '_ {
+		self.positions.keys().copied()
+	}
+
+	/// Starts tracking the given atom, or updates its position if it's already
+	/// being tracked, reading its position with `Byond_PixLoc` (or
+	/// `Byond_XYZ` on older versions).
+	///
+	/// Returns `false` if the value isn't an atom.
+	pub fn update(&mut self, atom: &ByondValue) -> bool {
+		let Some(key) = atom.downgrade() else {
+			return false;
+		};
+		match read_position(atom, self.icon_size) {
+			Some(position) => {
+				self.set_position(key, position);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Sets the position of an atom directly, starting to track it if it
+	/// isn't already. `None` means the atom is off-map.
+	pub fn set_position(&mut self, atom: WeakByondValue, position: Option<ByondPixLoc>) {
+		let position = position.filter(|pixloc| pixloc.z() > 0);
+		let old = self.positions.insert(atom, position).flatten();
+		let old_cell = old.map(|pixloc| self.cell_of(pixloc));
+		let new_cell = position.map(|pixloc| self.cell_of(pixloc));
+		if old_cell == new_cell {
+			return;
+		}
+		if let Some(cell) = old_cell {
+			self.remove_from_cell(cell, &atom);
+		}
+		if let Some(cell) = new_cell {
+			self.cells.entry(cell).or_default().push(atom);
+		}
+	}
+
+	/// Stops tracking the given atom, returning `false` if it wasn't being
+	/// tracked.
+	pub fn remove(&mut self, atom: &WeakByondValue) -> bool {
+		match self.positions.remove(atom) {
+			Some(position) => {
+				if let Some(pixloc) = position {
+					self.remove_from_cell(self.cell_of(pixloc), atom);
+				}
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Stops tracking every atom.
+	pub fn clear(&mut self) {
+		self.cells.clear();
+		self.positions.clear();
+	}
+
+	/// Stops tracking every atom that has been deleted, returning how many
+	/// were removed.
+	pub fn prune(&mut self) -> usize {
+		let stale = self
+			.positions
+			.keys()
+			.filter(|atom| !atom.is_valid())
+			.copied()
+			.collect::<Vec<_>>();
+		for atom in &stale {
+			self.remove(atom);
+		}
+		stale.len()
+	}
+
+	/// Re-reads the position of every tracked atom, and stops tracking any
+	/// that have been deleted, returning how many were removed.
+	///
+	/// Meant to be called once per tick, as an alternative to calling
+	/// [`update`](Self::update) on every move.
+	pub fn poll(&mut self) -> usize {
+		let atoms = self.positions.keys().copied().collect::<Vec<_>>();
+		let mut removed = 0;
+		for atom in atoms {
+			match atom
+				.upgrade()
+				.and_then(|value| read_position(&value, self.icon_size))
+			{
+				Some(position) => self.set_position(atom, position),
+				None => {
+					self.remove(&atom);
+					removed += 1;
+				}
+			}
+		}
+		removed
+	}
+
+	/// Returns every atom within `radius` pixels of `center`, on the same
+	/// z-level.
+	pub fn query_radius(&self, center: ByondPixLoc, radius: f32) -> Vec<WeakByondValue> {
+		let bounds = ByondPixBounds::from_corners(
+			ByondPixLoc::new(center.x() - radius, center.y() - radius, center.z()),
+			ByondPixLoc::new(center.x() + radius, center.y() + radius, center.z()),
+		);
+		let radius_squared = radius * radius;
+		self.query(&bounds, |pixloc| {
+			let (dx, dy) = (pixloc.x() - center.x(), pixloc.y() - center.y());
+			dx * dx + dy * dy <= radius_squared
+		})
+	}
+
+	/// Returns every atom within the given pixel bounds.
+	pub fn query_bounds(&self, bounds: &ByondPixBounds) -> Vec<WeakByondValue> {
+		self.query(bounds, |pixloc| bounds.contains(&pixloc))
+	}
+
+	/// Returns every atom on a tile within `radius` tiles of `center`, like
+	/// `range()`.
+	pub fn query_range(&self, center: ByondXYZ, radius: u16) -> Vec<WeakByondValue> {
+		self.query_block(&ByondBlock::around(center, radius))
+	}
+
+	/// Returns every atom on a tile within the given block.
+	pub fn query_block(&self, block: &ByondBlock) -> Vec<WeakByondValue> {
+		let mut results = Vec::new();
+		for z in block.min().z()..=block.max().z() {
+			let min = ByondPixLoc::from_xyz(
+				ByondXYZ::new(block.min().x(), block.min().y(), z),
+				self.icon_size,
+			);
+			let max = ByondPixLoc::from_xyz(
+				ByondXYZ::new(block.max().x() + 1, block.max().y() + 1, z),
+				self.icon_size,
+			);
+			let bounds = ByondPixBounds::from_corners(
+				min,
+				ByondPixLoc::new(max.x() - 1.0, max.y() - 1.0, z),
+			);
+			results.extend(self.query(&bounds, |pixloc| {
+				block.contains(&pixloc.to_xyz(self.icon_size))
+			}));
+		}
+		results
+	}
+
+	/// Returns every atom in the cells overlapping `bounds` whose position
+	/// passes `filter`.
+	fn query<Filter>(&self, bounds: &ByondPixBounds, mut filter: Filter) -> Vec<WeakByondValue>
+	where
+		Filter: FnMut(ByondPixLoc) -> bool,
+	{
+		let (min_x, min_y, z) = self.cell_of(bounds.min);
+		let (max_x, max_y, _) = self.cell_of(bounds.max);
+		let mut results = Vec::new();
+		for cell_y in min_y..=max_y {
+			for cell_x in min_x..=max_x {
+				let Some(atoms) = self.cells.get(&(cell_x, cell_y, z)) else {
+					continue;
+				};
+				results.extend(
+					atoms
+						.iter()
+						.filter(|atom| self.position(atom).is_some_and(&mut filter)),
+				);
+			}
+		}
+		results
+	}
+
+	fn cell_of(&self, pixloc: ByondPixLoc) -> CellKey {
+		(
+			((pixloc.x() - 1.0) / self.cell_width).floor() as i32,
+			((pixloc.y() - 1.0) / self.cell_height).floor() as i32,
+			pixloc.z(),
+		)
+	}
+
+	fn remove_from_cell(&mut self, cell: CellKey, atom: &WeakByondValue) {
+		if let Some(atoms) = self.cells.get_mut(&cell) {
+			atoms.retain(|other| other != atom);
+			if atoms.is_empty() {
+				self.cells.remove(&cell);
+			}
+		}
+	}
+}
+
+/// Reads an atom's position, returning `Some(None)` if it's off-map, or
+/// `None` if it isn't an atom.
+fn read_position(atom: &ByondValue, icon_size: (u16, u16)) -> Option<Option<ByondPixLoc>> {
+	let pixloc = match atom.pixloc() {
+		Some(pixloc) => pixloc,
+		None => ByondPixLoc::from_xyz(atom.xyz()?, icon_size),
+	};
+	Some((pixloc.z() > 0).then_some(pixloc))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ByondValueType;
+
+	fn atom(id: u32) -> WeakByondValue {
+		WeakByondValue::from_raw(ByondValueType::Mob, id)
+	}
+
+	fn tile(x: i16, y: i16) -> Option<ByondPixLoc> {
+		Some(ByondPixLoc::center_of(ByondXYZ::new(x, y, 1), (32, 32)))
+	}
+
+	#[test]
+	fn queries() {
+		let mut index = SpatialIndex::new((32, 32), 4);
+		index.set_position(atom(1), tile(5, 5));
+		index.set_position(atom(2), tile(7, 5));
+		index.set_position(atom(3), tile(20, 20));
+		index.set_position(atom(4), None);
+		assert_eq!(index.len(), 4);
+
+		let mut near = index.query_range(ByondXYZ::new(5, 5, 1), 2);
+		near.sort_by_key(WeakByondValue::ref_id);
+		assert_eq!(near, [atom(1), atom(2)]);
+		assert_eq!(index.query_range(ByondXYZ::new(5, 5, 1), 1), [atom(1)]);
+		assert_eq!(index.query_range(ByondXYZ::new(5, 5, 2), 5), []);
+		assert_eq!(index.query_radius(tile(20, 20).unwrap(), 16.0), [atom(3)]);
+		assert_eq!(
+			index
+				.query_block(&ByondBlock::new(
+					ByondXYZ::new(6, 1, 1),
+					ByondXYZ::new(30, 30, 1)
+				))
+				.len(),
+			2
+		);
+	}
+
+	#[test]
+	fn moving_and_removing() {
+		let mut index = SpatialIndex::new((32, 32), 4);
+		index.set_position(atom(1), tile(1, 1));
+		index.set_position(atom(1), tile(50, 50));
+		assert_eq!(index.query_range(ByondXYZ::new(1, 1, 1), 3), []);
+		assert_eq!(index.query_range(ByondXYZ::new(50, 50, 1), 0), [atom(1)]);
+		index.set_position(atom(1), None);
+		assert_eq!(index.query_range(ByondXYZ::new(50, 50, 1), 0), []);
+		assert!(index.contains(&atom(1)));
+		assert!(index.remove(&atom(1)));
+		assert!(!index.remove(&atom(1)));
+		assert!(index.is_empty());
+		assert!(index.cells.is_empty());
+	}
+}
@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: 0BSD
-use crate::{ByondValue, RcByondValue, byond, sys::CByondValue};
+use crate::{ByondError, ByondResult, ByondValue, RcByondValue, byond, sys::CByondValue};
 use std::{cell::Cell, os::raw::c_void, sync::OnceLock, thread::ThreadId};
 
 struct CallbackData<F: FnOnce() -> ByondValue + Send> {
@@ -26,6 +26,28 @@ pub fn thread_sync<F>(callback: F, block: bool) -> RcByondValue
 	}))
 }
 
+/// Calls `callback` on the main thread, blocking until it returns.
+///
+/// If this is already the main thread, the callback is called directly.
+pub(crate) fn call_on_main_thread<Output, Func>(callback: Func) -> ByondResult<Output>
+where
+	Func: FnOnce() -> Output + Send + 'static,
+	Output: Send + 'static,
+{
+	if is_main_thread() {
+		return Ok(callback());
+	}
+	let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+	thread_sync(
+		move || {
+			let _ = sender.send(callback());
+			ByondValue::NULL
+		},
+		true,
+	);
+	receiver.recv().map_err(ByondError::boxed)
+}
+
 thread_local! {
 	static THREAD_SYNC_DEPTH: Cell<usize> = const { Cell::new(0) };
 }
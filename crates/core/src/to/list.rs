@@ -133,11 +133,12 @@ impl<Key, Hasher> ToByond for HashSet<Key, Hasher>
 	Key: ToByond + Eq + Hash,
 {
 	fn to_byond(&self) -> ByondResult<ByondValue> {
+		let keys = self
+			.iter()
+			.map(|key| key.to_byond())
+			.collect::<ByondResult<Vec<_>>>()?;
 		let mut list = ByondValue::new_list()?;
-		for key in self {
-			let key = key.to_byond()?;
-			list.push_list(key)?;
-		}
+		list.write_list(keys)?;
 		Ok(list)
 	}
 }
@@ -163,11 +164,12 @@ impl<Key> ToByond for BTreeSet<Key>
 	Key: ToByond + Eq + Ord,
 {
 	fn to_byond(&self) -> ByondResult<ByondValue> {
+		let keys = self
+			.iter()
+			.map(|key| key.to_byond())
+			.collect::<ByondResult<Vec<_>>>()?;
 		let mut list = ByondValue::new_list()?;
-		for key in self {
-			let key = key.to_byond()?;
-			list.push_list(key)?;
-		}
+		list.write_list(keys)?;
 		Ok(list)
 	}
 }
@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: 0BSD
+//! Typepaths, such as `/obj/item/weapon`.
+//!
+//! `typesof()` and `text2path()` are built-in instructions rather than procs,
+//! so they can't be called through byondapi directly. To use
+//! [`TypePath::typesof`], [`TypePath::subtypesof`] or convert a [`TypePath`]
+//! into an actual path value, define these global procs somewhere in your DM
+//! code:
+//!
+//! ```dm
+//! /proc/meowtonin_typesof(text)
+//!     return typesof(text2path(text))
+//!
+//! /proc/meowtonin_text2path(text)
+//!     return text2path(text)
+//! ```
+//!
+//! Without them, ancestry checks fall back to only comparing the paths
+//! themselves, which doesn't account for `parent_type` overrides.
+use crate::{ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond, call_global};
+use ahash::AHashSet;
+use papaya::HashMap;
+use smol_str::SmolStr;
+use std::{
+	fmt,
+	str::FromStr,
+	sync::{
+		Arc, LazyLock,
+		atomic::{AtomicBool, Ordering},
+	},
+};
+
+/// The name of the global proc used to convert text into a typepath.
+pub const TEXT2PATH_PROC: &str = "meowtonin_text2path";
+
+/// The name of the global proc used to get all subtypes of a typepath.
+pub const TYPESOF_PROC: &str = "meowtonin_typesof";
+
+/// Builtin types that don't have a parent type.
+const ROOT_TYPES: &[&str] = &[
+	"/datum",
+	"/client",
+	"/world",
+	"/list",
+	"/alist",
+	"/savefile",
+	"/callee",
+	"/pixloc",
+	"/vector",
+];
+
+/// A validated DM typepath, such as `/datum/foo/bar`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TypePath(SmolStr);
+
+impl TypePath {
+	/// Parses and validates a typepath.
+	///
+	/// A valid typepath starts with a `/`, and consists of one or more
+	/// non-empty components made of alphanumeric characters and underscores.
+	pub fn new<Str>(path: Str) -> ByondResult<Self>
+	where
+		Str: AsRef<str>,
+	{
+		let path = path.as_ref();
+		let is_valid = path.strip_prefix('/').is_some_and(|components| {
+			components.split('/').all(|component| {
+				!component.is_empty()
+					&& component
+						.bytes()
+						.all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+			})
+		});
+		if is_valid {
+			Ok(Self(SmolStr::new(path)))
+		} else {
+			Err(ByondError::InvalidTypePath(path.to_owned()))
+		}
+	}
+
+	/// Returns the typepath as a string.
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// Iterates over each component of the typepath, i.e `/obj/item` yields
+	/// `"obj"` then `"item"`.
+	pub fn components(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+		self.0[1..].split('/')
+	}
+
+	/// Returns the last component of the typepath, i.e `item` for
+	/// `/obj/item`.
+	pub fn name(&self) -> &str {
+		self.components().next_back().unwrap_or_default()
+	}
+
+	/// Returns the number of components in the typepath.
+	pub fn depth(&self) -> usize {
+		self.components().count()
+	}
+
+	/// Returns if this is a builtin type without a parent, such as `/datum`
+	/// or `/list`.
+	pub fn is_root(&self) -> bool {
+		ROOT_TYPES.contains(&self.as_str())
+	}
+
+	/// Returns the parent of this typepath, accounting for implicit builtin
+	/// parents (i.e `/obj` is a child of `/atom/movable`, and top-level
+	/// types are children of `/datum`).
+	///
+	/// Returns `None` for root types, such as `/datum` or `/list`.
+	pub fn parent(&self) -> Option<Self> {
+		if self.is_root() {
+			return None;
+		}
+		let implicit_parent = match self.as_str() {
+			"/obj" | "/mob" => Some("/atom/movable"),
+			"/turf" | "/area" => Some("/atom"),
+			_ => None,
+		};
+		let parent = match implicit_parent {
+			Some(parent) => parent,
+			None => match self.0.rfind('/') {
+				Some(0) => "/datum",
+				Some(idx) => &self.0[..idx],
+				None => return None,
+			},
+		};
+		Some(Self(SmolStr::new(parent)))
+	}
+
+	/// Iterates over all ancestors of this typepath, starting with the direct
+	/// parent and ending with the root type.
+	pub fn ancestors(&self) -> impl Iterator<Item = Self> {
+		std::iter::successors(self.parent(), Self::parent)
+	}
+
+	/// Returns a new typepath with the given component appended.
+	pub fn join<Str>(&self, component: Str) -> ByondResult<Self>
+	where
+		Str: AsRef<str>,
+	{
+		Self::new(format!("{}/{}", self.0, component.as_ref()))
+	}
+
+	/// Returns if this typepath is a (strict) ancestor of `other`, based on
+	/// the paths alone.
+	pub fn is_ancestor_of(&self, other: &Self) -> bool {
+		other.ancestors().any(|ancestor| ancestor == *self)
+	}
+
+	/// Returns if this typepath is a (strict) descendant of `other`, based on
+	/// the paths alone.
+	pub fn is_descendant_of(&self, other: &Self) -> bool {
+		other.is_ancestor_of(self)
+	}
+
+	/// Returns if this typepath is either `other`, or a descendant of it.
+	///
+	/// This uses the cached type tree from [`typesof()`](Self::typesof) when
+	/// it's available, so it accounts for `parent_type` overrides.
+	///
+	/// If the `meowtonin_typesof` proc isn't defined, this only checks if
+	/// `other` is a prefix of this path (see
+	/// [`is_descendant_of`](Self::is_descendant_of)), which gets
+	/// `parent_type` overrides wrong. A missing proc is only tried once, so
+	/// this doesn't keep making failing calls. Any other error only falls
+	/// back for that check, and can be seen by calling
+	/// [`typesof()`](Self::typesof).
+	pub fn is_type(&self, other: &Self) -> bool {
+		if self == other {
+			return true;
+		}
+		if !TYPESOF_MISSING.load(Ordering::Relaxed)
+			&& let Ok(types) = cached_typesof(other)
+		{
+			return types.set.contains(self);
+		}
+		self.is_descendant_of(other)
+	}
+
+	/// Equivalent to `typesof(path)` in DM, returning this type and all of
+	/// its subtypes.
+	///
+	/// The result is cached for the lifetime of the world.
+	pub fn typesof(&self) -> ByondResult<Arc<[Self]>> {
+		cached_typesof(self).map(|types| types.list.clone())
+	}
+
+	/// Equivalent to `subtypesof(path)` in DM, returning all subtypes of this
+	/// type, not including itself.
+	pub fn subtypesof(&self) -> ByondResult<Vec<Self>> {
+		Ok(self
+			.typesof()?
+			.iter()
+			.filter(|path| *path != self)
+			.cloned()
+			.collect())
+	}
+}
+
+pub(crate) struct CachedTypes {
+	list: Arc<[TypePath]>,
+	set: AHashSet<TypePath>,
+}
+
+pub(crate) static TYPESOF_CACHE: LazyLock<HashMap<TypePath, Arc<CachedTypes>>> =
+	LazyLock::new(HashMap::new);
+
+/// Set when [`TYPESOF_PROC`] doesn't exist, so [`TypePath::is_type`] can go
+/// straight to comparing paths rather than failing again every time.
+///
+/// Only a missing proc sets this - errors from inside the proc, or from
+/// converting what it returns, are returned as usual.
+pub(crate) static TYPESOF_MISSING: AtomicBool = AtomicBool::new(false);
+
+fn cached_typesof(path: &TypePath) -> ByondResult<Arc<CachedTypes>> {
+	let cache = TYPESOF_CACHE.pin();
+	if let Some(types) = cache.get(path) {
+		return Ok(types.clone());
+	}
+	let list: Vec<TypePath> = call_global(TYPESOF_PROC, [path.as_str()]).inspect_err(|err| {
+		// If the name isn't in BYOND's string tree, no proc can have it.
+		if matches!(err, ByondError::InvalidProc) {
+			TYPESOF_MISSING.store(true, Ordering::Relaxed);
+		}
+	})?;
+	let set = list.iter().cloned().collect();
+	let types = Arc::new(CachedTypes {
+		list: list.into(),
+		set,
+	});
+	cache.insert(path.clone(), types.clone());
+	Ok(types)
+}
+
+impl ByondValue {
+	/// Equivalent to `text2path(path)` in DM.
+	///
+	/// Requires the `meowtonin_text2path` proc to be defined, see the
+	/// [module-level documentation](crate::typepath).
+	pub fn text2path(path: &TypePath) -> ByondResult<Self> {
+		call_global(TEXT2PATH_PROC, [path.as_str()])
+	}
+}
+
+impl FromStr for TypePath {
+	type Err = ByondError;
+
+	fn from_str(path: &str) -> Result<Self, Self::Err> {
+		Self::new(path)
+	}
+}
+
+impl fmt::Display for TypePath {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+impl AsRef<str> for TypePath {
+	fn as_ref(&self) -> &str {
+		&self.0
+	}
+}
+
+impl From<TypePath> for String {
+	fn from(path: TypePath) -> Self {
+		path.0.into()
+	}
+}
+
+impl FromByond for TypePath {
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let value_type = value.get_type();
+		if !value.is_string() && !ByondValueType::PATH_TYPES.contains(&value_type) {
+			return Err(ByondError::InvalidConversion {
+				expected: "typepath".into(),
+				got: value_type.name(),
+			});
+		}
+		Self::new(value.get_string()?)
+	}
+}
+
+impl ToByond for TypePath {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		ByondValue::text2path(self)
+	}
+}
+
+impl ToByond for &TypePath {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		ByondValue::text2path(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn path(path: &str) -> TypePath {
+		TypePath::new(path).expect("invalid typepath")
+	}
+
+	#[test]
+	fn validates_typepaths() {
+		assert!(TypePath::new("/datum/foo/bar").is_ok());
+		assert!(TypePath::new("/obj/item_2").is_ok());
+		assert!(TypePath::new("").is_err());
+		assert!(TypePath::new("/").is_err());
+		assert!(TypePath::new("datum/foo").is_err());
+		assert!(TypePath::new("/datum/").is_err());
+		assert!(TypePath::new("/datum//foo").is_err());
+		assert!(TypePath::new("/datum/foo bar").is_err());
+	}
+
+	#[test]
+	fn components() {
+		let item = path("/obj/item/weapon");
+		assert_eq!(item.components().collect::<Vec<_>>(), [
+			"obj", "item", "weapon"
+		]);
+		assert_eq!(item.name(), "weapon");
+		assert_eq!(item.depth(), 3);
+	}
+
+	#[test]
+	fn parents() {
+		assert_eq!(path("/obj/item").parent(), Some(path("/obj")));
+		assert_eq!(path("/obj").parent(), Some(path("/atom/movable")));
+		assert_eq!(path("/atom/movable").parent(), Some(path("/atom")));
+		assert_eq!(path("/turf").parent(), Some(path("/atom")));
+		assert_eq!(path("/atom").parent(), Some(path("/datum")));
+		assert_eq!(path("/foo").parent(), Some(path("/datum")));
+		assert_eq!(path("/datum").parent(), None);
+		assert_eq!(path("/list").parent(), None);
+		assert_eq!(path("/mob/living").ancestors().collect::<Vec<_>>(), [
+			path("/mob"),
+			path("/atom/movable"),
+			path("/atom"),
+			path("/datum"),
+		]);
+	}
+
+	#[test]
+	fn ancestry() {
+		let item = path("/obj/item");
+		assert!(path("/atom").is_ancestor_of(&item));
+		assert!(path("/datum").is_ancestor_of(&item));
+		assert!(item.is_descendant_of(&path("/obj")));
+		assert!(!item.is_descendant_of(&item));
+		assert!(!path("/mob").is_ancestor_of(&item));
+		assert!(!path("/list").is_ancestor_of(&item));
+		assert!(!path("/obj/item_2").is_descendant_of(&item));
+	}
+}
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: 0BSD
+pub mod alist;
 pub mod list;
 pub mod num;
 pub mod proc;
@@ -7,8 +8,10 @@
 pub mod typecheck;
 
 use crate::{
-	ByondError, ByondResult, ByondValueType, FromByond, ToByond, byond, pixloc::ByondPixLoc,
-	strid::lookup_string_id, sys::CByondValue,
+	ByondError, ByondResult, ByondValueType, ByondXYZ, FromByond, ToByond, TypePath, byond,
+	pixloc::ByondPixLoc,
+	strid::lookup_string_id,
+	sys::{CByondValue, u4c},
 };
 use std::{
 	fmt,
@@ -123,6 +126,18 @@ pub fn read_var<Name, Return>(&self, name: Name) -> ByondResult<Return>
 			return Err(ByondError::NotReferenceable);
 		}
 		let name_id = lookup_string_id(name).ok_or(ByondError::InvalidVariable)?;
+		self.read_var_by_id(name_id)
+	}
+
+	/// Read a variable through the ref, using the string ID of its name, from
+	/// [`lookup_string_id`].
+	///
+	/// Useful when reading the same variable from many values, as the name
+	/// only needs to be looked up once.
+	pub fn read_var_by_id<Return>(&self, name_id: u4c) -> ByondResult<Return>
+	where
+		Return: FromByond,
+	{
 		unsafe {
 			let mut result = MaybeUninit::uninit();
 			map_byond_error!(byond().Byond_ReadVarByStrId(&self.0, name_id, result.as_mut_ptr()))?;
@@ -140,6 +155,15 @@ pub fn write_var<Name, Value>(&mut self, name: Name, value: Value) -> ByondResul
 			return Err(ByondError::NotReferenceable);
 		}
 		let name_id = lookup_string_id(name).ok_or(ByondError::InvalidVariable)?;
+		self.write_var_by_id(name_id, value)
+	}
+
+	/// Write to a variable through the ref, using the string ID of its name,
+	/// from [`lookup_string_id`].
+	pub fn write_var_by_id<Value>(&mut self, name_id: u4c, value: Value) -> ByondResult<()>
+	where
+		Value: ToByond,
+	{
 		let value = value.to_byond()?;
 		map_byond_error!(byond().Byond_WriteVarByStrId(&self.0, name_id, &value.0))
 	}
@@ -169,6 +193,21 @@ pub fn write_pointer<Value>(&mut self, value: Value) -> ByondResult<()>
 		unsafe { map_byond_error!(byond().Byond_WritePointer(&self.0, &value.0)) }
 	}
 
+	/// Gets the tile coordinates of an atom.
+	///
+	/// Returns `None` if the value doesn't have coordinates, such as if value
+	/// is not an atom.
+	///
+	/// If the atom is off-map, this will return `0,0,0`.
+	pub fn xyz(&self) -> Option<ByondXYZ> {
+		let mut xyz = MaybeUninit::uninit();
+		if unsafe { byond().Byond_XYZ(&self.0, xyz.as_mut_ptr()) } {
+			Some(ByondXYZ(unsafe { xyz.assume_init() }))
+		} else {
+			None
+		}
+	}
+
 	/// Gets the pixloc coordinates of an atom.
 	///
 	/// Returns `None` if the value doesn't have pixloc coordinates, such as if
@@ -176,24 +215,41 @@ pub fn write_pointer<Value>(&mut self, value: Value) -> ByondResult<()>
 	///
 	/// If the atom is off-map, this will return [ByondPixLoc::ZERO].
 	pub fn pixloc(&self) -> Option<ByondPixLoc> {
+		let Ok(byond_pixloc) = &byond().optional().Byond_PixLoc else {
+			return None;
+		};
 		let mut pixloc = MaybeUninit::uninit();
-		if unsafe { byond().Byond_PixLoc(&self.0, pixloc.as_mut_ptr()) } {
+		if unsafe { byond_pixloc(&self.0, pixloc.as_mut_ptr()) } {
 			Some(ByondPixLoc(unsafe { pixloc.assume_init() }))
 		} else {
 			None
 		}
 	}
 
-	/// Equivalent to calling `istype(src, text2path(typepath))``.
-	#[cfg(feature = "byond-1664")]
+	/// Equivalent to calling `istype(src, text2path(typepath))`.
+	///
+	/// On versions of BYOND older than 516.1664, this falls back to
+	/// [`TypePath::is_type`], and returns `false` if the value's type can't be
+	/// read.
 	pub fn is_type<Str>(&self, typepath: Str) -> bool
 	where
 		Str: AsRef<str>,
 	{
-		match std::ffi::CString::new(typepath.as_ref()) {
-			Ok(typepath) => unsafe { byond().ByondValue_IsType(&self.0, typepath.as_ptr()) },
-			Err(_) => false,
+		if let Ok(byond_is_type) = &byond().optional().ByondValue_IsType {
+			return match std::ffi::CString::new(typepath.as_ref()) {
+				Ok(typepath) => unsafe { byond_is_type(&self.0, typepath.as_ptr()) },
+				Err(_) => false,
+			};
+		}
+		let Ok(target) = TypePath::new(typepath) else {
+			return false;
+		};
+		if !self.is_ref() {
+			return false;
 		}
+		self.typepath()
+			.and_then(TypePath::new)
+			.is_ok_and(|typepath| typepath.is_type(&target))
 	}
 }
 
@@ -269,6 +325,21 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 	}
 }
 
+/// Shows the type and raw data of the value. Unlike [`Display`](fmt::Display),
+/// this doesn't call into BYOND, so strings are shown by their ID.
+impl fmt::Debug for ByondValue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let value_type = self.get_type();
+		match value_type {
+			ByondValueType::Null => write!(f, "ByondValue(null)"),
+			ByondValueType::Number => write!(f, "ByondValue({})", unsafe { self.0.data.num }),
+			_ => write!(f, "ByondValue({value_type}, {:#x})", unsafe {
+				self.0.data.ref_
+			}),
+		}
+	}
+}
+
 #[doc(hidden)]
 pub fn test_byondvalue_clear_is_zero() {
 	assert!(
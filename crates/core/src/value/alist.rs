@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondError, ByondResult, ByondValue, FromByond, ToByond, byond, sync::call_on_main_thread,
+};
+use std::{
+	collections::{BTreeMap, HashMap},
+	hash::{BuildHasher, Hash},
+	ops::{Deref, DerefMut},
+};
+
+impl ByondValue {
+	/// Creates a new, empty `alist`.
+	///
+	/// Unlike regular lists, alists are always associative, preserve insertion
+	/// order, and treat numbers as keys rather than indices.
+	///
+	/// Requires BYOND 516 or later.
+	pub fn new_alist() -> ByondResult<Self> {
+		if !byond().capabilities().alist {
+			return Err(ByondError::unsupported("alist", 516, 0));
+		}
+		Self::new("/alist", [])
+	}
+
+	/// Iterates over the keys of the list, reading them all at once.
+	///
+	/// For regular lists, this is the same as iterating over the values.
+	pub fn keys(&self) -> ByondResult<impl Iterator<Item = ByondValue>> {
+		self.read_list().map(Vec::into_iter)
+	}
+}
+
+/// A wrapper around a Rust map (or a list of pairs) that converts to and from
+/// a BYOND `alist`, rather than a regular assoc list.
+///
+/// Converting from BYOND accepts both alists and regular assoc lists.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Alist<Map>(pub Map);
+
+impl<Map> Alist<Map> {
+	pub fn new(map: Map) -> Self {
+		Self(map)
+	}
+
+	pub fn into_inner(self) -> Map {
+		self.0
+	}
+}
+
+impl<Map> Deref for Alist<Map> {
+	type Target = Map;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<Map> DerefMut for Alist<Map> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl<Map> From<Map> for Alist<Map> {
+	fn from(map: Map) -> Self {
+		Self(map)
+	}
+}
+
+impl<Map> FromByond for Alist<Map>
+where
+	Map: FromByond,
+{
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		if !value.is_list() {
+			return Err(ByondError::NotAList);
+		}
+		Map::from_byond(value).map(Self)
+	}
+}
+
+/// Converts every pair up front, then builds the alist in a single trip to
+/// the main thread.
+///
+/// byondapi has no bulk write for associative pairs like `Byond_WriteList` is
+/// for values, so each pair is still its own write - but from other threads,
+/// this means one thread sync for the whole alist rather than one per pair.
+fn pairs_to_alist<'a, Key, Value, Pairs>(pairs: Pairs) -> ByondResult<ByondValue>
+where
+	Key: ToByond + 'a,
+	Value: ToByond + 'a,
+	Pairs: IntoIterator<Item = (&'a Key, &'a Value)>,
+{
+	let pairs = pairs
+		.into_iter()
+		.map(|(key, value)| Ok([key.to_byond()?, value.to_byond()?]))
+		.collect::<ByondResult<Vec<_>>>()?;
+	call_on_main_thread(move || {
+		let mut alist = ByondValue::new_alist()?;
+		for [key, value] in &pairs {
+			alist.write_list_index(key, value)?;
+		}
+		Ok(alist)
+	})?
+}
+
+impl<Key, Value, Hasher> ToByond for Alist<HashMap<Key, Value, Hasher>>
+where
+	Key: ToByond + Hash + Eq,
+	Value: ToByond,
+	Hasher: BuildHasher,
+{
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		pairs_to_alist(&self.0)
+	}
+}
+
+impl<Key, Value> ToByond for Alist<BTreeMap<Key, Value>>
+where
+	Key: ToByond + Ord,
+	Value: ToByond,
+{
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		pairs_to_alist(&self.0)
+	}
+}
+
+impl<Key, Value> ToByond for Alist<Vec<(Key, Value)>>
+where
+	Key: ToByond,
+	Value: ToByond,
+{
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		pairs_to_alist(self.0.iter().map(|(key, value)| (key, value)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wrapper() {
+		let mut map = Alist::from(BTreeMap::from([("a", 1), ("b", 2)]));
+		assert_eq!(map.get("a"), Some(&1));
+		map.insert("c", 3);
+		assert_eq!(map.len(), 3);
+		assert_eq!(map.into_inner().into_iter().collect::<Vec<_>>(), [
+			("a", 1),
+			("b", 2),
+			("c", 3)
+		]);
+		assert_eq!(Alist::new(vec![(1, 2)]), Alist(vec![(1, 2)]));
+		assert!(Alist::<HashMap<u8, u8>>::default().is_empty());
+	}
+}
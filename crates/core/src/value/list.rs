@@ -91,16 +91,21 @@ pub fn write_list_index<Idx, Value>(&mut self, idx: Idx, value: Value) -> ByondR
 		map_byond_error!(byond().Byond_WriteListIndex(&self.0, &idx.0, &value.0))
 	}
 
-	/// Pushes a value into a list
+	/// Pushes a value onto the end of a list.
+	///
+	/// The value is wrapped in a list before being passed to `list.Add()`, so
+	/// lists are pushed as-is, rather than having their contents added.
 	pub fn push_list(&mut self, value: ByondValue) -> ByondResult<()> {
 		if !self.is_list() {
 			return Err(ByondError::NotAList);
 		}
-		self.call::<_, _, _, ()>("Add", [[value]])?; // byond moment
-		Ok(())
+		self.call::<_, _, _, ()>("Add", [[value]]) // byond moment
 	}
 
-	/// Pops a value from a list
+	/// Pops a value from the end of a list.
+	///
+	/// The value is removed with `list.Cut()`, so this also works on special
+	/// lists, such as `contents`, whose `len` can't be written.
 	pub fn pop_list(&mut self) -> ByondResult<Option<ByondValue>> {
 		if !self.is_list() {
 			return Err(ByondError::NotAList);
@@ -110,7 +115,7 @@ pub fn pop_list(&mut self) -> ByondResult<Option<ByondValue>> {
 			return Ok(None);
 		}
 		let value = self.read_list_index(&len)?;
-		self.write_var("len", len - 1)?;
+		self.call::<_, _, _, ()>("Cut", [len])?;
 		Ok(Some(value))
 	}
 
@@ -120,28 +125,51 @@ pub fn pop_list(&mut self) -> ByondResult<Option<ByondValue>> {
 	///
 	/// Non assoc lists will have the second field of the tuple be null (key,
 	/// value) for proper assoc lists.
+	///
+	/// The entire list is read at once with a single bulk read, falling back
+	/// to reading it one index at a time if that somehow fails.
 	pub fn iter(&self) -> ByondResult<impl Iterator<Item = (ByondValue, ByondValue)> + '_> {
 		if !self.is_list() {
 			return Err(ByondError::NotAList);
 		}
-		let len = self.length()?;
-		Ok(ListIterator {
-			value: self,
-			len,
-			ctr: 1,
-		})
+		match self.read_assoc_list() {
+			Ok(list) => Ok(AssocIter::Bulk(list.into_iter())),
+			// alists treat numbers as keys rather than indices, so they can't be
+			// iterated by index.
+			Err(err) if self.is_alist() => Err(err),
+			Err(_) => {
+				let len = self.length()?;
+				Ok(AssocIter::Indexed(ListIterator {
+					value: self,
+					len,
+					ctr: 1,
+				}))
+			}
+		}
 	}
 
+	/// Iterates through the values of the list if this value is a list.
+	///
+	/// If the value isn't a list then it returns an error.
+	///
+	/// The entire list is read at once with a single bulk read, falling back
+	/// to reading it one index at a time if that somehow fails.
 	pub fn values(&self) -> ByondResult<impl Iterator<Item = ByondValue> + '_> {
 		if !self.is_list() {
 			return Err(ByondError::NotAList);
 		}
-		let len = self.length()?;
-		Ok(ValueIterator {
-			value: self,
-			len,
-			ctr: 1,
-		})
+		match self.read_list() {
+			Ok(list) => Ok(ValuesIter::Bulk(list.into_iter())),
+			Err(err) if self.is_alist() => Err(err),
+			Err(_) => {
+				let len = self.length()?;
+				Ok(ValuesIter::Indexed(ValueIterator {
+					value: self,
+					len,
+					ctr: 1,
+				}))
+			}
+		}
 	}
 }
 
@@ -194,6 +222,46 @@ fn size_hint(&self) -> (usize, Option<usize>) {
 	}
 }
 
+enum ValuesIter<'a> {
+	Indexed(ValueIterator<'a>),
+	Bulk(std::vec::IntoIter<ByondValue>),
+}
+impl Iterator for ValuesIter<'_> {
+	type Item = ByondValue;
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::Indexed(iter) => iter.next(),
+			Self::Bulk(iter) => iter.next(),
+		}
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self {
+			Self::Indexed(iter) => iter.size_hint(),
+			Self::Bulk(iter) => iter.size_hint(),
+		}
+	}
+}
+
+enum AssocIter<'a> {
+	Indexed(ListIterator<'a>),
+	Bulk(std::vec::IntoIter<[ByondValue; 2]>),
+}
+impl Iterator for AssocIter<'_> {
+	type Item = (ByondValue, ByondValue);
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::Indexed(iter) => iter.next(),
+			Self::Bulk(iter) => iter.next().map(|[key, value]| (key, value)),
+		}
+	}
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self {
+			Self::Indexed(iter) => iter.size_hint(),
+			Self::Bulk(iter) => iter.size_hint(),
+		}
+	}
+}
+
 // Safety: `list` should always have a length that is a multiple of 2.
 unsafe fn stupid_assoc_cast(list: Vec<ByondValue>) -> Vec<[ByondValue; 2]> {
 	use crate::sys::CByondValue;
@@ -94,6 +94,62 @@ pub fn test_ref(mut self) -> Option<Self> {
 			None
 		}
 	}
+
+	/// Creates a [`WeakByondValue`] pointing to this value, without touching
+	/// its reference count.
+	///
+	/// Returns `None` if the value is null or a number.
+	pub fn downgrade(&self) -> Option<WeakByondValue> {
+		if self.is_null() || self.is_number() {
+			return None;
+		}
+		Some(WeakByondValue {
+			value_type: self.get_type(),
+			ref_id: unsafe { self.0.data.ref_ },
+		})
+	}
+}
+
+/// A reference to a value that doesn't keep it alive, made up of only its type
+/// and reference ID.
+///
+/// Once the value is deleted, [`upgrade()`](Self::upgrade) returns `None`,
+/// unless its reference ID has already been reused by a new value of the same
+/// type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WeakByondValue {
+	value_type: ByondValueType,
+	ref_id: u32,
+}
+
+impl WeakByondValue {
+	/// Creates a weak reference from a value type and reference ID, without
+	/// checking if it's valid.
+	pub const fn from_raw(value_type: ByondValueType, ref_id: u32) -> Self {
+		Self { value_type, ref_id }
+	}
+
+	/// Returns the type of the referenced value.
+	#[inline]
+	pub const fn value_type(&self) -> ByondValueType {
+		self.value_type
+	}
+
+	/// Returns the reference ID of the referenced value.
+	#[inline]
+	pub const fn ref_id(&self) -> u32 {
+		self.ref_id
+	}
+
+	/// Returns the referenced value, or `None` if it no longer exists.
+	pub fn upgrade(&self) -> Option<ByondValue> {
+		ByondValue::new_ref(self.value_type, self.ref_id)
+	}
+
+	/// Returns if the referenced value still exists.
+	pub fn is_valid(&self) -> bool {
+		self.upgrade().is_some()
+	}
 }
 
 /// A wrapper for [`ByondValue`] that decrements the reference count when
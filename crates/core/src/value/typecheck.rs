@@ -54,6 +54,14 @@ pub fn is_list(&self) -> bool {
 		unsafe { byond().ByondValue_IsList(&self.0) }
 	}
 
+	/// Determines if the [ByondValue] is an `alist`.
+	///
+	/// # Returns
+	/// `true` if the value is an alist, `false` otherwise.
+	pub fn is_alist(&self) -> bool {
+		self.get_type() == ByondValueType::Alist
+	}
+
 	/// Evaluates whether the [ByondValue] is considered "true" or not.
 	///
 	/// # Returns
@@ -228,6 +236,12 @@ impl ByondValueType {
 		Self::Datum,
 	];
 
+	/// All types that are considered atoms
+	pub const ATOM_TYPES: &[Self] = &[Self::Turf, Self::Obj, Self::Mob, Self::Area];
+
+	/// All types that are considered movable atoms
+	pub const MOVABLE_TYPES: &[Self] = &[Self::Obj, Self::Mob];
+
 	/// All types that are tpyepaths.
 	pub const PATH_TYPES: &[Self] = &[
 		Self::MobTypepath,
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{
+	ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond, byond,
+	pixloc::ByondPixLoc, xyz::ByondXYZ,
+};
+use std::{
+	borrow::Cow,
+	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// A 3D vector, equivalent to BYOND 516's `vector` type.
+///
+/// 2D vectors are represented with a Z of 0.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ByondVector {
+	pub x: f32,
+	pub y: f32,
+	pub z: f32,
+}
+
+impl ByondVector {
+	/// A vector with all components as 0.
+	pub const ZERO: Self = Self::new(0.0, 0.0, 0.0);
+
+	/// Creates a new vector with the given components.
+	#[inline]
+	pub const fn new(x: f32, y: f32, z: f32) -> Self {
+		Self { x, y, z }
+	}
+
+	/// Returns the length (magnitude) of the vector.
+	///
+	/// Equivalent to `vector.len` in DM.
+	pub fn len(&self) -> f32 {
+		self.len_squared().sqrt()
+	}
+
+	/// Returns the squared length of the vector, which is cheaper to compute
+	/// than [`len()`](Self::len) when only comparing lengths.
+	pub fn len_squared(&self) -> f32 {
+		self.dot(self)
+	}
+
+	/// Returns if all components of the vector are 0.
+	pub fn is_zero(&self) -> bool {
+		*self == Self::ZERO
+	}
+
+	/// Returns the dot product of two vectors.
+	///
+	/// Equivalent to `vector.Dot(other)` in DM.
+	pub fn dot(&self, other: &Self) -> f32 {
+		self.x * other.x + self.y * other.y + self.z * other.z
+	}
+
+	/// Returns the cross product of two vectors.
+	///
+	/// Equivalent to `vector.Cross(other)` in DM.
+	pub fn cross(&self, other: &Self) -> Self {
+		Self::new(
+			self.y * other.z - self.z * other.y,
+			self.z * other.x - self.x * other.z,
+			self.x * other.y - self.y * other.x,
+		)
+	}
+
+	/// Returns a vector with the same direction and a length of 1, or a zero
+	/// vector if this has a length of 0.
+	///
+	/// Equivalent to `vector.Normalize()` in DM.
+	pub fn normalize(&self) -> Self {
+		let len = self.len();
+		if len == 0.0 { Self::ZERO } else { *self / len }
+	}
+
+	/// Returns the Euclidean distance between two vectors.
+	pub fn distance(&self, other: &Self) -> f32 {
+		(*self - *other).len()
+	}
+
+	/// Linearly interpolates between two vectors, where a `t` of 0 returns
+	/// `self` and a `t` of 1 returns `other`.
+	pub fn lerp(&self, other: &Self, t: f32) -> Self {
+		*self + (*other - *self) * t
+	}
+}
+
+impl FromByond for ByondVector {
+	/// Converts either a `vector`, or a list of 2 or 3 numbers.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let value_type = value.get_type();
+		if value_type == ByondValueType::Vector {
+			return Ok(Self::new(
+				value.read_var("x")?,
+				value.read_var("y")?,
+				value.read_var("z")?,
+			));
+		}
+		if value.is_list() {
+			match value.read_list()?.as_slice() {
+				[x, y] => return Ok(Self::new(x.get_number()?, y.get_number()?, 0.0)),
+				[x, y, z] => {
+					return Ok(Self::new(x.get_number()?, y.get_number()?, z.get_number()?));
+				}
+				_ => {}
+			}
+		}
+		Err(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("vector"),
+			got: value_type.name(),
+		})
+	}
+}
+
+impl ToByond for ByondVector {
+	/// Creates a new `vector`, which requires BYOND 516 or later.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		if !byond().capabilities().vector {
+			return Err(ByondError::unsupported("vector", 516, 0));
+		}
+		ByondValue::new("/vector", [
+			ByondValue::new_num(self.x),
+			ByondValue::new_num(self.y),
+			ByondValue::new_num(self.z),
+		])
+	}
+}
+
+impl From<ByondXYZ> for ByondVector {
+	#[inline]
+	fn from(xyz: ByondXYZ) -> Self {
+		Self::new(xyz.x() as f32, xyz.y() as f32, xyz.z() as f32)
+	}
+}
+
+impl From<ByondVector> for ByondXYZ {
+	/// Rounds each component to the nearest whole number.
+	#[inline]
+	fn from(vector: ByondVector) -> Self {
+		Self::new(
+			vector.x.round() as i16,
+			vector.y.round() as i16,
+			vector.z.round() as i16,
+		)
+	}
+}
+
+impl From<ByondPixLoc> for ByondVector {
+	#[inline]
+	fn from(pixloc: ByondPixLoc) -> Self {
+		Self::new(pixloc.x(), pixloc.y(), pixloc.z() as f32)
+	}
+}
+
+impl From<ByondVector> for ByondPixLoc {
+	/// The Z component is rounded to the nearest whole number.
+	#[inline]
+	fn from(vector: ByondVector) -> Self {
+		Self::new(vector.x, vector.y, vector.z.round() as i16)
+	}
+}
+
+impl From<(f32, f32, f32)> for ByondVector {
+	#[inline]
+	fn from((x, y, z): (f32, f32, f32)) -> Self {
+		Self::new(x, y, z)
+	}
+}
+
+impl From<ByondVector> for (f32, f32, f32) {
+	#[inline]
+	fn from(vector: ByondVector) -> Self {
+		(vector.x, vector.y, vector.z)
+	}
+}
+
+impl From<[f32; 3]> for ByondVector {
+	#[inline]
+	fn from([x, y, z]: [f32; 3]) -> Self {
+		Self::new(x, y, z)
+	}
+}
+
+impl From<ByondVector> for [f32; 3] {
+	#[inline]
+	fn from(vector: ByondVector) -> Self {
+		[vector.x, vector.y, vector.z]
+	}
+}
+
+impl Add for ByondVector {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+	}
+}
+
+impl AddAssign for ByondVector {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+impl Sub for ByondVector {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self {
+		Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+	}
+}
+
+impl SubAssign for ByondVector {
+	fn sub_assign(&mut self, other: Self) {
+		*self = *self - other;
+	}
+}
+
+impl Mul<f32> for ByondVector {
+	type Output = Self;
+
+	fn mul(self, scalar: f32) -> Self {
+		Self::new(self.x * scalar, self.y * scalar, self.z * scalar)
+	}
+}
+
+impl MulAssign<f32> for ByondVector {
+	fn mul_assign(&mut self, scalar: f32) {
+		*self = *self * scalar;
+	}
+}
+
+/// Component-wise multiplication, like `vector * vector` in DM.
+impl Mul for ByondVector {
+	type Output = Self;
+
+	fn mul(self, other: Self) -> Self {
+		Self::new(self.x * other.x, self.y * other.y, self.z * other.z)
+	}
+}
+
+impl Div<f32> for ByondVector {
+	type Output = Self;
+
+	fn div(self, scalar: f32) -> Self {
+		Self::new(self.x / scalar, self.y / scalar, self.z / scalar)
+	}
+}
+
+impl DivAssign<f32> for ByondVector {
+	fn div_assign(&mut self, scalar: f32) {
+		*self = *self / scalar;
+	}
+}
+
+impl Neg for ByondVector {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(-self.x, -self.y, -self.z)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn arithmetic() {
+		let a = ByondVector::new(1.0, 2.0, 3.0);
+		let b = ByondVector::new(4.0, 5.0, 6.0);
+		assert_eq!(a + b, ByondVector::new(5.0, 7.0, 9.0));
+		assert_eq!(b - a, ByondVector::new(3.0, 3.0, 3.0));
+		assert_eq!(a * 2.0, ByondVector::new(2.0, 4.0, 6.0));
+		assert_eq!(-a, ByondVector::new(-1.0, -2.0, -3.0));
+		assert_eq!(a.dot(&b), 32.0);
+		assert_eq!(a.cross(&b), ByondVector::new(-3.0, 6.0, -3.0));
+		assert_eq!(ByondVector::new(3.0, 4.0, 0.0).len(), 5.0);
+		assert_eq!(
+			ByondVector::new(0.0, 2.0, 0.0).normalize(),
+			ByondVector::new(0.0, 1.0, 0.0)
+		);
+		assert_eq!(ByondVector::ZERO.normalize(), ByondVector::ZERO);
+	}
+
+	#[test]
+	fn conversions() {
+		let xyz = ByondXYZ::new(3, 4, 1);
+		assert_eq!(ByondXYZ::from(ByondVector::from(xyz)), xyz);
+		assert_eq!(
+			ByondXYZ::from(ByondVector::new(2.6, 3.4, 1.0)),
+			ByondXYZ::new(3, 3, 1)
+		);
+		let pixloc = ByondPixLoc::new(32.5, 64.0, 2);
+		assert_eq!(ByondPixLoc::from(ByondVector::from(pixloc)), pixloc);
+	}
+}
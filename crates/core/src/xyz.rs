@@ -1,6 +1,12 @@
 // SPDX-License-Identifier: 0BSD
-use crate::sys::CByondXYZ;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use crate::{
+	ByondError, ByondResult, ByondValue, ByondValueType, FromByond, ToByond, handle::Turf, misc,
+	sys::CByondXYZ,
+};
+use std::{
+	borrow::Cow,
+	ops::{Add, AddAssign, Sub, SubAssign},
+};
 
 #[derive(Copy, Clone, Default)]
 #[repr(transparent)]
@@ -74,6 +80,60 @@ pub fn distance_euclidean(&self, other: &ByondXYZ) -> f64 {
 	}
 }
 
+impl ByondXYZ {
+	/// Returns the turf at these coordinates, or `None` if they're off-map.
+	///
+	/// Equivalent to `locate(x, y, z)` in DM.
+	pub fn turf(&self) -> ByondResult<Option<Turf>> {
+		if self.z() == 0 {
+			return Ok(None);
+		}
+		misc::locate_xyz(*self)?.to()
+	}
+}
+
+impl FromByond for ByondXYZ {
+	/// Converts either the coordinates of an atom, or a list of 3 numbers.
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let value_type = value.get_type();
+		if ByondValueType::ATOM_TYPES.contains(&value_type) {
+			if let Some(xyz) = value.xyz() {
+				return Ok(xyz);
+			}
+		} else if value.is_list() {
+			if let [x, y, z] = value.read_list()?.as_slice() {
+				return Ok(Self::new(
+					x.get_number()? as i16,
+					y.get_number()? as i16,
+					z.get_number()? as i16,
+				));
+			}
+		}
+		Err(ByondError::InvalidConversion {
+			expected: Cow::Borrowed("coordinates"),
+			got: value_type.name(),
+		})
+	}
+}
+
+impl ToByond for ByondXYZ {
+	/// Converts into the turf at these coordinates, or null if they're
+	/// off-map.
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		misc::locate_xyz(*self)
+	}
+}
+
+impl std::fmt::Debug for ByondXYZ {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ByondXYZ")
+			.field("x", &self.x())
+			.field("y", &self.y())
+			.field("z", &self.z())
+			.finish()
+	}
+}
+
 impl PartialEq for ByondXYZ {
 	fn eq(&self, other: &Self) -> bool {
 		self.x() == other.x() && self.y() == other.y() && self.z() == other.z()
@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: 0BSD
+use crate::DmiError;
+use std::{io::Write, path::Path};
+
+/// An 8-bit RGBA image, such as a single frame of an icon state.
+///
+/// Unlike DM's `GetPixel`, coordinates start at `(0, 0)` in the top-left
+/// corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+	width: u32,
+	height: u32,
+	pixels: Vec<u8>,
+}
+
+impl Bitmap {
+	/// Creates a fully transparent image.
+	#[must_use]
+	pub fn new(width: u32, height: u32) -> Self {
+		Self {
+			width,
+			height,
+			pixels: vec![0; width as usize * height as usize * 4],
+		}
+	}
+
+	/// Creates an image from RGBA pixel data, row by row, returning `None` if
+	/// the length of `pixels` doesn't match the size.
+	pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+		(pixels.len() == width as usize * height as usize * 4).then_some(Self {
+			width,
+			height,
+			pixels,
+		})
+	}
+
+	#[inline]
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+
+	#[inline]
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Returns the RGBA pixel data, row by row.
+	#[inline]
+	pub fn as_rgba(&self) -> &[u8] {
+		&self.pixels
+	}
+
+	/// Returns the RGBA pixel data, row by row.
+	#[inline]
+	pub fn as_rgba_mut(&mut self) -> &mut [u8] {
+		&mut self.pixels
+	}
+
+	#[inline]
+	pub fn into_rgba(self) -> Vec<u8> {
+		self.pixels
+	}
+
+	/// Returns the RGBA value of a pixel.
+	pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+		let idx = self.index_of(x, y)?;
+		self.pixels[idx..idx + 4].try_into().ok()
+	}
+
+	/// Sets the RGBA value of a pixel, returning `false` if it's out of
+	/// bounds.
+	pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) -> bool {
+		match self.index_of(x, y) {
+			Some(idx) => {
+				self.pixels[idx..idx + 4].copy_from_slice(&rgba);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Copies a region of this image into a new image. Any part of the region
+	/// outside of this image is left transparent.
+	#[must_use]
+	pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+		let mut cropped = Self::new(width, height);
+		cropped.copy_from(self, (x, y), (0, 0), (width, height));
+		cropped
+	}
+
+	/// Copies `other` onto this image with its top-left corner at `(x, y)`,
+	/// replacing the pixels underneath. Any part of `other` outside of this
+	/// image is ignored.
+	pub fn paste(&mut self, other: &Self, x: u32, y: u32) {
+		self.copy_from(other, (0, 0), (x, y), (other.width, other.height));
+	}
+
+	/// Draws `other` over this image with its top-left corner at `(x, y)`,
+	/// blending by alpha. Any part of `other` outside of this image is
+	/// ignored.
+	pub fn blend(&mut self, other: &Self, x: i32, y: i32) {
+		for other_y in 0..other.height {
+			let Ok(self_y) = u32::try_from(y + other_y as i32) else {
+				continue;
+			};
+			for other_x in 0..other.width {
+				let Ok(self_x) = u32::try_from(x + other_x as i32) else {
+					continue;
+				};
+				let (Some(src), Some(dst)) = (
+					other.index_of(other_x, other_y),
+					self.index_of(self_x, self_y),
+				) else {
+					continue;
+				};
+				let src: [u8; 4] = other.pixels[src..src + 4].try_into().unwrap_or_default();
+				blend_pixel(&mut self.pixels[dst..dst + 4], src);
+			}
+		}
+	}
+
+	/// Writes the image as a PNG.
+	pub fn write_png<Writer>(&self, writer: Writer) -> Result<(), DmiError>
+	where
+		Writer: Write,
+	{
+		self.encode(writer, None)
+	}
+
+	/// Returns the image encoded as a PNG.
+	pub fn to_png(&self) -> Result<Vec<u8>, DmiError> {
+		let mut bytes = Vec::new();
+		self.write_png(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Writes the image to a PNG file, replacing it if it already exists.
+	pub fn save_png<FilePath>(&self, path: FilePath) -> Result<(), DmiError>
+	where
+		FilePath: AsRef<Path>,
+	{
+		std::fs::write(path, self.to_png()?)?;
+		Ok(())
+	}
+
+	/// Writes the image as a PNG, with an optional compressed text chunk.
+	pub(crate) fn encode<Writer>(
+		&self,
+		writer: Writer,
+		text: Option<(&str, String)>,
+	) -> Result<(), DmiError>
+	where
+		Writer: Write,
+	{
+		let mut encoder = png::Encoder::new(writer, self.width, self.height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		if let Some((keyword, text)) = text {
+			encoder.add_ztxt_chunk(keyword.to_owned(), text)?;
+		}
+		let mut writer = encoder.write_header()?;
+		writer.write_image_data(&self.pixels)?;
+		writer.finish()?;
+		Ok(())
+	}
+
+	fn copy_from(
+		&mut self,
+		other: &Self,
+		(src_x, src_y): (u32, u32),
+		(dst_x, dst_y): (u32, u32),
+		(width, height): (u32, u32),
+	) {
+		let width = width
+			.min(other.width.saturating_sub(src_x))
+			.min(self.width.saturating_sub(dst_x)) as usize;
+		let height = height
+			.min(other.height.saturating_sub(src_y))
+			.min(self.height.saturating_sub(dst_y));
+		if width == 0 {
+			return;
+		}
+		for row in 0..height {
+			let (Some(src), Some(dst)) = (
+				other.index_of(src_x, src_y + row),
+				self.index_of(dst_x, dst_y + row),
+			) else {
+				break;
+			};
+			self.pixels[dst..dst + width * 4].copy_from_slice(&other.pixels[src..src + width * 4]);
+		}
+	}
+
+	fn index_of(&self, x: u32, y: u32) -> Option<usize> {
+		(x < self.width && y < self.height)
+			.then(|| (y as usize * self.width as usize + x as usize) * 4)
+	}
+}
+
+/// Draws `src` over `dst` with the "over" operator, using straight alpha.
+fn blend_pixel(dst: &mut [u8], src: [u8; 4]) {
+	match src[3] {
+		0 => {}
+		255 => dst.copy_from_slice(&src),
+		src_alpha => {
+			let src_alpha = f32::from(src_alpha) / 255.0;
+			let dst_alpha = f32::from(dst[3]) / 255.0 * (1.0 - src_alpha);
+			let alpha = src_alpha + dst_alpha;
+			for channel in 0..3 {
+				let color = (f32::from(src[channel]) * src_alpha
+					+ f32::from(dst[channel]) * dst_alpha)
+					/ alpha;
+				dst[channel] = color.round() as u8;
+			}
+			dst[3] = (alpha * 255.0).round() as u8;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn crop_and_paste() {
+		let mut sheet = Bitmap::new(4, 4);
+		assert!(sheet.set_pixel(2, 1, [255, 0, 0, 255]));
+		assert!(!sheet.set_pixel(4, 0, [255, 0, 0, 255]));
+
+		let cropped = sheet.crop(2, 0, 4, 2);
+		assert_eq!(cropped.width(), 4);
+		assert_eq!(cropped.pixel(0, 1), Some([255, 0, 0, 255]));
+		assert_eq!(cropped.pixel(1, 1), Some([0; 4]));
+		assert_eq!(cropped.pixel(3, 1), Some([0; 4]));
+
+		let mut other = Bitmap::new(3, 3);
+		other.paste(&cropped, 1, 1);
+		assert_eq!(other.pixel(1, 2), Some([255, 0, 0, 255]));
+		assert_eq!(other.pixel(3, 2), None);
+	}
+
+	#[test]
+	fn blend() {
+		let mut base = Bitmap::new(2, 2);
+		base.set_pixel(0, 0, [0, 0, 255, 255]);
+		let mut top = Bitmap::new(2, 2);
+		top.set_pixel(1, 1, [255, 0, 0, 128]);
+		top.set_pixel(0, 1, [0, 255, 0, 255]);
+		base.blend(&top, -1, -1);
+		assert_eq!(base.pixel(0, 0), Some([128, 0, 127, 255]));
+		base.blend(&top, 0, 0);
+		assert_eq!(base.pixel(0, 1), Some([0, 255, 0, 255]));
+		assert_eq!(base.pixel(1, 1), Some([255, 0, 0, 128]));
+	}
+}
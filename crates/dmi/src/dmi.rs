@@ -0,0 +1,326 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{Bitmap, DmiError, IconState, metadata};
+use std::{
+	io::{BufRead, Cursor, Seek, Write},
+	path::Path,
+};
+
+/// The keyword of the text chunk that DMI metadata is stored in.
+const KEYWORD: &str = "Description";
+
+/// An icon file: a list of states, where every image is the same size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dmi {
+	width: u32,
+	height: u32,
+	states: Vec<IconState>,
+}
+
+impl Dmi {
+	/// Creates an icon without any states, where every image is
+	/// `width`x`height`.
+	#[must_use]
+	pub fn new(width: u32, height: u32) -> Self {
+		Self {
+			width,
+			height,
+			states: Vec::new(),
+		}
+	}
+
+	/// Reads an icon from a PNG containing DMI metadata.
+	pub fn read<Reader>(reader: Reader) -> Result<Self, DmiError>
+	where
+		Reader: BufRead + Seek,
+	{
+		let mut decoder = png::Decoder::new(reader);
+		decoder.set_transformations(
+			png::Transformations::normalize_to_color8() | png::Transformations::ALPHA,
+		);
+		let mut reader = decoder.read_info()?;
+		let mut buffer = vec![0; reader.output_buffer_size()];
+		let output = reader.next_frame(&mut buffer)?;
+		let sheet = to_rgba(&buffer, output.width, output.height, output.color_type)?;
+		let text = match find_metadata(reader.info())? {
+			Some(text) => text,
+			None => {
+				// Text chunks may come after the image data.
+				reader.finish()?;
+				find_metadata(reader.info())?.ok_or(DmiError::MissingMetadata)?
+			}
+		};
+		let metadata::Metadata {
+			width,
+			height,
+			mut states,
+		} = metadata::parse(&text)?;
+		if width == 0 || height == 0 {
+			return Err(DmiError::Layout(format!(
+				"invalid icon size {width}x{height}"
+			)));
+		}
+		let columns = sheet.width() / width;
+		let capacity = columns as usize * (sheet.height() / height) as usize;
+		let mut next = 0_usize;
+		for state in &mut states {
+			// Checked before allocating anything, so corrupt frame counts
+			// can't make us allocate huge amounts of memory.
+			let count = (state.dirs as usize).checked_mul(state.frames);
+			let Some(end) = count.and_then(|count| next.checked_add(count)) else {
+				return Err(DmiError::Layout(format!(
+					"state \"{}\" has too many frames",
+					state.name
+				)));
+			};
+			if end > capacity {
+				return Err(DmiError::Layout(format!(
+					"state \"{}\" is outside of the {}x{} image",
+					state.name,
+					sheet.width(),
+					sheet.height()
+				)));
+			}
+			state.delays.resize(state.frames, 1.0);
+			state.images = (next..end)
+				.map(|idx| {
+					let (x, y) = (idx as u32 % columns, idx as u32 / columns);
+					sheet.crop(x * width, y * height, width, height)
+				})
+				.collect();
+			next = end;
+		}
+		Ok(Self {
+			width,
+			height,
+			states,
+		})
+	}
+
+	/// Reads an icon from the contents of a `.dmi` file.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, DmiError> {
+		Self::read(Cursor::new(bytes))
+	}
+
+	/// Reads an icon from a `.dmi` file.
+	pub fn from_file<FilePath>(path: FilePath) -> Result<Self, DmiError>
+	where
+		FilePath: AsRef<Path>,
+	{
+		Self::from_bytes(&std::fs::read(path)?)
+	}
+
+	/// Writes the icon as a PNG, laying the images out in a grid that's
+	/// roughly square, like Dream Maker does.
+	pub fn write<Writer>(&self, writer: Writer) -> Result<(), DmiError>
+	where
+		Writer: Write,
+	{
+		let count = self.image_count().max(1);
+		let columns = count.isqrt() + usize::from(count.isqrt().pow(2) < count);
+		let rows = count.div_ceil(columns);
+		let mut sheet = Bitmap::new(self.width * columns as u32, self.height * rows as u32);
+		let images = self.states.iter().flat_map(|state| &state.images);
+		for (idx, image) in images.enumerate() {
+			let (x, y) = ((idx % columns) as u32, (idx / columns) as u32);
+			sheet.paste(image, x * self.width, y * self.height);
+		}
+		let text = metadata::write(self.width, self.height, &self.states);
+		sheet.encode(writer, Some((KEYWORD, text)))
+	}
+
+	/// Writes the icon to a `.dmi` file's contents, the same as
+	/// [`write`](Self::write).
+	pub fn to_bytes(&self) -> Result<Vec<u8>, DmiError> {
+		let mut bytes = Vec::new();
+		self.write(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Writes the icon to a `.dmi` file, replacing it if it already exists.
+	pub fn save<FilePath>(&self, path: FilePath) -> Result<(), DmiError>
+	where
+		FilePath: AsRef<Path>,
+	{
+		std::fs::write(path, self.to_bytes()?)?;
+		Ok(())
+	}
+
+	/// Returns the width of every image.
+	#[inline]
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+
+	/// Returns the height of every image.
+	#[inline]
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Returns every state, in the order they appear in the file.
+	#[inline]
+	pub fn states(&self) -> &[IconState] {
+		&self.states
+	}
+
+	/// Returns the first non-movement state with the given name.
+	pub fn state(&self, name: &str) -> Option<&IconState> {
+		self.states
+			.iter()
+			.find(|state| !state.movement && state.name == name)
+	}
+
+	/// Returns the first non-movement state with the given name mutably.
+	pub fn state_mut(&mut self, name: &str) -> Option<&mut IconState> {
+		self.states
+			.iter_mut()
+			.find(|state| !state.movement && state.name == name)
+	}
+
+	/// Returns the first movement state with the given name.
+	pub fn movement_state(&self, name: &str) -> Option<&IconState> {
+		self.states
+			.iter()
+			.find(|state| state.movement && state.name == name)
+	}
+
+	/// Adds a state to the end of the icon, returning an error if its images
+	/// aren't the same size as the icon.
+	pub fn push_state(&mut self, state: IconState) -> Result<(), DmiError> {
+		if let Some(image) = state
+			.images
+			.iter()
+			.find(|image| (image.width(), image.height()) != (self.width, self.height))
+		{
+			return Err(DmiError::Layout(format!(
+				"state \"{}\" has a {}x{} image, but the icon is {}x{}",
+				state.name,
+				image.width(),
+				image.height(),
+				self.width,
+				self.height
+			)));
+		}
+		self.states.push(state);
+		Ok(())
+	}
+
+	/// Removes and returns the state at `idx`.
+	pub fn remove_state(&mut self, idx: usize) -> Option<IconState> {
+		(idx < self.states.len()).then(|| self.states.remove(idx))
+	}
+
+	fn image_count(&self) -> usize {
+		self.states.iter().map(|state| state.images.len()).sum()
+	}
+}
+
+fn find_metadata(info: &png::Info) -> Result<Option<String>, DmiError> {
+	if let Some(chunk) = info
+		.compressed_latin1_text
+		.iter()
+		.find(|chunk| chunk.keyword == KEYWORD)
+	{
+		return Ok(Some(chunk.get_text()?));
+	}
+	if let Some(chunk) = info
+		.uncompressed_latin1_text
+		.iter()
+		.find(|chunk| chunk.keyword == KEYWORD)
+	{
+		return Ok(Some(chunk.text.clone()));
+	}
+	match info.utf8_text.iter().find(|chunk| chunk.keyword == KEYWORD) {
+		Some(chunk) => Ok(Some(chunk.get_text()?)),
+		None => Ok(None),
+	}
+}
+
+/// Converts decoded 8-bit pixels, which are either RGBA or grayscale with
+/// alpha after expanding, to RGBA.
+fn to_rgba(
+	buffer: &[u8],
+	width: u32,
+	height: u32,
+	color_type: png::ColorType,
+) -> Result<Bitmap, DmiError> {
+	let pixels = match color_type {
+		png::ColorType::Rgba => buffer.to_vec(),
+		png::ColorType::GrayscaleAlpha => buffer
+			.chunks_exact(2)
+			.flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+			.collect(),
+		other => {
+			return Err(DmiError::Layout(format!(
+				"unsupported color type {other:?}"
+			)));
+		}
+	};
+	let len = width as usize * height as usize * 4;
+	Bitmap::from_rgba(
+		width,
+		height,
+		pixels.get(..len).unwrap_or_default().to_vec(),
+	)
+	.ok_or_else(|| DmiError::Layout("image data is truncated".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use meowtonin::ByondDir;
+
+	#[test]
+	fn round_trip() {
+		let mut dmi = Dmi::new(2, 3);
+		let mut door = IconState::new("door", 2, 3, 4, 2)
+			.delays([1.0, 3.0])
+			.rewind(true);
+		door.image_mut(ByondDir::WEST, 1)
+			.unwrap()
+			.set_pixel(1, 2, [10, 20, 30, 40]);
+		dmi.push_state(IconState::new("", 2, 3, 1, 1)).unwrap();
+		dmi.push_state(door).unwrap();
+		dmi.push_state(IconState::new("door", 2, 3, 1, 1).movement(true))
+			.unwrap();
+		assert!(dmi.push_state(IconState::new("big", 4, 4, 1, 1)).is_err());
+
+		let bytes = dmi.to_bytes().unwrap();
+		let read = Dmi::from_bytes(&bytes).unwrap();
+		assert_eq!(read, dmi);
+
+		let door = read.state("door").unwrap();
+		assert_eq!(door.frame_delays(), [1.0, 3.0]);
+		let pixel = |dir| door.image(dir, 1).unwrap().pixel(1, 2).unwrap();
+		assert_eq!(pixel(ByondDir::WEST), [10, 20, 30, 40]);
+		assert_eq!(pixel(ByondDir::NORTHWEST), [10, 20, 30, 40]);
+		assert_eq!(pixel(ByondDir::EAST), [0; 4]);
+		assert!(door.image(ByondDir::WEST, 2).is_none());
+		assert!(read.movement_state("door").unwrap().is_movement());
+
+		assert!(matches!(
+			Dmi::from_bytes(&bytes[..bytes.len() / 2]),
+			Err(DmiError::Decode(_))
+		));
+	}
+
+	#[test]
+	fn huge_frame_counts() {
+		let dmi = |frames: &str| {
+			let text = format!(
+				"# BEGIN DMI\nversion = 4.0\n\twidth = 1\n\theight = 1\nstate = \"a\"\n\tdirs = \
+				 8\n\tframes = {frames}\n# END DMI\n"
+			);
+			let mut bytes = Vec::new();
+			Bitmap::new(2, 2)
+				.encode(&mut bytes, Some((KEYWORD, text)))
+				.unwrap();
+			Dmi::from_bytes(&bytes)
+		};
+		assert!(matches!(dmi("4000000000"), Err(DmiError::Layout(_))));
+		assert!(matches!(
+			dmi(&usize::MAX.to_string()),
+			Err(DmiError::Layout(_))
+		));
+	}
+}
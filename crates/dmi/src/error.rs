@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: 0BSD
+#[derive(Debug, thiserror::Error)]
+pub enum DmiError {
+	#[error("failed to decode png: {0}")]
+	Decode(#[from] png::DecodingError),
+	#[error("failed to encode png: {0}")]
+	Encode(#[from] png::EncodingError),
+	#[error("png has no dmi metadata")]
+	MissingMetadata,
+	#[error("metadata line {line}: {message}")]
+	Metadata { line: usize, message: String },
+	#[error("{0}")]
+	Layout(String),
+	#[error("failed to read icon: {0}")]
+	Io(#[from] std::io::Error),
+}
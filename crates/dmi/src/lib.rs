@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: 0BSD
+//! Reading and writing of BYOND `.dmi` icon files.
+//!
+//! A DMI is a PNG with every image of every state laid out in a grid, along
+//! with a `Description` text chunk that says how big each image is and which
+//! states they belong to. Like with `meowtonin-dmm`, nothing here needs BYOND,
+//! except converting a resource to an [`IconPath`], so icons can be read and
+//! edited on any thread, instead of with DM's slow `icon` procs.
+//!
+//! ```no_run
+//! use meowtonin::{ByondDir, ByondError, ByondResult, ByondValue, ToByond};
+//! use meowtonin_dmi::IconPath;
+//!
+//! #[meowtonin::byond_fn]
+//! fn get_pixel(icon: IconPath, state: String, x: u32, y: u32) -> ByondResult<ByondValue> {
+//!     let dmi = icon.load().map_err(ByondError::boxed)?;
+//!     let pixel = dmi
+//!         .state(&state)
+//!         .and_then(|state| state.image(ByondDir::SOUTH, 0))
+//!         .and_then(|image| image.pixel(x, y));
+//!     match pixel {
+//!         Some([r, g, b, a]) => format!("#{r:02x}{g:02x}{b:02x}{a:02x}").to_byond(),
+//!         None => Ok(ByondValue::NULL),
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+mod bitmap;
+mod dmi;
+mod error;
+mod metadata;
+mod path;
+pub mod render;
+mod state;
+
+pub use crate::{bitmap::Bitmap, dmi::Dmi, error::DmiError, path::IconPath, state::IconState};
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: 0BSD
+//! The `Description` text stored in a DMI, which lists the size of each icon
+//! and every state in the order they appear in the image:
+//!
+//! ```text
+//! # BEGIN DMI
+//! version = 4.0
+//!     width = 32
+//!     height = 32
+//! state = "open"
+//!     dirs = 4
+//!     frames = 2
+//!     delay = 1,2
+//! # END DMI
+//! ```
+use crate::{DmiError, IconState};
+use std::{fmt::Write, str::FromStr};
+
+/// The parsed metadata. The states don't have any images yet, and their
+/// delays aren't padded to the number of frames until the images are read.
+pub(crate) struct Metadata {
+	pub width: u32,
+	pub height: u32,
+	pub states: Vec<IconState>,
+}
+
+pub(crate) fn parse(text: &str) -> Result<Metadata, DmiError> {
+	let mut lines = text
+		.lines()
+		.enumerate()
+		.map(|(idx, line)| (idx + 1, line.trim()));
+	if !lines.any(|(_, line)| line == "# BEGIN DMI") {
+		return Err(DmiError::MissingMetadata);
+	}
+	let mut metadata = Metadata {
+		width: 32,
+		height: 32,
+		states: Vec::new(),
+	};
+	for (line, text) in lines {
+		if text == "# END DMI" {
+			break;
+		} else if text.is_empty() || text.starts_with('#') {
+			continue;
+		}
+		let error = |message: String| DmiError::Metadata { line, message };
+		let Some((key, value)) = text.split_once('=') else {
+			return Err(error(format!("expected `key = value`, found `{text}`")));
+		};
+		let (key, value) = (key.trim(), value.trim());
+		match key {
+			"version" => {}
+			"width" => metadata.width = parse_number(value, line)?,
+			"height" => metadata.height = parse_number(value, line)?,
+			"state" => metadata.states.push(IconState {
+				name: parse_string(value, line)?,
+				dirs: 1,
+				frames: 1,
+				delays: Vec::new(),
+				loop_count: 0,
+				rewind: false,
+				movement: false,
+				extra: Vec::new(),
+				images: Vec::new(),
+			}),
+			_ => {
+				let Some(state) = metadata.states.last_mut() else {
+					return Err(error(format!("`{key}` before any state")));
+				};
+				match key {
+					"dirs" => {
+						state.dirs = parse_number(value, line)?;
+						if !matches!(state.dirs, 1 | 4 | 8) {
+							return Err(error(format!("invalid dirs `{value}`")));
+						}
+					}
+					"frames" => state.frames = parse_number::<usize>(value, line)?.max(1),
+					"delay" => {
+						state.delays = value
+							.split(',')
+							.map(|delay| parse_number(delay.trim(), line))
+							.collect::<Result<_, _>>()?
+					}
+					"loop" => state.loop_count = parse_number(value, line)?,
+					"rewind" => state.rewind = parse_number::<u32>(value, line)? != 0,
+					"movement" => state.movement = parse_number::<u32>(value, line)? != 0,
+					// Hotspots and anything newer aren't supported, so they're
+					// kept to be written back unchanged.
+					_ => state.extra.push((key.to_owned(), value.to_owned())),
+				}
+			}
+		}
+	}
+	Ok(metadata)
+}
+
+pub(crate) fn write(width: u32, height: u32, states: &[IconState]) -> String {
+	let mut out = String::new();
+	let _ = writeln!(out, "# BEGIN DMI\nversion = 4.0");
+	let _ = writeln!(out, "\twidth = {width}\n\theight = {height}");
+	for state in states {
+		let _ = writeln!(out, "state = \"{}\"", escape(&state.name));
+		let _ = writeln!(out, "\tdirs = {}\n\tframes = {}", state.dirs, state.frames);
+		if state.frames > 1 {
+			let delays = (0..state.frames)
+				.map(|frame| state.delays.get(frame).copied().unwrap_or(1.0).to_string())
+				.collect::<Vec<_>>()
+				.join(",");
+			let _ = writeln!(out, "\tdelay = {delays}");
+		}
+		if state.loop_count > 0 {
+			let _ = writeln!(out, "\tloop = {}", state.loop_count);
+		}
+		if state.rewind {
+			let _ = writeln!(out, "\trewind = 1");
+		}
+		if state.movement {
+			let _ = writeln!(out, "\tmovement = 1");
+		}
+		for (key, value) in &state.extra {
+			let _ = writeln!(out, "\t{key} = {value}");
+		}
+	}
+	out.push_str("# END DMI\n");
+	out
+}
+
+fn parse_number<Number>(value: &str, line: usize) -> Result<Number, DmiError>
+where
+	Number: FromStr,
+{
+	value.parse().map_err(|_| DmiError::Metadata {
+		line,
+		message: format!("invalid number `{value}`"),
+	})
+}
+
+fn parse_string(value: &str, line: usize) -> Result<String, DmiError> {
+	let Some(inner) = value
+		.strip_prefix('"')
+		.and_then(|value| value.strip_suffix('"'))
+	else {
+		return Err(DmiError::Metadata {
+			line,
+			message: format!("expected a quoted string, found `{value}`"),
+		});
+	};
+	let mut string = String::with_capacity(inner.len());
+	let mut chars = inner.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' => string.extend(chars.next()),
+			_ => string.push(c),
+		}
+	}
+	Ok(string)
+}
+
+fn escape(name: &str) -> String {
+	name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SAMPLE: &str = "# BEGIN DMI
+version = 4.0
+	width = 16
+	height = 24
+state = \"\"
+	dirs = 1
+	frames = 1
+state = \"door \\\"open\\\"\"
+	dirs = 4
+	frames = 3
+	delay = 1,0.5,2
+	loop = 1
+	rewind = 1
+	hotspot = 1,2,1
+state = \"walk\"
+	dirs = 8
+	frames = 2
+	movement = 1
+# END DMI
+";
+
+	#[test]
+	fn round_trip() {
+		let metadata = parse(SAMPLE).unwrap();
+		assert_eq!((metadata.width, metadata.height), (16, 24));
+		assert_eq!(metadata.states.len(), 3);
+
+		let door = &metadata.states[1];
+		assert_eq!(door.name, "door \"open\"");
+		assert_eq!((door.dirs, door.frames), (4, 3));
+		assert_eq!(door.delays, [1.0, 0.5, 2.0]);
+		assert_eq!(door.loop_count, 1);
+		assert!(door.rewind && !door.movement);
+		assert_eq!(door.extra, [("hotspot".to_owned(), "1,2,1".to_owned())]);
+
+		let walk = &metadata.states[2];
+		assert!(walk.delays.is_empty());
+		assert!(walk.movement);
+
+		let written = write(metadata.width, metadata.height, &metadata.states);
+		assert_eq!(
+			written,
+			SAMPLE.replace("frames = 2\n", "frames = 2\n\tdelay = 1,1\n")
+		);
+	}
+
+	#[test]
+	fn errors() {
+		let error = |text: &str| match parse(text) {
+			Err(DmiError::Metadata { line, .. }) => Some(line),
+			_ => None,
+		};
+		assert!(matches!(
+			parse("width = 32"),
+			Err(DmiError::MissingMetadata)
+		));
+		assert_eq!(error("# BEGIN DMI\ndirs = 4\n# END DMI"), Some(2));
+		assert_eq!(error("# BEGIN DMI\nstate = \"a\"\n\tdirs = 3"), Some(3));
+		assert_eq!(error("# BEGIN DMI\nstate = a"), Some(2));
+		assert_eq!(error("# BEGIN DMI\n\twidth = -1"), Some(2));
+	}
+}
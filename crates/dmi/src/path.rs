@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{Dmi, DmiError};
+use meowtonin::{ByondError, ByondResult, ByondValue, ByondValueType, FromByond};
+use std::{borrow::Cow, path::PathBuf};
+
+/// The path of an icon file, converted from a resource such as
+/// `'icons/obj/food.dmi'`, or a string containing a path.
+///
+/// Resources are relative to the directory of the `.dme`, which is usually
+/// the working directory of the server. Icons created at runtime with
+/// `icon()` aren't files, and fail to convert - save them with `fcopy()`
+/// first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IconPath(pub PathBuf);
+
+impl IconPath {
+	/// Reads and parses the icon file.
+	pub fn load(&self) -> Result<Dmi, DmiError> {
+		Dmi::from_file(&self.0)
+	}
+}
+
+impl FromByond for IconPath {
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		match value.get_type() {
+			ByondValueType::Resource | ByondValueType::String => {
+				PathBuf::from_byond(value).map(Self)
+			}
+			other => Err(ByondError::InvalidConversion {
+				expected: Cow::Borrowed("icon resource"),
+				got: other.name(),
+			}),
+		}
+	}
+}
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: 0BSD
+//! Compositing of appearances into a single image, like DM's `getFlatIcon`,
+//! but without creating any icons in BYOND.
+//!
+//! A [`Sprite`] is read from an atom on the main thread, then drawn by a
+//! [`Renderer`] on any thread. Overlays and underlays are sorted by layer,
+//! with `FLOAT_LAYER` (and other negative layers) placing them at their
+//! parent's layer, and inherit their parent's direction, offset, color and
+//! alpha, unless `RESET_COLOR` or `RESET_ALPHA` are set. Only the first frame
+//! of animated states is drawn, and `transform`, `blend_mode` and filters are
+//! ignored.
+//!
+//! ```no_run
+//! use meowtonin::{ByondError, ByondResult};
+//! use meowtonin_dmi::render::{Renderer, Sprite};
+//!
+//! #[meowtonin::byond_fn]
+//! fn flat_icon(sprite: Sprite, path: String) -> ByondResult<bool> {
+//!     let Some(bitmap) = Renderer::new().render(&sprite) else {
+//!         return Ok(false);
+//!     };
+//!     bitmap.save_png(&path).map_err(ByondError::boxed)?;
+//!     // DM can now use `icon(file(path))`, or send it to a client with
+//!     // `browse_rsc(file(path))`.
+//!     Ok(true)
+//! }
+//! # fn main() {}
+//! ```
+mod sprite;
+mod tint;
+
+pub use self::{
+	sprite::{RESET_ALPHA, RESET_COLOR, Sprite},
+	tint::Tint,
+};
+
+use crate::{Bitmap, Dmi, DmiError};
+use meowtonin::{ByondColor, ByondDir};
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+};
+
+/// Draws sprites, caching every icon file it reads.
+#[derive(Debug, Default)]
+pub struct Renderer {
+	icons: HashMap<PathBuf, Dmi>,
+	unreadable: HashMap<PathBuf, DmiError>,
+}
+
+/// A single image to draw, after flattening overlays and underlays.
+struct Draw<'a> {
+	/// Sorted by layer, then underlays/self/overlays for floating layers, then
+	/// the floating layer itself.
+	order: (f32, u8, f32),
+	sprite: &'a Sprite,
+	layer: f32,
+	dir: ByondDir,
+	tints: Vec<&'a Tint>,
+	alpha: f32,
+	offset: (i32, i32),
+}
+
+impl Renderer {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Renders a sprite to a new image, just big enough to hold every
+	/// overlay and underlay. Returns `None` if there's nothing to draw.
+	///
+	/// Icon files that can't be read are skipped, the same as missing states,
+	/// and can be checked with [`unreadable`](Self::unreadable).
+	pub fn render(&mut self, sprite: &Sprite) -> Option<Bitmap> {
+		let draws = self.prepare(sprite);
+		let mut bounds: Option<(i32, i32, i32, i32)> = None;
+		for (draw, image) in &draws {
+			let (left, bottom) = draw.offset;
+			let (right, top) = (left + image.width() as i32, bottom + image.height() as i32);
+			bounds = Some(match bounds {
+				Some((min_x, min_y, max_x, max_y)) => (
+					min_x.min(left),
+					min_y.min(bottom),
+					max_x.max(right),
+					max_y.max(top),
+				),
+				None => (left, bottom, right, top),
+			});
+		}
+		let (min_x, min_y, max_x, max_y) = bounds?;
+		let mut canvas = Bitmap::new((max_x - min_x) as u32, (max_y - min_y) as u32);
+		composite(&mut canvas, &draws, -min_x, max_y);
+		Some(canvas)
+	}
+
+	/// Draws a sprite onto an existing image, such as a map preview.
+	///
+	/// `(left, bottom)` is where the bottom-left corner of the sprite goes,
+	/// where `bottom` is the row just below it, so a 32x32 sprite at
+	/// `(0, 32)` covers the top-left corner of the canvas. Icon files that
+	/// can't be read are skipped, like in [`render`](Self::render).
+	pub fn draw(&mut self, canvas: &mut Bitmap, sprite: &Sprite, left: i32, bottom: i32) {
+		let draws = self.prepare(sprite);
+		composite(canvas, &draws, left, bottom);
+	}
+
+	/// Returns an icon file, reading it if it hasn't been read yet.
+	pub fn icon(&mut self, path: &Path) -> Result<&Dmi, DmiError> {
+		if !self.icons.contains_key(path) {
+			self.icons.insert(path.to_owned(), Dmi::from_file(path)?);
+		}
+		Ok(&self.icons[path])
+	}
+
+	/// Returns every icon file that couldn't be read while drawing, and why.
+	/// They aren't read again until the cache is cleared.
+	pub fn unreadable(&self) -> impl Iterator<Item = (&Path, &DmiError)> {
+		self.unreadable
+			.iter()
+			.map(|(path, error)| (path.as_path(), error))
+	}
+
+	/// Forgets every cached icon file, so they're read again if they've
+	/// changed.
+	pub fn clear_cache(&mut self) {
+		self.icons.clear();
+		self.unreadable.clear();
+	}
+
+	/// Flattens a sprite, reads every icon it uses, and pairs each draw with
+	/// the image it draws.
+	fn prepare<'a>(&mut self, sprite: &'a Sprite) -> Vec<(Draw<'a>, Bitmap)> {
+		let mut draws = Vec::new();
+		flatten(sprite, None, 1, &mut draws);
+		draws.sort_by(|a, b| {
+			let (a, b) = (a.order, b.order);
+			a.0.total_cmp(&b.0)
+				.then(a.1.cmp(&b.1))
+				.then(a.2.total_cmp(&b.2))
+		});
+		for path in draws.iter().filter_map(|draw| draw.sprite.icon.as_deref()) {
+			if self.unreadable.contains_key(path) {
+				continue;
+			}
+			if let Err(error) = self.icon(path) {
+				self.unreadable.insert(path.to_owned(), error);
+			}
+		}
+		let icons = &self.icons;
+		draws
+			.into_iter()
+			.filter_map(|draw| {
+				let dmi = icons.get(draw.sprite.icon.as_deref()?)?;
+				let state = dmi
+					.state(&draw.sprite.icon_state)
+					.or_else(|| dmi.state(""))?;
+				let mut image = state.image(draw.dir, 0)?.clone();
+				for tint in &draw.tints {
+					tint.apply(&mut image);
+				}
+				if draw.alpha < 1.0 {
+					Tint::Multiply(
+						ByondColor::WHITE.with_alpha((draw.alpha * 255.0).round() as u8),
+					)
+					.apply(&mut image);
+				}
+				Some((draw, image))
+			})
+			.collect()
+	}
+}
+
+/// Adds a sprite and everything on it to `draws`, with `group` being 0 for
+/// underlays, 1 for the root, and 2 for overlays.
+fn flatten<'a>(
+	sprite: &'a Sprite,
+	parent: Option<&Draw<'a>>,
+	group: u8,
+	draws: &mut Vec<Draw<'a>>,
+) {
+	let (order, layer) = match parent {
+		Some(parent) if sprite.layer < 0.0 => ((parent.layer, group, sprite.layer), parent.layer),
+		_ => ((sprite.layer, 1, 0.0), sprite.layer),
+	};
+	let mut tints = sprite.color.iter().collect::<Vec<_>>();
+	let mut alpha = f32::from(sprite.alpha) / 255.0;
+	let mut offset = (sprite.pixel_x, sprite.pixel_y);
+	let mut dir = sprite.dir;
+	if let Some(parent) = parent {
+		if sprite.appearance_flags & RESET_COLOR == 0 {
+			tints.extend(&parent.tints);
+		}
+		if sprite.appearance_flags & RESET_ALPHA == 0 {
+			alpha *= parent.alpha;
+		}
+		offset = (offset.0 + parent.offset.0, offset.1 + parent.offset.1);
+		dir = parent.dir;
+	}
+	let draw = Draw {
+		order,
+		sprite,
+		layer,
+		dir,
+		tints,
+		alpha,
+		offset,
+	};
+	for underlay in &sprite.underlays {
+		flatten(underlay, Some(&draw), 0, draws);
+	}
+	let idx = draws.len();
+	for overlay in &sprite.overlays {
+		flatten(overlay, Some(&draw), 2, draws);
+	}
+	draws.insert(idx, draw);
+}
+
+fn composite(canvas: &mut Bitmap, draws: &[(Draw, Bitmap)], left: i32, bottom: i32) {
+	for (draw, image) in draws {
+		let (x, y) = draw.offset;
+		canvas.blend(image, left + x, bottom - y - image.height() as i32);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::IconState;
+
+	fn solid(name: &str, rgba: [u8; 4]) -> IconState {
+		let mut state = IconState::new(name, 2, 2, 1, 1);
+		let image = state.image_mut(ByondDir::SOUTH, 0).unwrap();
+		for y in 0..2 {
+			for x in 0..2 {
+				image.set_pixel(x, y, rgba);
+			}
+		}
+		state
+	}
+
+	#[test]
+	fn layers_and_offsets() {
+		let path =
+			std::env::temp_dir().join(format!("meowtonin-render-{}.dmi", std::process::id()));
+		let mut dmi = Dmi::new(2, 2);
+		dmi.push_state(solid("red", [255, 0, 0, 255])).unwrap();
+		dmi.push_state(solid("green", [0, 255, 0, 255])).unwrap();
+		dmi.push_state(solid("white", [255, 255, 255, 255]))
+			.unwrap();
+		dmi.save(&path).unwrap();
+
+		let mut base = Sprite::new(&path, "red");
+		base.layer = 3.0;
+		base.color = Tint::parse("#0000ff");
+		// Floats above the base, and inherits its color, so it's blue.
+		let mut above = Sprite::new(&path, "white");
+		above.layer = -1.0;
+		above.pixel_x = 1;
+		base.overlays.push(above);
+		// Lower layer, so drawn below despite being an overlay.
+		let mut below = Sprite::new(&path, "green");
+		below.layer = 2.0;
+		below.pixel_y = 1;
+		below.appearance_flags = RESET_COLOR;
+		base.overlays.push(below);
+		// Unknown states fall back to the default state, which doesn't exist.
+		base.underlays.push(Sprite::new(&path, "missing"));
+		// Unreadable icons are skipped too.
+		let missing = path.with_extension("missing.dmi");
+		base.underlays.push(Sprite::new(&missing, "red"));
+
+		let mut renderer = Renderer::new();
+		let bitmap = renderer.render(&base).unwrap();
+		assert_eq!((bitmap.width(), bitmap.height()), (3, 3));
+		assert_eq!(bitmap.pixel(0, 0), Some([0, 255, 0, 255]));
+		assert_eq!(bitmap.pixel(0, 1), Some([0, 0, 0, 255]));
+		assert_eq!(bitmap.pixel(2, 2), Some([0, 0, 255, 255]));
+		assert_eq!(bitmap.pixel(2, 0), Some([0; 4]));
+
+		let mut canvas = Bitmap::new(4, 4);
+		renderer.draw(&mut canvas, &Sprite::new(&path, "green"), 2, 2);
+		assert_eq!(canvas.pixel(2, 0), Some([0, 255, 0, 255]));
+		assert_eq!(canvas.pixel(2, 2), Some([0; 4]));
+
+		assert!(renderer.render(&Sprite::default()).is_none());
+		let unreadable = renderer.unreadable().collect::<Vec<_>>();
+		assert_eq!(unreadable.len(), 1);
+		assert_eq!(unreadable[0].0, missing);
+		let _ = std::fs::remove_file(path);
+	}
+}
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{IconPath, render::Tint};
+use meowtonin::{ByondDir, ByondResult, ByondValue, FromByond};
+use std::path::PathBuf;
+
+/// The `RESET_COLOR` appearance flag.
+pub const RESET_COLOR: u32 = 1 << 1;
+/// The `RESET_ALPHA` appearance flag.
+pub const RESET_ALPHA: u32 = 1 << 2;
+
+/// The parts of an appearance needed to draw it: the vars of an atom, image,
+/// or appearance, along with its overlays and underlays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+	/// The path of the icon file, or `None` if there's no icon, or it was
+	/// created at runtime.
+	pub icon: Option<PathBuf>,
+	pub icon_state: String,
+	pub dir: ByondDir,
+	pub color: Option<Tint>,
+	pub alpha: u8,
+	/// The layer, where negative values such as `FLOAT_LAYER` are relative
+	/// to the parent's layer.
+	pub layer: f32,
+	/// The total horizontal offset, from both `pixel_x` and `pixel_w`.
+	pub pixel_x: i32,
+	/// The total vertical offset, from both `pixel_y` and `pixel_z`.
+	pub pixel_y: i32,
+	pub appearance_flags: u32,
+	pub overlays: Vec<Sprite>,
+	pub underlays: Vec<Sprite>,
+}
+
+impl Sprite {
+	/// Creates a sprite showing a state of an icon, facing south.
+	#[must_use]
+	pub fn new<IconFile, State>(icon: IconFile, icon_state: State) -> Self
+	where
+		IconFile: Into<PathBuf>,
+		State: Into<String>,
+	{
+		Self {
+			icon: Some(icon.into()),
+			icon_state: icon_state.into(),
+			..Self::default()
+		}
+	}
+}
+
+impl Default for Sprite {
+	fn default() -> Self {
+		Self {
+			icon: None,
+			icon_state: String::new(),
+			dir: ByondDir::SOUTH,
+			color: None,
+			alpha: 255,
+			layer: 0.0,
+			pixel_x: 0,
+			pixel_y: 0,
+			appearance_flags: 0,
+			overlays: Vec::new(),
+			underlays: Vec::new(),
+		}
+	}
+}
+
+/// Reads the appearance of an atom, image, or appearance, including every
+/// overlay and underlay.
+impl FromByond for Sprite {
+	fn from_byond(value: ByondValue) -> ByondResult<Self> {
+		let icon = value.read_var::<_, ByondValue>("icon")?;
+		let number = |name: &str| -> ByondResult<f32> {
+			Ok(value.read_var::<_, Option<f32>>(name)?.unwrap_or_default())
+		};
+		let sprites = |name: &str| -> ByondResult<Vec<Sprite>> {
+			value
+				.read_var::<_, Vec<ByondValue>>(name)?
+				.into_iter()
+				.map(Sprite::from_byond)
+				.collect()
+		};
+		Ok(Self {
+			icon: IconPath::from_byond(icon).ok().map(|path| path.0),
+			icon_state: value
+				.read_var::<_, Option<String>>("icon_state")?
+				.unwrap_or_default(),
+			dir: value.read_var("dir")?,
+			color: Tint::read(value.read_var("color")?)?,
+			alpha: number("alpha")?.clamp(0.0, 255.0) as u8,
+			layer: number("layer")?,
+			pixel_x: (number("pixel_x")? + number("pixel_w")?) as i32,
+			pixel_y: (number("pixel_y")? + number("pixel_z")?) as i32,
+			appearance_flags: number("appearance_flags")? as u32,
+			overlays: sprites("overlays")?,
+			underlays: sprites("underlays")?,
+		})
+	}
+}
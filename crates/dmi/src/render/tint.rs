@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: 0BSD
+use crate::Bitmap;
+use meowtonin::{ByondColor, ByondError, ByondResult, ByondValue, ColorMatrix, FromByond};
+
+/// An atom's `color` var.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tint {
+	/// A color such as `"#ff8000"` or `"#ff800080"`, which every pixel is
+	/// multiplied by.
+	Multiply(ByondColor),
+	/// A color matrix.
+	Matrix(ColorMatrix),
+}
+
+impl Tint {
+	/// Parses a color string in the `#RGB`, `#RGBA`, `#RRGGBB` or
+	/// `#RRGGBBAA` forms.
+	pub fn parse(color: &str) -> Option<Self> {
+		ByondColor::parse(color).map(Self::Multiply)
+	}
+
+	/// Converts a color matrix list with 9, 12, 16 or 20 numbers.
+	pub fn from_matrix(values: &[f32]) -> Option<Self> {
+		ColorMatrix::from_list(values).map(Self::Matrix)
+	}
+
+	/// Applies the tint to every pixel of an image.
+	pub fn apply(&self, bitmap: &mut Bitmap) {
+		for pixel in bitmap.as_rgba_mut().chunks_exact_mut(4) {
+			match self {
+				Self::Multiply(color) => {
+					for (channel, multiplier) in pixel.iter_mut().zip(<[u8; 4]>::from(*color)) {
+						*channel =
+							((u16::from(*channel) * u16::from(multiplier) + 127) / 255) as u8;
+					}
+				}
+				Self::Matrix(matrix) => {
+					let input = ByondColor::new(pixel[0], pixel[1], pixel[2], pixel[3]);
+					pixel.copy_from_slice(&<[u8; 4]>::from(matrix.apply(input)));
+				}
+			}
+		}
+	}
+
+	/// Converts a color string or color matrix list. Colors that can't be
+	/// parsed, such as names like `"red"`, convert to `None`.
+	pub(crate) fn read(value: ByondValue) -> ByondResult<Option<Self>> {
+		if value.is_string() {
+			Ok(Self::parse(&value.get_string()?))
+		} else if value.is_list() {
+			match ColorMatrix::from_byond(value) {
+				Ok(matrix) => Ok(Some(Self::Matrix(matrix))),
+				Err(ByondError::InvalidConversion { .. }) => Ok(None),
+				Err(err) => Err(err),
+			}
+		} else {
+			Ok(None)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_and_apply() {
+		assert_eq!(
+			Tint::parse("#f80"),
+			Some(Tint::Multiply(ByondColor::rgb(255, 136, 0)))
+		);
+		assert_eq!(
+			Tint::parse("#FF880080"),
+			Some(Tint::Multiply(ByondColor::new(255, 136, 0, 128)))
+		);
+		assert_eq!(Tint::parse("red"), None);
+		assert_eq!(Tint::parse("#12345"), None);
+
+		let mut bitmap = Bitmap::from_rgba(1, 1, vec![200, 100, 50, 255]).unwrap();
+		Tint::parse("#ff800080").unwrap().apply(&mut bitmap);
+		assert_eq!(bitmap.as_rgba(), [200, 50, 0, 128]);
+
+		// Swap red and blue, then add some green.
+		let matrix = [0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.2, 0.0];
+		let mut bitmap = Bitmap::from_rgba(1, 1, vec![200, 100, 50, 255]).unwrap();
+		Tint::from_matrix(&matrix).unwrap().apply(&mut bitmap);
+		assert_eq!(bitmap.as_rgba(), [50, 151, 200, 255]);
+	}
+}
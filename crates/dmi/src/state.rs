@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: 0BSD
+use crate::Bitmap;
+use meowtonin::ByondDir;
+
+/// The order directions are stored in within each frame of a state.
+const DIR_ORDER: [ByondDir; 8] = [
+	ByondDir::SOUTH,
+	ByondDir::NORTH,
+	ByondDir::EAST,
+	ByondDir::WEST,
+	ByondDir::SOUTHEAST,
+	ByondDir::SOUTHWEST,
+	ByondDir::NORTHEAST,
+	ByondDir::NORTHWEST,
+];
+
+/// A single icon state, with an image for every direction of every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconState {
+	pub(crate) name: String,
+	pub(crate) dirs: u8,
+	pub(crate) frames: usize,
+	pub(crate) delays: Vec<f32>,
+	pub(crate) loop_count: u32,
+	pub(crate) rewind: bool,
+	pub(crate) movement: bool,
+	pub(crate) extra: Vec<(String, String)>,
+	pub(crate) images: Vec<Bitmap>,
+}
+
+impl IconState {
+	/// Creates a state where every image is fully transparent, and every frame
+	/// lasts one tick.
+	///
+	/// `dirs` should be 1, 4 or 8, and is rounded up to the nearest of those.
+	/// `frames` is at least 1.
+	#[must_use]
+	pub fn new<Name>(name: Name, width: u32, height: u32, dirs: u8, frames: usize) -> Self
+	where
+		Name: Into<String>,
+	{
+		let dirs = match dirs {
+			0 | 1 => 1,
+			2..=4 => 4,
+			_ => 8,
+		};
+		let frames = frames.max(1);
+		Self {
+			name: name.into(),
+			dirs,
+			frames,
+			delays: vec![1.0; frames],
+			loop_count: 0,
+			rewind: false,
+			movement: false,
+			extra: Vec::new(),
+			images: vec![Bitmap::new(width, height); dirs as usize * frames],
+		}
+	}
+
+	/// Sets how long each frame lasts, in ticks. Frames without a delay last
+	/// one tick, and extra delays are ignored.
+	#[must_use]
+	pub fn delays<Delays>(mut self, delays: Delays) -> Self
+	where
+		Delays: IntoIterator<Item = f32>,
+	{
+		let mut delays = delays.into_iter();
+		for delay in &mut self.delays {
+			*delay = delays.next().unwrap_or(1.0);
+		}
+		self
+	}
+
+	/// Sets how many times the animation plays, where 0 means forever.
+	#[must_use]
+	pub fn loop_count(mut self, loop_count: u32) -> Self {
+		self.loop_count = loop_count;
+		self
+	}
+
+	/// Sets whether the animation plays backwards after playing forwards.
+	#[must_use]
+	pub fn rewind(mut self, rewind: bool) -> Self {
+		self.rewind = rewind;
+		self
+	}
+
+	/// Sets whether this is a movement state, which is shown while an atom
+	/// is gliding instead of the normal state with the same name.
+	#[must_use]
+	pub fn movement(mut self, movement: bool) -> Self {
+		self.movement = movement;
+		self
+	}
+
+	#[inline]
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Returns how many directions the state has: 1, 4 or 8.
+	#[inline]
+	pub fn dir_count(&self) -> u8 {
+		self.dirs
+	}
+
+	#[inline]
+	pub fn frame_count(&self) -> usize {
+		self.frames
+	}
+
+	/// Returns how long each frame lasts, in ticks.
+	#[inline]
+	pub fn frame_delays(&self) -> &[f32] {
+		&self.delays
+	}
+
+	/// Returns how many times the animation plays, where 0 means forever.
+	#[inline]
+	pub fn loops(&self) -> u32 {
+		self.loop_count
+	}
+
+	#[inline]
+	pub fn rewinds(&self) -> bool {
+		self.rewind
+	}
+
+	#[inline]
+	pub fn is_movement(&self) -> bool {
+		self.movement
+	}
+
+	/// Returns the metadata keys this crate doesn't understand, such as
+	/// `hotspot`, with their values as written. They're kept as-is when the
+	/// file is written back.
+	#[inline]
+	pub fn extra_keys(&self) -> &[(String, String)] {
+		&self.extra
+	}
+
+	/// Returns every image in the state, in the order they're stored in the
+	/// file: every direction of the first frame, then the second frame, and
+	/// so on.
+	#[inline]
+	pub fn images(&self) -> &[Bitmap] {
+		&self.images
+	}
+
+	/// Returns the image for a direction and frame, where the first frame is
+	/// 0.
+	///
+	/// Directions the state doesn't have fall back to the one BYOND would
+	/// show instead - diagonals use their east or west part in 4-direction
+	/// states, and everything uses south in 1-direction states.
+	pub fn image(&self, dir: ByondDir, frame: usize) -> Option<&Bitmap> {
+		self.images.get(self.index_of(dir, frame)?)
+	}
+
+	pub fn image_mut(&mut self, dir: ByondDir, frame: usize) -> Option<&mut Bitmap> {
+		let idx = self.index_of(dir, frame)?;
+		self.images.get_mut(idx)
+	}
+
+	fn index_of(&self, dir: ByondDir, frame: usize) -> Option<usize> {
+		if frame >= self.frames {
+			return None;
+		}
+		let dir = dir.horizontal();
+		let dir = match self.dirs {
+			1 => ByondDir::SOUTH,
+			4 if dir.is_diagonal() => dir & (ByondDir::EAST | ByondDir::WEST),
+			_ => dir,
+		};
+		let dir_idx = DIR_ORDER
+			.iter()
+			.position(|&other| other == dir)
+			.filter(|&idx| idx < self.dirs as usize)
+			.unwrap_or(0);
+		Some(frame * self.dirs as usize + dir_idx)
+	}
+}
@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: 0BSD
+#[derive(Debug, thiserror::Error)]
+pub enum DmmError {
+	#[error("line {line}: {message}")]
+	Parse { line: usize, message: String },
+	#[error("failed to read map: {0}")]
+	Io(#[from] std::io::Error),
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: 0BSD
+//! Parsing and loading of BYOND `.dmm` map files, in both the classic format
+//! and the TGM format used by most SS13 codebases.
+//!
+//! Parsing doesn't need BYOND at all, so maps can be parsed ahead of time, or
+//! on a worker thread. A parsed [`Dmm`] can then be instantiated anywhere on
+//! the map with a [`MapLoader`], which spreads the work across as many ticks
+//! as needed.
+//!
+//! Loading converts typepaths into actual path values, for var-less areas and
+//! for vars set to a path, which requires the `meowtonin_text2path` proc to be
+//! defined somewhere in your DM code, see [`meowtonin::typepath`]:
+//!
+//! ```dm
+//! /proc/meowtonin_text2path(text)
+//!     return text2path(text)
+//! ```
+//!
+//! ```no_run
+//! use meowtonin::ByondXYZ;
+//! use meowtonin_dmm::{Dmm, MapLoader};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dmm = Dmm::from_file("_maps/shuttles/emergency_box.dmm")?;
+//! let mut loader = MapLoader::new(dmm, ByondXYZ::new(100, 100, 2));
+//! while !loader.load(Duration::from_millis(10))? {
+//!     // ...wait for the next tick...
+//! }
+//! # Ok(())
+//! # }
+//! ```
+mod error;
+mod load;
+mod map;
+mod parse;
+mod value;
+
+pub use crate::{
+	error::DmmError,
+	load::MapLoader,
+	map::{Dmm, Format, Prefab, Tile},
+	value::DmmValue,
+};
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{Dmm, Prefab};
+use meowtonin::{ByondResult, ByondValue, ByondXYZ, ToByond, TypePath, misc};
+use std::time::{Duration, Instant};
+
+type ResourceLoader = Box<dyn Fn(&str) -> ByondResult<ByondValue>>;
+
+/// Instantiates a [`Dmm`] on the map, spreading the work across ticks.
+///
+/// For each tile, turfs are created first with `new path(locate(x, y, z))`,
+/// replacing the existing turf, then everything else on the tile. The tile is
+/// then added to its area's `contents`. Vars are set after each atom is
+/// created, so they're set after `New()` has already run.
+///
+/// Areas with the same type and vars are shared between tiles, and areas
+/// without any vars reuse the existing instance of their type, like areas
+/// placed in Dream Maker.
+///
+/// Finding those areas, and setting vars to typepaths, requires the
+/// `meowtonin_text2path` proc to be defined, see the
+/// [crate-level documentation](crate). Without it, loading fails on the first
+/// tile that needs it.
+pub struct MapLoader {
+	dmm: Dmm,
+	offset: ByondXYZ,
+	ignored: Vec<TypePath>,
+	resources: Option<ResourceLoader>,
+	areas: Vec<(Prefab, ByondValue)>,
+	next: usize,
+}
+
+impl MapLoader {
+	/// Creates a loader that places the bottom-left corner of the map at
+	/// `offset`. Use `(1, 1, 1)` to load the map at the same coordinates as
+	/// in the file.
+	#[must_use]
+	pub fn new(dmm: Dmm, offset: ByondXYZ) -> Self {
+		Self {
+			dmm,
+			offset,
+			ignored: Vec::new(),
+			resources: None,
+			areas: Vec::new(),
+			next: 0,
+		}
+	}
+
+	/// Skips every prefab of the given type (not including subtypes), such as
+	/// `/turf/template_noop`, leaving whatever is already there.
+	#[must_use]
+	pub fn ignore(mut self, path: TypePath) -> Self {
+		self.ignored.push(path);
+		self
+	}
+
+	/// Sets how resource files, like `'icons/obj/thing.dmi'`, are converted
+	/// when setting vars.
+	///
+	/// By default, they're converted to their path as a string. As BYOND has
+	/// no way to call `file()` directly, this is usually a call to a global
+	/// proc that does it instead.
+	#[must_use]
+	pub fn resources<Func>(mut self, resources: Func) -> Self
+	where
+		Func: Fn(&str) -> ByondResult<ByondValue> + 'static,
+	{
+		self.resources = Some(Box::new(resources));
+		self
+	}
+
+	/// Returns the map being loaded.
+	#[inline]
+	pub fn dmm(&self) -> &Dmm {
+		&self.dmm
+	}
+
+	/// Returns how many tiles haven't been loaded yet.
+	pub fn remaining(&self) -> usize {
+		self.dmm.grid.len() - self.next
+	}
+
+	/// Loads tiles until the whole map has been loaded, or `budget` runs out,
+	/// returning `true` once the whole map has been loaded.
+	///
+	/// At least one tile is always loaded. Tiles that would be outside of the
+	/// world are skipped. If a tile fails to load, the error is returned and
+	/// the rest of that tile is skipped.
+	///
+	/// This must be called from the main thread.
+	pub fn load(&mut self, budget: Duration) -> ByondResult<bool> {
+		let started = Instant::now();
+		let start = self.next;
+		while self.next < self.dmm.grid.len() {
+			if self.next > start && started.elapsed() >= budget {
+				return Ok(false);
+			}
+			let idx = self.next;
+			self.next += 1;
+			self.load_tile(idx)?;
+		}
+		Ok(true)
+	}
+
+	fn load_tile(&mut self, idx: usize) -> ByondResult<()> {
+		let Some(xyz) = self.dmm.grid.block().xyz_at(idx) else {
+			return Ok(());
+		};
+		let target = ByondXYZ::new(
+			self.offset.x() + xyz.x() - 1,
+			self.offset.y() + xyz.y() - 1,
+			self.offset.z() + xyz.z() - 1,
+		);
+		let mut turf = misc::locate_xyz(target)?;
+		if turf.is_null() {
+			return Ok(());
+		}
+		let resources = self.resources.as_ref();
+		let tile = &self.dmm.tiles[self.dmm.grid.as_slice()[idx]];
+		let prefabs = tile
+			.prefabs
+			.iter()
+			.filter(|prefab| !self.ignored.contains(&prefab.path))
+			.collect::<Vec<_>>();
+		let is_turf = |prefab: &Prefab| prefab.path.components().next() == Some("turf");
+		for prefab in prefabs.iter().filter(|prefab| is_turf(prefab)) {
+			turf = create(prefab, Some(&turf), resources)?;
+		}
+		for prefab in &prefabs {
+			match prefab.path.components().next() {
+				Some("turf") => {}
+				Some("area") => {
+					let area = area(&mut self.areas, prefab, resources)?;
+					area.read_var::<_, ByondValue>("contents")?
+						.call::<_, _, _, ()>("Add", [&turf])?;
+				}
+				_ => {
+					let _ = create(prefab, Some(&turf), resources)?;
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns the area for a prefab, creating it if needed.
+fn area(
+	areas: &mut Vec<(Prefab, ByondValue)>,
+	prefab: &Prefab,
+	resources: Option<&ResourceLoader>,
+) -> ByondResult<ByondValue> {
+	if let Some((_, area)) = areas.iter().find(|(other, _)| other == prefab) {
+		return Ok(area.clone());
+	}
+	let existing = if prefab.vars.is_empty() {
+		misc::locate(prefab.path.to_byond()?, None)?
+	} else {
+		ByondValue::NULL
+	};
+	let area = if existing.is_null() {
+		create(prefab, None, resources)?
+	} else {
+		existing
+	};
+	areas.push((prefab.clone(), area.clone()));
+	Ok(area)
+}
+
+fn create(
+	prefab: &Prefab,
+	loc: Option<&ByondValue>,
+	resources: Option<&ResourceLoader>,
+) -> ByondResult<ByondValue> {
+	let args = loc.into_iter().cloned().collect::<Vec<_>>();
+	let mut atom = ByondValue::new(prefab.path.as_str(), args)?;
+	for (name, value) in &prefab.vars {
+		let value = match resources {
+			Some(resources) => value.to_byond_with(resources)?,
+			None => value.to_byond()?,
+		};
+		atom.write_var(name, value)?;
+	}
+	Ok(atom)
+}
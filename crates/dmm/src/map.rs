@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{DmmError, DmmValue};
+use meowtonin::{ByondBlock, ByondXYZ, TypePath, grid::Grid};
+use std::path::Path;
+
+/// Which format a map file was written in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+	/// The format written by Dream Maker, with each tile on one line.
+	#[default]
+	Classic,
+	/// The TGM format, which puts each prefab and var on its own line, and
+	/// each column of the map in its own block, so diffs are easier to read.
+	Tgm,
+}
+
+/// A single atom in a tile, with any vars that differ from its type's
+/// defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prefab {
+	/// The type of the atom.
+	pub path: TypePath,
+	/// The overridden vars, in the order they appear in the map.
+	pub vars: Vec<(String, DmmValue)>,
+}
+
+impl Prefab {
+	/// Returns the value of an overridden var.
+	pub fn var(&self, name: &str) -> Option<&DmmValue> {
+		self.vars
+			.iter()
+			.find_map(|(var, value)| (var == name).then_some(value))
+	}
+}
+
+/// An entry in a map's dictionary - a key, and the prefabs placed on every
+/// tile with that key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tile {
+	/// The key used for this tile in the map's grid, such as `"aJq"`.
+	pub key: String,
+	/// The atoms on this tile. By convention, movables come first, then the
+	/// turf, then the area.
+	pub prefabs: Vec<Prefab>,
+}
+
+/// A parsed map file.
+///
+/// Coordinates within the map start at `(1, 1, 1)` in the bottom-left of the
+/// first z-level, matching the coordinates in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dmm {
+	pub(crate) format: Format,
+	pub(crate) key_length: usize,
+	pub(crate) tiles: Vec<Tile>,
+	pub(crate) grid: Grid<usize>,
+}
+
+impl Dmm {
+	/// Parses a map from a string.
+	pub fn parse(source: &str) -> Result<Self, DmmError> {
+		crate::parse::parse(source)
+	}
+
+	/// Reads and parses a map file.
+	pub fn from_file<FilePath>(path: FilePath) -> Result<Self, DmmError>
+	where
+		FilePath: AsRef<Path>,
+	{
+		Self::parse(&std::fs::read_to_string(path)?)
+	}
+
+	/// Returns which format the map was written in.
+	#[inline]
+	pub fn format(&self) -> Format {
+		self.format
+	}
+
+	/// Returns the length of each key, in characters.
+	#[inline]
+	pub fn key_length(&self) -> usize {
+		self.key_length
+	}
+
+	/// Returns every entry in the map's dictionary, in the order they appear
+	/// in the file.
+	#[inline]
+	pub fn tiles(&self) -> &[Tile] {
+		&self.tiles
+	}
+
+	/// Returns the index into [`tiles`](Self::tiles) for every tile on the
+	/// map.
+	#[inline]
+	pub fn grid(&self) -> &Grid<usize> {
+		&self.grid
+	}
+
+	/// Returns the block covered by the map, from `(1, 1, 1)` to its size.
+	#[inline]
+	pub fn block(&self) -> ByondBlock {
+		self.grid.block()
+	}
+
+	/// Returns the size of the map, as the coordinates of its top-right
+	/// corner on the last z-level.
+	#[inline]
+	pub fn size(&self) -> ByondXYZ {
+		self.grid.block().max()
+	}
+
+	/// Returns the tile at the given coordinates.
+	pub fn tile_at(&self, xyz: ByondXYZ) -> Option<&Tile> {
+		self.grid.get(xyz).map(|&idx| &self.tiles[idx])
+	}
+
+	/// Returns the prefabs at the given coordinates.
+	pub fn prefabs_at(&self, xyz: ByondXYZ) -> Option<&[Prefab]> {
+		self.tile_at(xyz).map(|tile| tile.prefabs.as_slice())
+	}
+
+	/// Iterates over every tile on the map, along with its coordinates.
+	pub fn iter(&self) -> impl ExactSizeIterator<Item = (ByondXYZ, &Tile)> + '_ {
+		self.grid.iter().map(|(xyz, &idx)| (xyz, &self.tiles[idx]))
+	}
+}
@@ -0,0 +1,586 @@
+// SPDX-License-Identifier: 0BSD
+use crate::{Dmm, DmmError, DmmValue, Format, Prefab, Tile};
+use meowtonin::{ByondBlock, ByondXYZ, TypePath, grid::Grid};
+use std::collections::HashMap;
+
+const TGM_HEADER: &str = "//MAP CONVERTED BY dmm2tgm.py";
+
+pub(crate) fn parse(source: &str) -> Result<Dmm, DmmError> {
+	let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+	let format = if source.starts_with(TGM_HEADER) {
+		Format::Tgm
+	} else {
+		Format::Classic
+	};
+	let mut parser = Parser {
+		source,
+		pos: 0,
+		line: 1,
+	};
+	let mut tiles = Vec::new();
+	let mut blocks = Vec::new();
+	loop {
+		parser.skip_whitespace();
+		match parser.peek() {
+			None => break,
+			Some(b'"') => tiles.push(parser.tile()?),
+			Some(b'(') => blocks.push(parser.grid_block()?),
+			Some(_) => return Err(parser.error("expected a key or a grid block")),
+		}
+	}
+	build(format, tiles, blocks)
+}
+
+/// A block of the grid, such as `(1,1,1) = {"..."}`.
+struct GridBlock<'a> {
+	origin: ByondXYZ,
+	line: usize,
+	content: &'a str,
+}
+
+struct Parser<'a> {
+	source: &'a str,
+	pos: usize,
+	line: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn error(&self, message: impl Into<String>) -> DmmError {
+		DmmError::Parse {
+			line: self.line,
+			message: message.into(),
+		}
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.source.as_bytes().get(self.pos).copied()
+	}
+
+	fn bump(&mut self) -> Option<u8> {
+		let byte = self.peek()?;
+		self.pos += 1;
+		if byte == b'\n' {
+			self.line += 1;
+		}
+		Some(byte)
+	}
+
+	fn rest(&self) -> &'a str {
+		&self.source[self.pos..]
+	}
+
+	fn expect(&mut self, expected: u8) -> Result<(), DmmError> {
+		if self.peek() == Some(expected) {
+			self.bump();
+			Ok(())
+		} else {
+			Err(self.error(format!("expected `{}`", expected as char)))
+		}
+	}
+
+	/// Skips whitespace, along with `//` and `/* */` comments.
+	fn skip_whitespace(&mut self) {
+		loop {
+			let rest = self.rest();
+			if rest.starts_with("//") {
+				while self.peek().is_some_and(|byte| byte != b'\n') {
+					self.bump();
+				}
+			} else if rest.starts_with("/*") {
+				// Compare bytes, as the comment may contain multi-byte characters.
+				while self.peek().is_some()
+					&& !self.source.as_bytes()[self.pos..].starts_with(b"*/")
+				{
+					self.bump();
+				}
+				self.pos = (self.pos + 2).min(self.source.len());
+			} else if self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+				self.bump();
+			} else {
+				break;
+			}
+		}
+	}
+
+	/// Reads bytes while `predicate` matches, returning them.
+	fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a str {
+		let start = self.pos;
+		while self.peek().is_some_and(&predicate) {
+			self.bump();
+		}
+		&self.source[start..self.pos]
+	}
+
+	/// Parses a dictionary entry, such as `"aa" = (/turf/open,/area/space)`.
+	fn tile(&mut self) -> Result<Tile, DmmError> {
+		self.expect(b'"')?;
+		let key = self.take_while(|byte| byte != b'"' && byte != b'\n');
+		self.expect(b'"')?;
+		self.skip_whitespace();
+		self.expect(b'=')?;
+		self.skip_whitespace();
+		self.expect(b'(')?;
+		let mut prefabs = Vec::new();
+		loop {
+			self.skip_whitespace();
+			if self.peek() == Some(b')') {
+				self.bump();
+				break;
+			}
+			prefabs.push(self.prefab()?);
+			self.skip_whitespace();
+			match self.bump() {
+				Some(b',') => continue,
+				Some(b')') => break,
+				_ => return Err(self.error("expected `,` or `)` after prefab")),
+			}
+		}
+		Ok(Tile {
+			key: key.to_owned(),
+			prefabs,
+		})
+	}
+
+	/// Parses a prefab, such as `/obj/item{name = "thing"; dir = 4}`.
+	fn prefab(&mut self) -> Result<Prefab, DmmError> {
+		let path = self.path()?;
+		let mut vars = Vec::new();
+		self.skip_whitespace();
+		if self.peek() == Some(b'{') {
+			self.bump();
+			loop {
+				self.skip_whitespace();
+				if self.peek() == Some(b'}') {
+					self.bump();
+					break;
+				}
+				let name = self.ident()?;
+				self.skip_whitespace();
+				self.expect(b'=')?;
+				self.skip_whitespace();
+				vars.push((name.to_owned(), self.value()?));
+				self.skip_whitespace();
+				match self.bump() {
+					Some(b';') => continue,
+					Some(b'}') => break,
+					_ => return Err(self.error("expected `;` or `}` after var")),
+				}
+			}
+		}
+		Ok(Prefab { path, vars })
+	}
+
+	fn path(&mut self) -> Result<TypePath, DmmError> {
+		if self.peek() != Some(b'/') {
+			return Err(self.error("expected a typepath"));
+		}
+		let path =
+			self.take_while(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'/');
+		TypePath::new(path).map_err(|_| self.error(format!("invalid typepath `{path}`")))
+	}
+
+	fn ident(&mut self) -> Result<&'a str, DmmError> {
+		if !self
+			.peek()
+			.is_some_and(|byte| byte.is_ascii_alphabetic() || byte == b'_')
+		{
+			return Err(self.error("expected an identifier"));
+		}
+		Ok(self.take_while(|byte| byte.is_ascii_alphanumeric() || byte == b'_'))
+	}
+
+	fn value(&mut self) -> Result<DmmValue, DmmError> {
+		match self.peek() {
+			Some(b'"') => self.string().map(DmmValue::String),
+			Some(b'\'') => {
+				self.bump();
+				let path = self.take_while(|byte| byte != b'\'' && byte != b'\n');
+				self.expect(b'\'')?;
+				Ok(DmmValue::Resource(path.to_owned()))
+			}
+			Some(b'/') => self.path().map(DmmValue::Path),
+			Some(byte) if byte == b'-' || byte == b'.' || byte.is_ascii_digit() => self.number(),
+			Some(_) => match self.ident()? {
+				"null" => Ok(DmmValue::Null),
+				"list" => self.list(),
+				other => Err(self.error(format!("unsupported value `{other}`"))),
+			},
+			None => Err(self.error("expected a value")),
+		}
+	}
+
+	fn string(&mut self) -> Result<String, DmmError> {
+		self.expect(b'"')?;
+		let mut string = String::new();
+		let mut span = self.pos;
+		loop {
+			match self.peek() {
+				None => return Err(self.error("unterminated string")),
+				Some(b'"') => {
+					string.push_str(&self.source[span..self.pos]);
+					self.bump();
+					return Ok(string);
+				}
+				Some(b'\\') => {
+					string.push_str(&self.source[span..self.pos]);
+					self.bump();
+					let escaped = match self.peek() {
+						Some(b'"') => Some('"'),
+						Some(b'\\') => Some('\\'),
+						Some(b'n') => Some('\n'),
+						Some(b't') => Some('\t'),
+						// Keep anything else, such as `\improper`, as-is.
+						_ => None,
+					};
+					match escaped {
+						Some(escaped) => {
+							string.push(escaped);
+							self.bump();
+						}
+						None => string.push('\\'),
+					}
+					span = self.pos;
+				}
+				Some(_) => {
+					self.bump();
+				}
+			}
+		}
+	}
+
+	fn number(&mut self) -> Result<DmmValue, DmmError> {
+		let start = self.pos;
+		if self.peek() == Some(b'-') {
+			self.bump();
+		}
+		self.take_while(|byte| byte.is_ascii_digit() || byte == b'.');
+		if self.peek().is_some_and(|byte| byte == b'e' || byte == b'E') {
+			self.bump();
+			if self.peek().is_some_and(|byte| byte == b'+' || byte == b'-') {
+				self.bump();
+			}
+			self.take_while(|byte| byte.is_ascii_digit());
+		}
+		let text = &self.source[start..self.pos];
+		let number = if self.rest().starts_with("#INF") {
+			self.pos += 4;
+			f32::INFINITY.copysign(if text.starts_with('-') { -1.0 } else { 1.0 })
+		} else if self.rest().starts_with("#IND") {
+			self.pos += 4;
+			f32::NAN
+		} else {
+			text.parse()
+				.map_err(|_| self.error(format!("invalid number `{text}`")))?
+		};
+		Ok(DmmValue::Number(number))
+	}
+
+	/// Parses the rest of a list, after the `list`.
+	fn list(&mut self) -> Result<DmmValue, DmmError> {
+		self.skip_whitespace();
+		self.expect(b'(')?;
+		let mut entries = Vec::new();
+		loop {
+			self.skip_whitespace();
+			if self.peek() == Some(b')') {
+				self.bump();
+				break;
+			}
+			let key = self.list_key()?;
+			self.skip_whitespace();
+			let value = if self.peek() == Some(b'=') {
+				self.bump();
+				self.skip_whitespace();
+				Some(self.value()?)
+			} else {
+				None
+			};
+			entries.push((key, value));
+			self.skip_whitespace();
+			match self.bump() {
+				Some(b',') => continue,
+				Some(b')') => break,
+				_ => return Err(self.error("expected `,` or `)` in list")),
+			}
+		}
+		Ok(DmmValue::List(entries))
+	}
+
+	/// Parses a list key, which may be a bare identifier, as in
+	/// `list(a = 1)`.
+	fn list_key(&mut self) -> Result<DmmValue, DmmError> {
+		let (pos, line) = (self.pos, self.line);
+		if self.peek().is_some_and(|byte| byte.is_ascii_alphabetic()) {
+			let ident = self.ident()?;
+			if !matches!(ident, "null" | "list") {
+				return Ok(DmmValue::String(ident.to_owned()));
+			}
+			(self.pos, self.line) = (pos, line);
+		}
+		self.value()
+	}
+
+	fn int(&mut self) -> Result<i16, DmmError> {
+		self.skip_whitespace();
+		let digits = self.take_while(|byte| byte.is_ascii_digit());
+		digits
+			.parse()
+			.map_err(|_| self.error("expected a coordinate"))
+	}
+
+	/// Parses a block of the grid, such as `(1,1,1) = {"...."}`.
+	fn grid_block(&mut self) -> Result<GridBlock<'a>, DmmError> {
+		self.expect(b'(')?;
+		let x = self.int()?;
+		self.skip_whitespace();
+		self.expect(b',')?;
+		let y = self.int()?;
+		self.skip_whitespace();
+		self.expect(b',')?;
+		let z = self.int()?;
+		self.skip_whitespace();
+		self.expect(b')')?;
+		self.skip_whitespace();
+		self.expect(b'=')?;
+		self.skip_whitespace();
+		self.expect(b'{')?;
+		self.expect(b'"')?;
+		let line = self.line;
+		let Some(length) = self.rest().find("\"}") else {
+			return Err(self.error("unterminated grid block"));
+		};
+		let content = &self.rest()[..length];
+		let end = self.pos + length + 2;
+		while self.pos < end {
+			self.bump();
+		}
+		Ok(GridBlock {
+			origin: ByondXYZ::new(x, y, z),
+			line,
+			content,
+		})
+	}
+}
+
+fn build(format: Format, tiles: Vec<Tile>, blocks: Vec<GridBlock>) -> Result<Dmm, DmmError> {
+	let error = |line: usize, message: String| DmmError::Parse { line, message };
+	let Some(key_length) = tiles.first().map(|tile| tile.key.len()) else {
+		return Err(error(1, "map has no keys".to_owned()));
+	};
+	if key_length == 0 {
+		return Err(error(1, "map has an empty key".to_owned()));
+	}
+	let mut keys = HashMap::with_capacity(tiles.len());
+	for (idx, tile) in tiles.iter().enumerate() {
+		if tile.key.len() != key_length {
+			return Err(error(1, format!("key `{}` has the wrong length", tile.key)));
+		}
+		if keys.insert(tile.key.as_str(), idx).is_some() {
+			return Err(error(1, format!("key `{}` is defined twice", tile.key)));
+		}
+	}
+
+	let mut placed = Vec::new();
+	let mut max = ByondXYZ::new(1, 1, 1);
+	for block in &blocks {
+		let mut rows = block.content.split('\n').enumerate().collect::<Vec<_>>();
+		if rows
+			.first()
+			.is_some_and(|(_, row)| row.trim_end().is_empty())
+		{
+			rows.remove(0);
+		}
+		if rows
+			.last()
+			.is_some_and(|(_, row)| row.trim_end().is_empty())
+		{
+			rows.pop();
+		}
+		let height = rows.len() as i16;
+		for (row_idx, (line_offset, row)) in rows.into_iter().enumerate() {
+			let line = block.line + line_offset;
+			let row = row.trim_end_matches('\r');
+			if row.len() % key_length != 0 {
+				return Err(error(
+					line,
+					"row length isn't a multiple of the key length".to_owned(),
+				));
+			}
+			let y = block.origin.y() + height - 1 - row_idx as i16;
+			for (column, start) in (0..row.len()).step_by(key_length).enumerate() {
+				let key = row
+					.get(start..start + key_length)
+					.ok_or_else(|| error(line, "invalid key in grid".to_owned()))?;
+				let idx = *keys
+					.get(key)
+					.ok_or_else(|| error(line, format!("unknown key `{key}`")))?;
+				let xyz = ByondXYZ::new(block.origin.x() + column as i16, y, block.origin.z());
+				if xyz.x() < 1 || xyz.y() < 1 || xyz.z() < 1 {
+					return Err(error(line, format!("tile at {xyz:?} is out of bounds")));
+				}
+				max = ByondXYZ::new(
+					max.x().max(xyz.x()),
+					max.y().max(xyz.y()),
+					max.z().max(xyz.z()),
+				);
+				placed.push((xyz, idx));
+			}
+		}
+	}
+
+	let block = ByondBlock::new(ByondXYZ::new(1, 1, 1), max);
+	let mut grid = Grid::new(block, None);
+	for (xyz, idx) in placed {
+		grid.set(xyz, Some(idx));
+	}
+	let mut cells = Vec::with_capacity(block.len());
+	for (xyz, idx) in grid.iter() {
+		let idx =
+			idx.ok_or_else(|| error(1, format!("tile at {xyz:?} is missing from the grid")))?;
+		cells.push(idx);
+	}
+	Ok(Dmm {
+		format,
+		key_length,
+		tiles,
+		grid: Grid::from_vec(block, cells).expect("grid has one cell per tile"),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CLASSIC: &str = r#""a" = (/turf/open/floor{icon_state = "dark"; dir = 4},/area/station/kitchen)
+"b" = (/obj/item/knife{name = "Knife \"Sharp\""},/turf/open/floor,/area/station/kitchen)
+"c" = (/turf/closed/wall,/area/space)
+
+(1,1,1) = {"
+ccc
+cab
+cba
+"}
+"#;
+
+	const TGM: &str = r#"//MAP CONVERTED BY dmm2tgm.py THIS HEADER COMMENT PREVENTS RECONVERSION, DO NOT REMOVE
+"a" = (
+/turf/open/floor{
+	icon_state = "dark";
+	dir = 4
+	},
+/area/station/kitchen)
+"b" = (
+/obj/item/knife{
+	name = "Knife \"Sharp\""
+	},
+/turf/open/floor,
+/area/station/kitchen)
+"c" = (
+/turf/closed/wall,
+/area/space)
+
+(1,1,1) = {"
+c
+c
+c
+"}
+(2,1,1) = {"
+c
+a
+b
+"}
+(3,1,1) = {"
+c
+b
+a
+"}
+"#;
+
+	#[test]
+	fn classic_and_tgm_match() {
+		let classic = Dmm::parse(CLASSIC).unwrap();
+		let tgm = Dmm::parse(TGM).unwrap();
+		assert_eq!(classic.format(), Format::Classic);
+		assert_eq!(tgm.format(), Format::Tgm);
+		assert_eq!(classic.tiles(), tgm.tiles());
+		assert_eq!(classic.grid(), tgm.grid());
+		assert_eq!(classic.size(), ByondXYZ::new(3, 3, 1));
+
+		let tile = classic.tile_at(ByondXYZ::new(2, 2, 1)).unwrap();
+		assert_eq!(tile.key, "a");
+		assert_eq!(tile.prefabs[0].path.as_str(), "/turf/open/floor");
+		assert_eq!(tile.prefabs[0].var("dir"), Some(&DmmValue::Number(4.0)));
+		let knife = &classic.prefabs_at(ByondXYZ::new(3, 2, 1)).unwrap()[0];
+		assert_eq!(
+			knife.var("name"),
+			Some(&DmmValue::String("Knife \"Sharp\"".to_owned()))
+		);
+		assert_eq!(classic.tile_at(ByondXYZ::new(1, 3, 1)).unwrap().key, "c");
+		assert_eq!(classic.tile_at(ByondXYZ::new(3, 1, 1)).unwrap().key, "a");
+	}
+
+	#[test]
+	fn values() {
+		let dmm = Dmm::parse(
+			r#"/* generated */
+"aa" = (/obj/thing{a = -1.5e+002; b = 'icons/obj/thing.dmi'; c = null; d = list(1, "x" = /obj/other, y = list()); e = 1.#INF; f = "\improper Thing\n"},/turf/space,/area/space)
+(1,1,1) = {"
+aaaa
+"}"#,
+		)
+		.unwrap();
+		assert_eq!(dmm.key_length(), 2);
+		assert_eq!(dmm.size(), ByondXYZ::new(2, 1, 1));
+		let thing = &dmm.tiles()[0].prefabs[0];
+		assert_eq!(thing.var("a"), Some(&DmmValue::Number(-150.0)));
+		assert_eq!(
+			thing.var("b"),
+			Some(&DmmValue::Resource("icons/obj/thing.dmi".to_owned()))
+		);
+		assert_eq!(thing.var("c"), Some(&DmmValue::Null));
+		assert_eq!(
+			thing.var("d"),
+			Some(&DmmValue::List(vec![
+				(DmmValue::Number(1.0), None),
+				(
+					DmmValue::String("x".to_owned()),
+					Some(DmmValue::Path(TypePath::new("/obj/other").unwrap()))
+				),
+				(
+					DmmValue::String("y".to_owned()),
+					Some(DmmValue::List(Vec::new()))
+				),
+			]))
+		);
+		assert_eq!(thing.var("e"), Some(&DmmValue::Number(f32::INFINITY)));
+		assert_eq!(
+			thing.var("f"),
+			Some(&DmmValue::String("\\improper Thing\n".to_owned()))
+		);
+	}
+
+	#[test]
+	fn errors() {
+		let line = |source: &str| match Dmm::parse(source) {
+			Err(DmmError::Parse { line, .. }) => line,
+			other => panic!("expected a parse error, got {other:?}"),
+		};
+		assert_eq!(
+			line("\"a\" = (/turf{x = 1},/area)\n(1,1,1) = {\"\nb\n\"}"),
+			3
+		);
+		assert_eq!(line("\"a\" = (/turf{x = what},/area)"), 1);
+		assert_eq!(line("\"a\" = (/turf,\n/area{name = \"oops}\n)"), 3);
+		assert_eq!(line("\"a\" = (/turf,/area)\n(2,1,1) = {\"\na\n\"}"), 1);
+	}
+
+	#[test]
+	fn non_ascii_comments() {
+		let dmm = Dmm::parse("/* café */\n\"a\" = (/turf,/area)\n(1,1,1) = {\"\na\n\"}").unwrap();
+		assert_eq!(dmm.size(), ByondXYZ::new(1, 1, 1));
+		assert!(matches!(
+			Dmm::parse("/* ☕\n */\n\"a\" = (/turf{x = what},/area)"),
+			Err(DmmError::Parse { line: 3, .. })
+		));
+	}
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: 0BSD
+use meowtonin::{ByondResult, ByondValue, ToByond, TypePath};
+
+/// A var override in a map file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DmmValue {
+	/// `null`.
+	Null,
+	/// A number, such as `4` or `-0.5`.
+	Number(f32),
+	/// A string, such as `"Kitchen"`, with escapes already resolved.
+	String(String),
+	/// A resource file, such as `'icons/turf/floors.dmi'`.
+	Resource(String),
+	/// A typepath, such as `/obj/item/food`.
+	Path(TypePath),
+	/// A list, such as `list(1, "a" = 2)`, where each entry may have an
+	/// associated value.
+	List(Vec<(DmmValue, Option<DmmValue>)>),
+}
+
+impl DmmValue {
+	/// Converts the value, calling `resource` to convert resource files.
+	pub(crate) fn to_byond_with<Func>(&self, resource: &Func) -> ByondResult<ByondValue>
+	where
+		Func: Fn(&str) -> ByondResult<ByondValue>,
+	{
+		match self {
+			Self::Null => Ok(ByondValue::NULL),
+			Self::Number(number) => number.to_byond(),
+			Self::String(string) => string.to_byond(),
+			Self::Resource(path) => resource(path),
+			Self::Path(path) => path.to_byond(),
+			Self::List(entries) => {
+				let mut list = ByondValue::new_list()?;
+				for (key, value) in entries {
+					let key = key.to_byond_with(resource)?;
+					match value {
+						Some(value) => {
+							list.write_list_index(key, value.to_byond_with(resource)?)?
+						}
+						None => list.push_list(key)?,
+					}
+				}
+				Ok(list)
+			}
+		}
+	}
+}
+
+/// Resource files are converted to their path as a string. Use
+/// [`MapLoader::resources`](crate::MapLoader::resources) to convert them to
+/// actual resources.
+impl ToByond for DmmValue {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		self.to_byond_with(&|path: &str| path.to_byond())
+	}
+}
+
+impl ToByond for &DmmValue {
+	fn to_byond(&self) -> ByondResult<ByondValue> {
+		(*self).to_byond()
+	}
+}
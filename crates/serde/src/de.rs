@@ -4,7 +4,7 @@
 use serde::de::{
 	self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess,
 };
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 
 pub(crate) struct ByondDeserializer {
 	pub(crate) value: ByondValue,
@@ -26,6 +26,7 @@ fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 				"serializable value (null, str, num, list, string-ish)".into(),
 				"pointer".into(),
 			)),
+			ByondValueType::Alist => self.deserialize_map(visitor),
 			_ if self.value.is_list() => {
 				if self.value.is_likely_assoc()? {
 					self.deserialize_map(visitor)
@@ -237,8 +238,7 @@ fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 	where
 		V: de::Visitor<'de>,
 	{
-		let iter = self.value.iter()?;
-		let vec: VecDeque<_> = iter.map(|(key, _)| key).collect();
+		let vec: VecDeque<_> = self.value.values()?.collect();
 		visitor.visit_seq(ByondSeqAccess { iter: vec })
 	}
 
@@ -266,7 +266,7 @@ fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
 		V: de::Visitor<'de>,
 	{
 		let iter = self.value.iter()?;
-		let map: HashMap<_, _> = iter.collect();
+		let map: Vec<_> = iter.collect();
 		visitor.visit_map(ByondMapAccess {
 			iter: map.into_iter(),
 			current: None,
@@ -328,7 +328,7 @@ fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Er
 }
 
 struct ByondMapAccess {
-	iter: std::collections::hash_map::IntoIter<ByondValue, ByondValue>,
+	iter: std::vec::IntoIter<(ByondValue, ByondValue)>,
 	current: Option<(ByondValue, ByondValue)>,
 }
 
@@ -6,13 +6,23 @@
 
 use meowtonin::ByondValue;
 
-pub use crate::value::ByondSerde;
+pub use crate::{ser::SerializeOptions, value::ByondSerde};
 
 pub fn serialize<Value>(v: &Value) -> Result<ByondValue, error::SerializeError>
 where
 	Value: serde::Serialize,
 {
-	v.serialize(&mut ser::ByondSerializer)
+	v.serialize(&mut ser::ByondSerializer::default())
+}
+
+pub fn serialize_with_options<Value>(
+	v: &Value,
+	options: SerializeOptions,
+) -> Result<ByondValue, error::SerializeError>
+where
+	Value: serde::Serialize,
+{
+	v.serialize(&mut ser::ByondSerializer { options })
 }
 
 pub fn deserialize<'de, Value>(value: ByondValue) -> Result<Value, error::DeserializeError>
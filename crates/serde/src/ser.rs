@@ -7,7 +7,32 @@
 use meowtonin::{ByondValue, ToByond};
 use serde::ser::{Serialize, Serializer};
 
-pub(crate) struct ByondSerializer;
+/// Options controlling how Rust values are serialized into BYOND values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct SerializeOptions {
+	/// Serialize maps into an `alist` rather than a regular assoc list.
+	/// Structs are still serialized into regular lists.
+	///
+	/// Requires BYOND 516 or later.
+	pub maps_as_alist: bool,
+}
+
+impl SerializeOptions {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn maps_as_alist(mut self, maps_as_alist: bool) -> Self {
+		self.maps_as_alist = maps_as_alist;
+		self
+	}
+}
+
+#[derive(Default)]
+pub(crate) struct ByondSerializer {
+	pub options: SerializeOptions,
+}
 
 impl<'a> Serializer for &'a mut ByondSerializer {
 	type Ok = ByondValue;
@@ -159,7 +184,11 @@ fn serialize_tuple_variant(
 	}
 
 	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		map::ByondMapSerializer::new(self, None).map_err(SerializeError::from)
+		if self.options.maps_as_alist {
+			map::ByondMapSerializer::new_alist(self).map_err(SerializeError::from)
+		} else {
+			map::ByondMapSerializer::new(self, None).map_err(SerializeError::from)
+		}
 	}
 
 	fn serialize_struct(
@@ -180,3 +209,17 @@ fn serialize_struct_variant(
 		map::ByondMapSerializer::new(self, Some(variant)).map_err(SerializeError::from)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn options() {
+		assert_eq!(SerializeOptions::new(), SerializeOptions::default());
+		assert!(!ByondSerializer::default().options.maps_as_alist);
+		let options = SerializeOptions::new().maps_as_alist(true);
+		assert!(options.maps_as_alist);
+		assert!(!options.maps_as_alist(false).maps_as_alist);
+	}
+}
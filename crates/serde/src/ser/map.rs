@@ -23,6 +23,15 @@ pub fn new(
 			key: ByondValue::NULL,
 		})
 	}
+
+	pub fn new_alist(serializer: &'a mut ByondSerializer) -> ByondResult<Self> {
+		Ok(Self {
+			serializer,
+			map: ByondValue::new_alist()?,
+			variant: None,
+			key: ByondValue::NULL,
+		})
+	}
 }
 
 impl SerializeMap for ByondMapSerializer<'_> {
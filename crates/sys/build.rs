@@ -4,15 +4,39 @@
 mod inner {
 	use bindgen::{Abi, callbacks::ParseCallbacks};
 
+	/// Functions that older versions of BYOND don't export, which are loaded
+	/// separately so that a missing one doesn't fail initialization.
+	const OPTIONAL_FUNCTIONS: &str =
+		"Byond_Return|ByondValue_IsType|Byond_PixLoc|Byond_BoundPixLoc";
+
 	pub fn run() {
 		println!("cargo:rerun-if-changed=bindings/byondapi.h");
 		println!("cargo:rerun-if-changed=bindings/wrapper.hpp");
-		let bindings = bindgen::Builder::default()
-			.header("bindings/wrapper.hpp")
+		builder()
 			.dynamic_library_name("ByondApi")
 			.dynamic_link_require_all(true)
 			.allowlist_item("C?Byond.*")
 			.allowlist_item("[su][1-9].*")
+			.blocklist_function(OPTIONAL_FUNCTIONS)
+			.generate()
+			.expect("failed to generate byondapi bindings")
+			.write_to_file("src/bindings.rs")
+			.expect("Couldn't write bindings!");
+		builder()
+			.dynamic_library_name("ByondApiOptional")
+			.dynamic_link_require_all(false)
+			.allowlist_function(OPTIONAL_FUNCTIONS)
+			.allowlist_recursively(false)
+			.raw_line("use super::bindings::*;")
+			.generate()
+			.expect("failed to generate optional byondapi bindings")
+			.write_to_file("src/optional.rs")
+			.expect("Couldn't write optional bindings!");
+	}
+
+	fn builder() -> bindgen::Builder {
+		bindgen::Builder::default()
+			.header("bindings/wrapper.hpp")
 			.override_abi(Abi::CUnwind, "Byond.*")
 			.generate_block(true)
 			.derive_default(true)
@@ -21,11 +45,6 @@ pub fn run() {
 			.layout_tests(false)
 			.parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
 			.parse_callbacks(Box::new(DoxygenCallbacks))
-			.generate()
-			.expect("failed to generate byondapi bindings");
-		bindings
-			.write_to_file("src/bindings.rs")
-			.expect("Couldn't write bindings!");
 	}
 
 	#[derive(Debug)]
@@ -208,22 +208,12 @@ pub struct ByondApi {
 		buf: *mut ::std::os::raw::c_char,
 		buflen: *mut u4c,
 	) -> bool,
-	#[cfg(feature = "byond-1664")]
-	pub Byond_Return: unsafe extern "C-unwind" fn(
-		waiting_proc: *const CByondValue,
-		retval: *const CByondValue,
-	) -> bool,
 	pub Byond_Block: unsafe extern "C-unwind" fn(
 		corner1: *const CByondXYZ,
 		corner2: *const CByondXYZ,
 		list: *mut CByondValue,
 		len: *mut u4c,
 	) -> bool,
-	#[cfg(feature = "byond-1664")]
-	pub ByondValue_IsType: unsafe extern "C-unwind" fn(
-		src: *const CByondValue,
-		typestr: *const ::std::os::raw::c_char,
-	) -> bool,
 	pub Byond_Length:
 		unsafe extern "C-unwind" fn(src: *const CByondValue, result: *mut CByondValue) -> bool,
 	pub Byond_LocateIn: unsafe extern "C-unwind" fn(
@@ -248,13 +238,6 @@ pub struct ByondApi {
 		unsafe extern "C-unwind" fn(src: *const CByondValue, result: *mut u4c) -> bool,
 	pub Byond_XYZ:
 		unsafe extern "C-unwind" fn(src: *const CByondValue, xyz: *mut CByondXYZ) -> bool,
-	pub Byond_PixLoc:
-		unsafe extern "C-unwind" fn(src: *const CByondValue, pixloc: *mut CByondPixLoc) -> bool,
-	pub Byond_BoundPixLoc: unsafe extern "C-unwind" fn(
-		src: *const CByondValue,
-		dir: u1c,
-		pixloc: *mut CByondPixLoc,
-	) -> bool,
 	pub ByondValue_IncRef: unsafe extern "C-unwind" fn(src: *const CByondValue),
 	pub ByondValue_DecRef: unsafe extern "C-unwind" fn(src: *const CByondValue),
 	pub ByondValue_DecTempRef: unsafe extern "C-unwind" fn(src: *const CByondValue),
@@ -313,11 +296,7 @@ pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
 			.get(b"Byond_CallGlobalProcByStrId\0")
 			.map(|sym| *sym)?;
 		let Byond_ToString = __library.get(b"Byond_ToString\0").map(|sym| *sym)?;
-		#[cfg(feature = "byond-1664")]
-		let Byond_Return = __library.get(b"Byond_Return\0").map(|sym| *sym)?;
 		let Byond_Block = __library.get(b"Byond_Block\0").map(|sym| *sym)?;
-		#[cfg(feature = "byond-1664")]
-		let ByondValue_IsType = __library.get(b"ByondValue_IsType\0").map(|sym| *sym)?;
 		let Byond_Length = __library.get(b"Byond_Length\0").map(|sym| *sym)?;
 		let Byond_LocateIn = __library.get(b"Byond_LocateIn\0").map(|sym| *sym)?;
 		let Byond_LocateXYZ = __library.get(b"Byond_LocateXYZ\0").map(|sym| *sym)?;
@@ -325,8 +304,6 @@ pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
 		let Byond_NewArglist = __library.get(b"Byond_NewArglist\0").map(|sym| *sym)?;
 		let Byond_Refcount = __library.get(b"Byond_Refcount\0").map(|sym| *sym)?;
 		let Byond_XYZ = __library.get(b"Byond_XYZ\0").map(|sym| *sym)?;
-		let Byond_PixLoc = __library.get(b"Byond_PixLoc\0").map(|sym| *sym)?;
-		let Byond_BoundPixLoc = __library.get(b"Byond_BoundPixLoc\0").map(|sym| *sym)?;
 		let ByondValue_IncRef = __library.get(b"ByondValue_IncRef\0").map(|sym| *sym)?;
 		let ByondValue_DecRef = __library.get(b"ByondValue_DecRef\0").map(|sym| *sym)?;
 		let ByondValue_DecTempRef = __library.get(b"ByondValue_DecTempRef\0").map(|sym| *sym)?;
@@ -371,11 +348,7 @@ pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
 			Byond_CallGlobalProc,
 			Byond_CallGlobalProcByStrId,
 			Byond_ToString,
-			#[cfg(feature = "byond-1664")]
-			Byond_Return,
 			Byond_Block,
-			#[cfg(feature = "byond-1664")]
-			ByondValue_IsType,
 			Byond_Length,
 			Byond_LocateIn,
 			Byond_LocateXYZ,
@@ -383,8 +356,6 @@ pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
 			Byond_NewArglist,
 			Byond_Refcount,
 			Byond_XYZ,
-			Byond_PixLoc,
-			Byond_BoundPixLoc,
 			ByondValue_IncRef,
 			ByondValue_DecRef,
 			ByondValue_DecTempRef,
@@ -724,18 +695,6 @@ pub unsafe fn Byond_ToString(
 	) -> bool {
 		(self.Byond_ToString)(src, buf, buflen)
 	}
-	#[cfg(feature = "byond-1664")]
-	#[doc = "Returns from a byond,await: call\n Blocks if not on the main thread.\n # \
-	         Arguments\n\n* `waiting_proc` - The /callee waiting on a response\n * `retval` - The \
-	         return value to pass back to the waiting proc\n # Returns\n\nTrue on success; false \
-	         if the waiting proc doesn't exist"]
-	pub unsafe fn Byond_Return(
-		&self,
-		waiting_proc: *const CByondValue,
-		retval: *const CByondValue,
-	) -> bool {
-		(self.Byond_Return)(waiting_proc, retval)
-	}
 	#[doc = "Equivalent to calling block(x1,y1,z1, x2,y2,z2).\n Blocks if not on the main \
 	         thread.\n # Arguments\n\n* `corner1` - One corner of the block\n * `corner2` - \
 	         Another corner of the block\n * `list` - CByondValue array, allocated by caller (can \
@@ -752,17 +711,6 @@ pub unsafe fn Byond_Block(
 	) -> bool {
 		(self.Byond_Block)(corner1, corner2, list, len)
 	}
-	#[cfg(feature = "byond-1664")]
-	#[doc = "Equivalent to calling istype(src, text2path(typestr)).\n Blocks if not on the main \
-	         thread.\n # Arguments\n\n* `src` - The value\n * `typestr` - The type path to check\n \
-	         # Returns\n\nTrue if src is of the given type, false if not"]
-	pub unsafe fn ByondValue_IsType(
-		&self,
-		src: *const CByondValue,
-		typestr: *const ::std::os::raw::c_char,
-	) -> bool {
-		(self.ByondValue_IsType)(src, typestr)
-	}
 	#[doc = "Equivalent to calling length(src).\n Blocks if not on the main thread.\n # \
 	         Arguments\n\n* `src` - The value\n * `result` - Pointer to accept result as a \
 	         CByondValue (intended for future possible override of length)\n # Returns\n\nTrue on \
@@ -827,23 +775,6 @@ pub unsafe fn Byond_Refcount(&self, src: *const CByondValue, result: *mut u4c) -
 	pub unsafe fn Byond_XYZ(&self, src: *const CByondValue, xyz: *mut CByondXYZ) -> bool {
 		(self.Byond_XYZ)(src, xyz)
 	}
-	#[doc = "Get pixloc coords of an atom\n Blocks if not on the main thread.\n # Arguments\n\n* \
-	         `src` - The object to read\n * `pixloc` - Pointer to accept CByondPixLoc result\n # \
-	         Returns\n\nTrue on success"]
-	pub unsafe fn Byond_PixLoc(&self, src: *const CByondValue, pixloc: *mut CByondPixLoc) -> bool {
-		(self.Byond_PixLoc)(src, pixloc)
-	}
-	#[doc = "Get pixloc coords of an atom based on its bounding box\n Blocks if not on the main \
-	         thread.\n # Arguments\n\n* `src` - The object to read\n * `dir` - The direction\n * \
-	         `pixloc` - Pointer to accept CByondPixLoc result\n # Returns\n\nTrue on success"]
-	pub unsafe fn Byond_BoundPixLoc(
-		&self,
-		src: *const CByondValue,
-		dir: u1c,
-		pixloc: *mut CByondPixLoc,
-	) -> bool {
-		(self.Byond_BoundPixLoc)(src, dir, pixloc)
-	}
 	#[doc = "Increase the persistent reference count of an object used in Byondapi\n Reminder: \
 	         Calls only create temporary references when made on the main thread. On other \
 	         threads, the references are already persistent.\n Blocks if not on the main thread.\n \
@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: 0BSD
+use super::{ByondVersion, optional::ByondApiOptional};
+
+/// Optional parts of byondapi that may or may not be available, depending on
+/// the version of BYOND that loaded the library.
+///
+/// Symbols are resolved at runtime, so a library built against a newer
+/// version of BYOND can still be loaded by an older DreamDaemon, just without
+/// the newer functionality.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Capabilities {
+	/// `Byond_PixLoc` and `Byond_BoundPixLoc` are available.
+	pub pixloc: bool,
+	/// `ByondValue_IsType` is available (516.1664+).
+	pub is_type: bool,
+	/// `Byond_Return` is available, allowing `byond,await:` calls to be
+	/// returned from (516.1664+).
+	pub await_return: bool,
+	/// The `alist` type exists (516+).
+	pub alist: bool,
+	/// The `vector` type exists (516+).
+	pub vector: bool,
+}
+
+impl Capabilities {
+	pub(crate) fn detect(api: &ByondApiOptional, version: ByondVersion) -> Self {
+		Self {
+			pixloc: api.Byond_PixLoc.is_ok() && api.Byond_BoundPixLoc.is_ok(),
+			is_type: api.ByondValue_IsType.is_ok(),
+			await_return: api.Byond_Return.is_ok(),
+			alist: version.version >= 516,
+			vector: version.version >= 516,
+		}
+	}
+}
@@ -9,6 +9,10 @@
 #[allow(warnings, clippy::all)]
 #[rustfmt::skip]
 pub mod bindings;
+#[allow(warnings, clippy::all)]
+#[rustfmt::skip]
+pub mod optional;
+mod capabilities;
 mod version;
 
 #[cfg(doc)]
@@ -19,7 +23,9 @@
 #[cfg_attr(target_pointer_width = "64", repr(align(128)))]
 pub struct ByondApi {
 	internal: bindings::ByondApi,
+	optional: optional::ByondApiOptional,
 	version: ByondVersion,
+	capabilities: Capabilities,
 }
 
 unsafe impl Sync for ByondApi {}
@@ -27,14 +33,28 @@ unsafe impl Send for ByondApi {}
 
 impl ByondApi {
 	/// Initialize [ByondApi], using the given library.
-	pub unsafe fn init_from_library<Lib>(library: Lib) -> Result<ByondApi, libloading::Error>
+	///
+	/// The library is opened twice: once for the functions every supported
+	/// version of BYOND has, and once for the ones that only newer versions
+	/// have, which are allowed to be missing.
+	pub unsafe fn init_from_library<Lib>(
+		library: Lib,
+		optional: Lib,
+	) -> Result<ByondApi, libloading::Error>
 	where
 		Lib: Into<libloading::Library>,
 	{
 		let lib = library.into();
 		let version = unsafe { version::get_byond_version(&lib) };
 		let internal = unsafe { bindings::ByondApi::from_library(lib) }?;
-		Ok(ByondApi { internal, version })
+		let optional = unsafe { optional::ByondApiOptional::from_library(optional) }?;
+		let capabilities = Capabilities::detect(&optional, version);
+		Ok(ByondApi {
+			internal,
+			optional,
+			version,
+			capabilities,
+		})
 	}
 
 	/// Get the version of the ByondApi library.
@@ -42,6 +62,20 @@ pub unsafe fn init_from_library<Lib>(library: Lib) -> Result<ByondApi, libloadin
 	pub fn get_version(&self) -> ByondVersion {
 		self.version
 	}
+
+	/// Get the optional functionality supported by the loaded ByondApi
+	/// library.
+	#[must_use]
+	pub fn capabilities(&self) -> Capabilities {
+		self.capabilities
+	}
+
+	/// Get the functions that may not be available on older versions of
+	/// BYOND. Each is an `Err` if the running version doesn't have it.
+	#[must_use]
+	pub fn optional(&self) -> &optional::ByondApiOptional {
+		&self.optional
+	}
 }
 
 impl std::ops::Deref for ByondApi {
@@ -52,7 +86,7 @@ fn deref(&self) -> &Self::Target {
 	}
 }
 
-pub use crate::version::ByondVersion;
+pub use crate::{capabilities::Capabilities, version::ByondVersion};
 // Stabilized types
 pub use crate::bindings::{
 	ByondValueData, ByondValueType, CByondPixLoc, CByondValue, CByondXYZ, s1c, s2c, s4c, s8c, u1c,
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: 0BSD
+/* automatically generated by rust-bindgen 0.72.0 */
+
+use super::bindings::*;
+
+pub struct ByondApiOptional {
+	__library: ::libloading::Library,
+	pub Byond_Return: Result<
+		unsafe extern "C-unwind" fn(
+			waiting_proc: *const CByondValue,
+			retval: *const CByondValue,
+		) -> bool,
+		::libloading::Error,
+	>,
+	pub ByondValue_IsType: Result<
+		unsafe extern "C-unwind" fn(
+			src: *const CByondValue,
+			typestr: *const ::std::os::raw::c_char,
+		) -> bool,
+		::libloading::Error,
+	>,
+	pub Byond_PixLoc: Result<
+		unsafe extern "C-unwind" fn(src: *const CByondValue, pixloc: *mut CByondPixLoc) -> bool,
+		::libloading::Error,
+	>,
+	pub Byond_BoundPixLoc: Result<
+		unsafe extern "C-unwind" fn(
+			src: *const CByondValue,
+			dir: u1c,
+			pixloc: *mut CByondPixLoc,
+		) -> bool,
+		::libloading::Error,
+	>,
+}
+impl ByondApiOptional {
+	pub unsafe fn new<P>(path: P) -> Result<Self, ::libloading::Error>
+	where
+		P: AsRef<::std::ffi::OsStr>,
+	{
+		let library = ::libloading::Library::new(path)?;
+		Self::from_library(library)
+	}
+	pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
+	where
+		L: Into<::libloading::Library>,
+	{
+		let __library = library.into();
+		let Byond_Return = __library.get(b"Byond_Return\0").map(|sym| *sym);
+		let ByondValue_IsType = __library.get(b"ByondValue_IsType\0").map(|sym| *sym);
+		let Byond_PixLoc = __library.get(b"Byond_PixLoc\0").map(|sym| *sym);
+		let Byond_BoundPixLoc = __library.get(b"Byond_BoundPixLoc\0").map(|sym| *sym);
+		Ok(ByondApiOptional {
+			__library,
+			Byond_Return,
+			ByondValue_IsType,
+			Byond_PixLoc,
+			Byond_BoundPixLoc,
+		})
+	}
+	#[doc = "Returns from a byond,await: call\n Blocks if not on the main thread.\n # \
+	         Arguments\n\n* `waiting_proc` - The /callee waiting on a response\n * `retval` - The \
+	         return value to pass back to the waiting proc\n # Returns\n\nTrue on success; false \
+	         if the waiting proc doesn't exist"]
+	pub unsafe fn Byond_Return(
+		&self,
+		waiting_proc: *const CByondValue,
+		retval: *const CByondValue,
+	) -> bool {
+		(self
+			.Byond_Return
+			.as_ref()
+			.expect("Expected function, got error."))(waiting_proc, retval)
+	}
+	#[doc = "Equivalent to calling istype(src, text2path(typestr)).\n Blocks if not on the main \
+	         thread.\n # Arguments\n\n* `src` - The value\n * `typestr` - The type path to check\n \
+	         # Returns\n\nTrue if src is of the given type, false if not"]
+	pub unsafe fn ByondValue_IsType(
+		&self,
+		src: *const CByondValue,
+		typestr: *const ::std::os::raw::c_char,
+	) -> bool {
+		(self
+			.ByondValue_IsType
+			.as_ref()
+			.expect("Expected function, got error."))(src, typestr)
+	}
+	#[doc = "Get pixloc coords of an atom\n Blocks if not on the main thread.\n # Arguments\n\n* \
+	         `src` - The object to read\n * `pixloc` - Pointer to accept CByondPixLoc result\n # \
+	         Returns\n\nTrue on success"]
+	pub unsafe fn Byond_PixLoc(&self, src: *const CByondValue, pixloc: *mut CByondPixLoc) -> bool {
+		(self
+			.Byond_PixLoc
+			.as_ref()
+			.expect("Expected function, got error."))(src, pixloc)
+	}
+	#[doc = "Get pixloc coords of an atom based on its bounding box\n Blocks if not on the main \
+	         thread.\n # Arguments\n\n* `src` - The object to read\n * `dir` - The direction\n * \
+	         `pixloc` - Pointer to accept CByondPixLoc result\n # Returns\n\nTrue on success"]
+	pub unsafe fn Byond_BoundPixLoc(
+		&self,
+		src: *const CByondValue,
+		dir: u1c,
+		pixloc: *mut CByondPixLoc,
+	) -> bool {
+		(self
+			.Byond_BoundPixLoc
+			.as_ref()
+			.expect("Expected function, got error."))(src, dir, pixloc)
+	}
+}